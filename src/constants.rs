@@ -15,18 +15,30 @@ pub const MAX_PLAYERS: u8 = 6;
 /// Minimum number of players per game
 pub const MIN_PLAYERS: u8 = 2;
 
+// Blind positions (dealer+1/+2/+3) only make sense with at least two seats
+const _: () = assert!(MIN_PLAYERS >= 2 && MIN_PLAYERS <= MAX_PLAYERS);
+
 /// Number of cards in a deck
 pub const DECK_SIZE: usize = 52;
 
+/// Number of cards in a short deck (Six through Ace)
+pub const SHORT_DECK_SIZE: usize = 36;
+
 /// Number of hole cards per player
 pub const HOLE_CARDS_PER_PLAYER: u8 = 2;
 
 /// Maximum community cards
 pub const MAX_COMMUNITY_CARDS: u8 = 5;
 
+/// Cards dealt together on the flop
+pub const FLOP_CARDS: u8 = 3;
+
 /// Token decimal multiplier (10^9 for SPL tokens with 9 decimals)
 pub const TOKEN_DECIMAL_MULTIPLIER: u64 = 1_000_000_000;
 
+/// Largest chip_decimals whose chip unit (10^decimals) fits in a u64
+pub const MAX_CHIP_DECIMALS: u8 = 19;
+
 // PDA Seeds
 pub const GAME_CONFIG_SEED: &[u8] = b"game_config";
 pub const GAME_STATE_SEED: &[u8] = b"game_state";
@@ -36,6 +48,7 @@ pub const ACCUMULATOR_SEED: &[u8] = b"accumulator";
 pub const COMMUNITY_CARDS_SEED: &[u8] = b"community";
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const PLAYER_LIST_SEED: &[u8] = b"player_list";
+pub const PLAYER_STATS_SEED: &[u8] = b"stats";
 
 // secp256k1 curve constants (matching Solidity Utils.sol)
 /// Curve parameter a = 0
@@ -3,7 +3,7 @@
 //! This module provides EC operations for the Mental Poker card encryption/decryption
 //! protocol using Solana's native alt_bn128 syscalls via the solana-bn254 crate.
 
-use crate::constants::{BN254_N, BN254_N_MINUS_2};
+use crate::constants::{BN254_G1_X, BN254_G1_Y, BN254_N, BN254_N_MINUS_2};
 use crate::error::PokerError;
 use solana_bn254::prelude::{
     alt_bn128_g1_addition_be, alt_bn128_g1_multiplication_be,
@@ -217,6 +217,32 @@ pub fn mod_inverse_bn254(a: &[u8; 32]) -> Option<[u8; 32]> {
     Some(result)
 }
 
+/// Scalar multiplication in the BN254 scalar field: (a * b) mod n
+///
+/// Used to check that a combined inverse key is the product of the
+/// individual players' inverse keys.
+#[inline(never)]
+pub fn mul_mod_bn254(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    bigint_mul_mod(&bigint_mod(a, &BN254_N), &bigint_mod(b, &BN254_N), &BN254_N)
+}
+
+/// Reduce a 256-bit value into the BN254 scalar field: a mod n
+pub fn reduce_mod_bn254(a: &[u8; 32]) -> [u8; 32] {
+    bigint_mod(a, &BN254_N)
+}
+
+/// Scalar addition in the BN254 scalar field: (a + b) mod n
+///
+/// Keeps the shuffle accumulator in-field instead of wrapping at 2^256.
+pub fn add_mod_bn254(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (sum, overflow) = bigint_add(&bigint_mod(a, &BN254_N), &bigint_mod(b, &BN254_N));
+    if overflow || bigint_cmp(&sum, &BN254_N) >= 0 {
+        bigint_sub(&sum, &BN254_N)
+    } else {
+        sum
+    }
+}
+
 // =============================================================================
 // High-Level Operations for Mental Poker
 // =============================================================================
@@ -245,6 +271,38 @@ pub fn decrypt_point(key: &[u8; 32], point: &[u8; 64]) -> Result<[u8; 64], Poker
         .map_err(|_| PokerError::ECOperationFailed)
 }
 
+/// Remove one lock from a card point with a client-supplied inverse key
+///
+/// A valid card never decrypts to the point at infinity; getting the
+/// identity back means a zero key or a degenerate point, so it is rejected.
+#[inline(never)]
+pub fn apply_inverse_key(point: &[u8; 64], inv_key: &[u8; 32]) -> Result<[u8; 64], PokerError> {
+    let decrypted = bn254_mul(point, inv_key).map_err(|_| PokerError::ECOperationFailed)?;
+    if is_identity(&decrypted) {
+        return Err(PokerError::InvalidPoint);
+    }
+    Ok(decrypted)
+}
+
+/// Check a reveal key against the lock public key committed for the card
+///
+/// `lock_public_key` is `lock_key * G`, compressed. The inverse of the lock
+/// key takes it back to the generator, so a key that doesn't is rejected
+/// before it is applied to the card.
+#[inline(never)]
+pub fn verify_inverse_key(
+    lock_public_key: &[u8; COMPRESSED_G1_SIZE],
+    inv_key: &[u8; 32],
+) -> Result<(), PokerError> {
+    let lock_point =
+        bn254_g1_decompress(lock_public_key).map_err(|_| PokerError::InvalidDecryptProof)?;
+    let unlocked = bn254_mul(&lock_point, inv_key).map_err(|_| PokerError::InvalidDecryptProof)?;
+    if unlocked[..32] != BN254_G1_X || unlocked[32..] != BN254_G1_Y {
+        return Err(PokerError::InvalidDecryptProof);
+    }
+    Ok(())
+}
+
 /// Decrypt a point given as separate (x, y) coordinates.
 /// This is a convenience wrapper around `decrypt_point` for cases where
 /// coordinates are stored separately.
@@ -320,4 +378,48 @@ mod tests {
         one[31] = 1;
         assert_eq!(result, one);
     }
+
+    #[test]
+    fn test_verify_inverse_key() {
+        let mut generator = [0u8; 64];
+        generator[..32].copy_from_slice(&BN254_G1_X);
+        generator[32..].copy_from_slice(&BN254_G1_Y);
+        let mut lock_key = [0u8; 32];
+        lock_key[31] = 7;
+        let lock_public_key = bn254_g1_compress(&bn254_mul(&generator, &lock_key).unwrap()).unwrap();
+
+        let inv_key = mod_inverse_bn254(&lock_key).unwrap();
+        assert_eq!(verify_inverse_key(&lock_public_key, &inv_key), Ok(()));
+
+        // The inverse of some other key
+        let mut other = [0u8; 32];
+        other[31] = 8;
+        let wrong = mod_inverse_bn254(&other).unwrap();
+        assert_eq!(
+            verify_inverse_key(&lock_public_key, &wrong),
+            Err(PokerError::InvalidDecryptProof)
+        );
+        // Nothing committed for the card
+        assert_eq!(
+            verify_inverse_key(&[0u8; 32], &inv_key),
+            Err(PokerError::InvalidDecryptProof)
+        );
+    }
+
+    #[test]
+    fn test_apply_inverse_key_rejects_identity() {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+        assert!(apply_inverse_key(&generator, &two).is_ok());
+
+        // Zero key and the curve order both send the card to infinity
+        assert_eq!(apply_inverse_key(&generator, &[0u8; 32]), Err(PokerError::InvalidPoint));
+        assert_eq!(apply_inverse_key(&generator, &BN254_N), Err(PokerError::InvalidPoint));
+
+        // So does a card point that is already the identity
+        assert_eq!(apply_inverse_key(&[0u8; 64], &two), Err(PokerError::InvalidPoint));
+    }
 }
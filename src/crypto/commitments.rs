@@ -2,6 +2,8 @@
 //!
 //! Uses Keccak256 hash for commitment-reveal pattern
 
+use super::bn254::reduce_mod_bn254;
+
 extern "C" {
     fn sol_keccak256(vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
 }
@@ -25,6 +27,28 @@ pub fn keccak256(data: &[u8]) -> [u8; 32] {
     hash
 }
 
+/// Shuffle value a seed contributes to deck index `index`
+///
+/// v[i] = keccak256(seed || i) mod n. Off-chain clients computing the
+/// expected accumulator must derive exactly this value.
+pub fn derive_shuffle_value(seed: &[u8; 32], index: u8) -> [u8; 32] {
+    derive_shuffle_value_with(seed, index, keccak256)
+}
+
+/// `derive_shuffle_value` with the hash passed in
+///
+/// Lets the derivation run off-chain, where the keccak syscall is unavailable.
+pub fn derive_shuffle_value_with(
+    seed: &[u8; 32],
+    index: u8,
+    hash: impl Fn(&[u8]) -> [u8; 32],
+) -> [u8; 32] {
+    let mut preimage = [0u8; 33];
+    preimage[..32].copy_from_slice(seed);
+    preimage[32] = index;
+    reduce_mod_bn254(&hash(&preimage))
+}
+
 /// Compute commitment for a shuffle vector
 /// commitment = keccak256(abi.encode(vector))
 pub fn compute_commitment(vector: &[[u8; 32]; 52]) -> [u8; 32] {
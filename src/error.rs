@@ -81,6 +81,28 @@ pub enum PokerError {
     NotCommunityCard = 321,
     /// Invalid number of players
     InvalidNumPlayers = 322,
+    /// Deck size must be 36 (short-deck) or 52
+    InvalidDeckSize = 323,
+    /// Card has already been dealt to a player or the board
+    CardAlreadyDealt = 324,
+    /// Straddles are disabled for this game or the window has passed
+    StraddleNotAllowed = 325,
+    /// Chip decimals too large for a u64 chip unit
+    InvalidChipDecimals = 326,
+    /// Reveal key was already used for another card this hand
+    RevealKeyReused = 327,
+    /// Player already contributed a shuffle vector this hand
+    AlreadyGenerated = 328,
+    /// Reveal was signed for an earlier card than the one being revealed
+    StaleRevealRound = 330,
+    /// Reveal key is not the inverse of the player's committed lock key
+    InvalidDecryptProof = 331,
+    /// Lock public keys must be committed for every card before locking
+    LockKeysNotCommitted = 332,
+    /// A chunked generate is under way; finish it with GenerateChunk
+    GenerateInProgress = 333,
+    /// PlayerStats are only kept on tables initialized with stats enabled
+    StatsNotEnabled = 334,
 
     // Crypto errors (400-499)
     /// Invalid elliptic curve point
@@ -97,6 +119,8 @@ pub enum PokerError {
     DuplicateCards = 501,
     /// Illegal card (not from player's cards or community cards)
     IllegalCard = 502,
+    /// Player already submitted a hand this hand
+    HandAlreadySubmitted = 503,
 
     // Account errors (600-699)
     /// Invalid PDA
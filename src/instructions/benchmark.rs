@@ -0,0 +1,149 @@
+//! Benchmark instruction - measures compute units of the crypto primitives
+//!
+//! Only built with the `benchmark` feature; never part of production builds.
+//! Used to size the split instructions (e.g. `CARDS_PER_PART`).
+//!
+//! Data: op(1) + iterations(1)
+//!   op 0 = bn254_mul, 1 = bn254_g1_decompress, 2 = keccak256
+//!
+//! Logs: sol_log_64(op, iterations, total_cu, cu_per_iteration, 0)
+
+use pinocchio::{
+    account_info::AccountInfo, log::sol_log_64, msg, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    constants::{BN254_G1_X, BN254_G1_Y},
+    crypto::{
+        bn254::{bn254_g1_compress, bn254_g1_decompress, bn254_mul, G1_POINT_SIZE, SCALAR_SIZE},
+        commitments::keccak256,
+    },
+    error::PokerError,
+};
+
+/// Primitive to benchmark
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BenchmarkOp {
+    Bn254Mul = 0,
+    Bn254Decompress = 1,
+    Keccak256 = 2,
+}
+
+impl TryFrom<u8> for BenchmarkOp {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(BenchmarkOp::Bn254Mul),
+            1 => Ok(BenchmarkOp::Bn254Decompress),
+            2 => Ok(BenchmarkOp::Keccak256),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// Compute units consumed by a benchmark run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchmarkReport {
+    pub total: u64,
+    pub per_iteration: u64,
+}
+
+pub fn process_benchmark(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if data.len() < 2 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let op = BenchmarkOp::try_from(data[0])?;
+    let iterations = data[1];
+
+    // Fixed inputs: the generator point, its compressed form, and a small scalar
+    let mut point = [0u8; G1_POINT_SIZE];
+    point[..32].copy_from_slice(&BN254_G1_X);
+    point[32..].copy_from_slice(&BN254_G1_Y);
+    let compressed = bn254_g1_compress(&point).map_err(|_| PokerError::ECOperationFailed)?;
+    let mut scalar = [0u8; SCALAR_SIZE];
+    scalar[SCALAR_SIZE - 1] = 2;
+
+    let report = run_benchmark(iterations, remaining_compute_units, || match op {
+        BenchmarkOp::Bn254Mul => bn254_mul(&point, &scalar)
+            .map(|_| ())
+            .map_err(|_| PokerError::ECOperationFailed.into()),
+        BenchmarkOp::Bn254Decompress => bn254_g1_decompress(&compressed)
+            .map(|_| ())
+            .map_err(|_| PokerError::DecompressionFailed.into()),
+        BenchmarkOp::Keccak256 => {
+            keccak256(&point[..32]);
+            Ok(())
+        }
+    })?;
+
+    log_report(op, iterations, &report);
+    Ok(())
+}
+
+/// Run `op` `iterations` times and measure the compute units it consumed
+fn run_benchmark(
+    iterations: u8,
+    remaining: impl Fn() -> u64,
+    mut op: impl FnMut() -> ProgramResult,
+) -> Result<BenchmarkReport, ProgramError> {
+    let before = remaining();
+    for _ in 0..iterations {
+        op()?;
+    }
+    let total = before.saturating_sub(remaining());
+    let per_iteration = if iterations == 0 { 0 } else { total / iterations as u64 };
+    Ok(BenchmarkReport { total, per_iteration })
+}
+
+fn log_report(op: BenchmarkOp, iterations: u8, report: &BenchmarkReport) {
+    msg!("Benchmark");
+    sol_log_64(op as u64, iterations as u64, report.total, report.per_iteration, 0);
+}
+
+/// Remaining compute units in this transaction (0 off-chain)
+fn remaining_compute_units() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_remaining_compute_units()
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_benchmark_runs_and_reports() {
+        // Fake meter: every op burns 1500 CU
+        let meter = Cell::new(200_000u64);
+        let calls = Cell::new(0u8);
+        let report = run_benchmark(4, || meter.get(), || {
+            meter.set(meter.get() - 1500);
+            calls.set(calls.get() + 1);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(calls.get(), 4);
+        assert_eq!(report, BenchmarkReport { total: 6000, per_iteration: 1500 });
+        log_report(BenchmarkOp::Bn254Mul, 4, &report);
+    }
+
+    #[test]
+    fn test_benchmark_propagates_op_failure() {
+        let result = run_benchmark(3, || 0, || Err(PokerError::ECOperationFailed.into()));
+        assert_eq!(result, Err(PokerError::ECOperationFailed.into()));
+        assert_eq!(BenchmarkOp::try_from(3), Err(ProgramError::InvalidInstructionData));
+    }
+}
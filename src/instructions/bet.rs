@@ -5,10 +5,19 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{error::PokerError, state::*};
+use crate::{
+    constants::MAX_PLAYERS,
+    error::PokerError,
+    poker::min_raise,
+    state::*,
+    utils::{
+        active_seats, assert_can_act, assert_is_turn_at, assert_not_all_in, assert_not_finished,
+        first_in_hand_from, next_to_bet, read_table_betting,
+    },
+};
 
 pub fn process_bet(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
@@ -24,6 +33,9 @@ pub fn process_bet(
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // Remaining accounts (optional): every seat's PlayerState, so the round can
+    // close as soon as all but one player are all-in (required when bets are
+    // capped to the effective stack)
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -34,128 +46,650 @@ pub fn process_bet(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
+    // Zero-copy view: writes go straight to account data
     let mut game_state = unsafe {
-        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+        GameStateMut::from_bytes(game_state_acc.borrow_mut_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
     let mut player_state = unsafe {
-        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+        PlayerStateMut::from_bytes(player_state_acc.borrow_mut_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Validate state
-    if game_state.texas_state() != TexasHoldEmState::Betting {
-        return Err(PokerError::InvalidTexasState.into());
-    }
+    // Validate state (blinds are posted via place_blind, not bet/fold)
+    assert_not_finished(game_state.game_phase())?;
+    assert_can_act(game_state.texas_state(), game_state.betting_round_state())?;
 
     // Validate turn
-    let current_player = player_list.get_player(game_state.current_turn)
-        .ok_or(PokerError::NotAPlayer)?;
-    if current_player != player.key() {
-        return Err(PokerError::NotYourTurn.into());
+    let seat = assert_is_turn_at(game_state.current_turn(), &player_list, player.key())?;
+
+    // Chips only come out of the signer's own stack at this table
+    if player_state.player() != player.key() || player_state.game_id() != &game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+
+    let table = read_table_betting(
+        iter.as_slice(),
+        program_id,
+        &game_config.game_id,
+        &player_list,
+        game_config.max_players,
+    );
+    apply_bet(&mut game_state, &mut player_state, &game_config, &mut player_list, seat, amount, table)?;
+
+    let clock = Clock::get()?;
+    game_state.set_last_action_timestamp(clock.unix_timestamp);
+
+    unsafe {
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
-    // Validate not folded
+    Ok(())
+}
+
+/// Put `amount` chips in for `seat` and move the betting round on
+///
+/// `table` is every seat's betting status when all PlayerStates were passed.
+/// A bet that empties the stack marks the seat all-in, and a round that
+/// closes with at most one seat able to bet runs the board out.
+fn apply_bet(
+    game_state: &mut GameStateMut,
+    player_state: &mut PlayerStateMut,
+    game_config: &GameConfig,
+    player_list: &mut PlayerList,
+    seat: u8,
+    amount: u64,
+    mut table: Option<[SeatBetting; MAX_PLAYERS as usize]>,
+) -> ProgramResult {
+    // Validate not folded or already all-in
     if player_state.is_folded() {
         return Err(PokerError::AlreadyFolded.into());
     }
+    assert_not_all_in(player_state.chips())?;
 
-    // Validate amount
-    if player_state.chips < amount {
-        return Err(PokerError::InsufficientChips.into());
-    }
+    // Validate amount; a call the stack can't cover goes in all-in for less
+    let amount = call_all_in_amount(
+        amount,
+        player_state.chips(),
+        player_state.amount_to_call(game_state),
+    )?;
 
-    // Must call or raise (or all-in)
-    let new_bet = player_state.current_bet + amount;
-    if new_bet < game_state.current_call_amount && amount != player_state.chips {
-        return Err(PokerError::InvalidBetAmount.into());
-    }
+    // Optionally cap the bet at what the deepest opponent can still call;
+    // the capped amount is what goes in, so it is what gets validated
+    let seats = table.as_ref().map(|seats| &seats[..game_config.max_players as usize]);
+    let capped = capped_bet_amount(game_config, amount, player_state.current_bet(), seat, seats)?;
+
+    // Must check/call exactly or make a legal raise; only a whole-stack
+    // all-in, or a bet the cap cut down to put every opponent all-in, may
+    // fall short of either
+    let min_raise = min_raise(game_state.last_raise(), game_config.big_blind());
+    validate_bet_size(
+        capped,
+        player_state.amount_to_call(game_state),
+        min_raise,
+        amount == player_state.chips() || capped < amount,
+    )?;
+    let amount = capped;
+    let new_bet = player_state.current_bet() + amount;
 
     // Place chips
-    player_state.chips -= amount;
-    player_state.current_bet = new_bet;
-    game_state.pot += amount;
+    player_state.set_chips(player_state.chips() - amount);
+    player_state.set_current_bet(new_bet);
+    game_state.set_pot(game_state.pot() + amount);
 
     // Check if raise
-    if new_bet > game_state.current_call_amount {
-        game_state.current_call_amount = new_bet;
-        // Set last to call to previous player
-        let prev_index = if game_state.current_turn == 0 {
-            game_config.max_players - 1
-        } else {
-            game_state.current_turn - 1
-        };
+    let raised = new_bet > game_state.current_call_amount();
+    if raised {
+        // A short all-in raise leaves the minimum re-raise where it was
+        let raise = new_bet - game_state.current_call_amount();
+        if raise >= min_raise {
+            game_state.set_last_raise(raise);
+        }
+        game_state.set_current_call_amount(new_bet);
+        // Set last to call to the previous player still in the hand
+        let prev_index = previous_actor(seat, player_list, game_config.max_players);
         if let Some(prev_player) = player_list.get_player(prev_index) {
-            game_state.last_to_call = *prev_player;
+            game_state.set_last_to_call(prev_player);
         }
+        game_state.record_action(seat, BetActionType::Raise, amount);
         msg!("PlayerRaised");
+    } else if amount == 0 {
+        game_state.record_action(seat, BetActionType::Check, 0);
+        msg!("PlayerChecked");
+    } else if new_bet < game_state.current_call_amount() {
+        // Short all-in call: `current_bet` stays at what was matched, which
+        // caps the pots this seat can win (see `claim_pot::pay_side_pots`)
+        game_state.record_action(seat, BetActionType::AllIn, amount);
+        msg!("PlayerCalledAllIn");
     } else {
+        game_state.record_action(seat, BetActionType::Call, amount);
         msg!("PlayerCalled");
     }
 
-    let clock = Clock::get()?;
-    game_state.last_action_timestamp = clock.unix_timestamp;
+    // Check if betting round complete (a raise re-opens action for everyone
+    // else who can still bet); this seat counts as able until marked all-in
+    let active_players = player_list.count_can_bet();
+    game_state.note_player_acted(raised, active_players);
+    if player_state.chips() == 0 {
+        player_list.mark_all_in(seat);
+    }
+    if let Some(seats) = table.as_mut() {
+        seats[seat as usize] = SeatBetting {
+            is_folded: false,
+            chips: player_state.chips(),
+            current_bet: new_bet,
+        };
+    }
+    let seats = table.as_ref().map(|seats| &seats[..game_config.max_players as usize]);
+    if game_state.is_betting_round_complete(seats) {
+        if player_list.count_can_bet() <= 1 {
+            game_state.set_everybody_all_in();
+            msg!("AllIn: no further betting this hand");
+        }
+        finish_betting_round(game_state, game_config, player_list);
+    } else {
+        let next = next_to_bet(seat, player_list, game_config.max_players);
+        game_state.set_current_turn(next);
+    }
+
+    Ok(())
+}
 
-    // Check if betting round complete
-    if game_state.last_to_call == *player.key() || check_all_in(&game_state, &player_list, accounts) {
-        finish_betting_round(&mut game_state, &game_config);
+/// Chips actually put in for a requested `amount`
+///
+/// A call of the full amount from a stack that can't cover it is cut down to
+/// the whole stack (calling all-in for less). Any other request above the
+/// stack is rejected.
+fn call_all_in_amount(amount: u64, chips: u64, to_call: u64) -> Result<u64, PokerError> {
+    if amount <= chips {
+        Ok(amount)
+    } else if amount == to_call {
+        Ok(chips)
     } else {
-        // Next turn
-        game_state.current_turn = next_active_player(
-            game_state.current_turn,
-            game_config.max_players,
-            &player_list,
-            accounts,
-        );
+        Err(PokerError::InsufficientChips)
     }
+}
 
-    unsafe {
-        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
-            .copy_from_slice(&game_state.to_bytes());
-        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
-            .copy_from_slice(&player_state.to_bytes());
+/// Check that the chips put in are an exact call or a full raise
+///
+/// Anything strictly between `to_call` and `to_call + min_raise` is
+/// neither, unless the player is all-in.
+fn validate_bet_size(amount: u64, to_call: u64, min_raise: u64, all_in: bool) -> ProgramResult {
+    if all_in || amount == to_call || amount >= to_call.saturating_add(min_raise) {
+        return Ok(());
     }
+    Err(PokerError::InvalidBetAmount.into())
+}
 
-    Ok(())
+/// Apply the table's effective-stack cap to a bet, if enabled
+///
+/// The cap needs every seat's PlayerState, so a capped table rejects bets
+/// that don't pass them.
+fn capped_bet_amount(
+    game_config: &GameConfig,
+    amount: u64,
+    current_bet: u64,
+    seat: u8,
+    seats: Option<&[SeatBetting]>,
+) -> Result<u64, ProgramError> {
+    if game_config.cap_to_effective_stack == 0 {
+        return Ok(amount);
+    }
+    let seats = seats.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    Ok(cap_to_effective_stack(amount, current_bet, seat, seats))
 }
 
-fn check_all_in(_game_state: &GameState, _player_list: &PlayerList, _accounts: &[AccountInfo]) -> bool {
-    // Simplified - would need to check all players' chips
-    false
+/// Limit `amount` so the bet never exceeds the largest opponent stack
+///
+/// An opponent can match at most their chips plus what they already put in
+/// this round, so anything above the deepest such total could never be called.
+fn cap_to_effective_stack(amount: u64, current_bet: u64, seat: u8, seats: &[SeatBetting]) -> u64 {
+    let deepest_opponent = seats
+        .iter()
+        .enumerate()
+        .filter(|&(i, other)| i != seat as usize && !other.is_folded)
+        .map(|(_, other)| other.chips + other.current_bet)
+        .max()
+        .unwrap_or(0);
+    amount.min(deepest_opponent.saturating_sub(current_bet))
 }
 
-fn next_active_player(current: u8, max: u8, _player_list: &PlayerList, _accounts: &[AccountInfo]) -> u8 {
-    // Simplified - would need to skip folded players
-    (current + 1) % max
+/// Seat the action closes on after `seat` raises
+///
+/// The nearest seat before the raiser that can still bet.
+fn previous_actor(seat: u8, player_list: &PlayerList, max_players: u8) -> u8 {
+    active_seats(player_list, max_players, |s| !player_list.can_bet(s))
+        .previous_before(seat)
+        .unwrap_or(seat)
 }
 
-fn finish_betting_round(game_state: &mut GameState, game_config: &GameConfig) {
+fn finish_betting_round(
+    game_state: &mut GameStateMut,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) {
     match game_state.betting_round_state() {
         BettingRoundState::Blinds => {
-            game_state.texas_state = TexasHoldEmState::Drawing as u8;
+            game_state.set_texas_state(TexasHoldEmState::Drawing);
         }
         BettingRoundState::PreFlop => {
-            game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
-            game_state.community_cards_state = CommunityCardsState::FlopAwaiting as u8;
+            game_state.set_texas_state(TexasHoldEmState::CommunityCardsAwaiting);
+            game_state.set_community_cards_state(CommunityCardsState::FlopAwaiting);
         }
         BettingRoundState::PostFlop => {
-            game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
-            game_state.community_cards_state = CommunityCardsState::TurnAwaiting as u8;
+            game_state.set_texas_state(TexasHoldEmState::CommunityCardsAwaiting);
+            game_state.set_community_cards_state(CommunityCardsState::TurnAwaiting);
         }
         BettingRoundState::PostTurn => {
-            game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
-            game_state.community_cards_state = CommunityCardsState::RiverAwaiting as u8;
+            game_state.set_texas_state(TexasHoldEmState::CommunityCardsAwaiting);
+            game_state.set_community_cards_state(CommunityCardsState::RiverAwaiting);
         }
         BettingRoundState::Showdown => {
-            game_state.texas_state = TexasHoldEmState::Revealing as u8;
+            game_state.set_texas_state(TexasHoldEmState::Revealing);
         }
     }
-    game_state.current_turn = game_config.dealer_index;
+    let next = if game_state.texas_state() == TexasHoldEmState::Revealing {
+        first_in_hand_from(player_list, game_config.max_players, game_config.dealer_index)
+    } else {
+        game_config.dealer_seat(player_list)
+    };
+    game_state.set_current_turn(next);
     msg!("BettingRoundFinished");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat(chips: u64, current_bet: u64) -> SeatBetting {
+        SeatBetting { is_folded: false, chips, current_bet }
+    }
+
+    #[test]
+    fn test_validate_bet_size() {
+        // 100 to call after a raise of 60: put in exactly 100, or 160+ to raise
+        assert_eq!(validate_bet_size(100, 100, 60, false), Ok(()));
+        assert_eq!(validate_bet_size(160, 100, 60, false), Ok(()));
+        assert_eq!(validate_bet_size(400, 100, 60, false), Ok(()));
+        assert_eq!(
+            validate_bet_size(90, 100, 60, false),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+        // In-between amounts are neither a call nor a raise
+        assert_eq!(
+            validate_bet_size(101, 100, 60, false),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+        assert_eq!(
+            validate_bet_size(159, 100, 60, false),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+        // All-ins may land anywhere
+        assert_eq!(validate_bet_size(90, 100, 60, true), Ok(()));
+        assert_eq!(validate_bet_size(130, 100, 60, true), Ok(()));
+    }
+
+    #[test]
+    fn test_previous_actor_skips_folded_seat() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for i in 0..4u8 {
+            player_list.add_player([i + 1; 32]);
+        }
+
+        // Seat 2 raises: action closes on seat 1
+        assert_eq!(previous_actor(2, &player_list, 4), 1);
+
+        // Seat 1 folded, so it falls to seat 0; wrapping past a fold at seat 3
+        player_list.mark_folded(1);
+        assert_eq!(previous_actor(2, &player_list, 4), 0);
+        player_list.mark_folded(3);
+        assert_eq!(previous_actor(0, &player_list, 4), 2);
+
+        // Empty seats are skipped too
+        player_list.remove_player(1);
+        assert_eq!(previous_actor(2, &player_list, 4), 0);
+    }
+
+    #[test]
+    fn test_call_all_in_for_less() {
+        // Facing 500 with 300 behind: the call goes in for 300
+        assert_eq!(call_all_in_amount(500, 300, 500), Ok(300));
+        assert_eq!(call_all_in_amount(300, 300, 500), Ok(300));
+        // A raise the stack can't cover is still an error
+        assert_eq!(call_all_in_amount(800, 300, 500), Err(PokerError::InsufficientChips));
+        assert_eq!(call_all_in_amount(200, 300, 500), Ok(200));
+
+        // Seat 0 bet 500, seat 1 called all-in for 300, seat 2 called 500:
+        // seat 1 only contests the 900 it matched, not the 400 side pot
+        let seats = [(500, false), (300, false), (500, false)];
+        assert_eq!(crate::poker::pot_breakdown(&seats), vec![(900, 0b111), (400, 0b101)]);
+    }
+
+    #[test]
+    fn test_post_flop_check_then_min_bet() {
+        use crate::instructions::open_community_card::advance_after_open;
+        use crate::test_utils::{make_player_list, make_player_state};
+
+        // Three players each put 60 in pre-flop after a raise, then the flop is opened
+        let game_config = GameConfig::new(0, [7u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut player_list =
+            PlayerList::from_bytes(&make_player_list(&[[1u8; 32], [2u8; 32], [3u8; 32]])).unwrap();
+        let mut game_state = GameState::new(0, [7u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.pot = 180;
+        game_state.current_call_amount = 60;
+        game_state.last_raise = 40;
+        advance_after_open(&mut game_state, &game_config, &player_list, 3);
+        let mut game_data = game_state.to_bytes();
+        let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+
+        let mut players: Vec<_> = (0..3u8)
+            .map(|seat| {
+                let mut player_state = PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 940)).unwrap();
+                player_state.current_bet = 60;
+                player_state.to_bytes()
+            })
+            .collect();
+
+        // Seat 1 checks: not a raise, the big-blind minimum stands
+        let seat = game_state.current_turn();
+        assert_eq!(seat, 1);
+        let mut checker = PlayerStateMut::from_bytes(&mut players[1]).unwrap();
+        apply_bet(&mut game_state, &mut checker, &game_config, &mut player_list, seat, 0, None).unwrap();
+        assert_eq!(game_state.last_raise(), game_config.big_blind());
+        assert_eq!(game_state.players_to_act(), 2);
+        assert_eq!(game_state.current_turn(), 2);
+
+        // Seat 2 can't under-bet with chips behind, but a big blind is a legal bet
+        let mut bettor = PlayerStateMut::from_bytes(&mut players[2]).unwrap();
+        assert_eq!(
+            apply_bet(&mut game_state, &mut bettor, &game_config, &mut player_list, 2, 10, None),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+        apply_bet(&mut game_state, &mut bettor, &game_config, &mut player_list, 2, 20, None).unwrap();
+        assert_eq!(game_state.current_call_amount(), 80);
+        assert_eq!(game_state.last_raise(), 20);
+        assert_eq!(game_state.pot(), 200);
+        assert_eq!(game_state.players_to_act(), 2);
+    }
+
+    #[test]
+    fn test_short_all_in_raise_keeps_min_raise() {
+        use crate::instructions::open_community_card::advance_after_open;
+        use crate::test_utils::{make_player_list, make_player_state};
+
+        // Three players each put 60 in pre-flop, then the flop is opened
+        let game_config = GameConfig::new(0, [7u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut player_list =
+            PlayerList::from_bytes(&make_player_list(&[[1u8; 32], [2u8; 32], [3u8; 32]])).unwrap();
+        let mut game_state = GameState::new(0, [7u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.pot = 180;
+        game_state.current_call_amount = 60;
+        advance_after_open(&mut game_state, &game_config, &player_list, 3);
+        let mut game_data = game_state.to_bytes();
+        let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+
+        let mut players: Vec<_> = [(0u8, 940u64), (1, 940), (2, 60)]
+            .iter()
+            .map(|&(seat, chips)| {
+                let mut player_state = PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, chips)).unwrap();
+                player_state.current_bet = 60;
+                player_state.to_bytes()
+            })
+            .collect();
+
+        // Seat 1 bets 40, seat 2 raises all-in for 20 more: a short raise
+        let mut bettor = PlayerStateMut::from_bytes(&mut players[1]).unwrap();
+        apply_bet(&mut game_state, &mut bettor, &game_config, &mut player_list, 1, 40, None).unwrap();
+        assert_eq!(game_state.last_raise(), 40);
+        let mut shover = PlayerStateMut::from_bytes(&mut players[2]).unwrap();
+        apply_bet(&mut game_state, &mut shover, &game_config, &mut player_list, 2, 60, None).unwrap();
+        assert_eq!(game_state.current_call_amount(), 120);
+        assert_eq!(game_state.last_raise(), 40);
+
+        // Seat 0 faces 60: re-raising by the short 20 is not enough, by 40 is
+        assert_eq!(game_state.current_turn(), 0);
+        let mut raiser = PlayerStateMut::from_bytes(&mut players[0]).unwrap();
+        assert_eq!(
+            apply_bet(&mut game_state, &mut raiser, &game_config, &mut player_list, 0, 80, None),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+        apply_bet(&mut game_state, &mut raiser, &game_config, &mut player_list, 0, 100, None).unwrap();
+        assert_eq!(game_state.current_call_amount(), 160);
+        assert_eq!(game_state.last_raise(), 40);
+    }
+
+    #[test]
+    fn test_all_in_then_call_runs_out_the_board() {
+        use crate::instructions::open_community_card::advance_after_open;
+        use crate::test_utils::{make_player_state, seated_player_list};
+
+        // Heads-up, 60 each in pre-flop, flop opened: seat 1 acts first
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 2, 10, 100, 0);
+        let mut player_list = seated_player_list(2);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.pot = 120;
+        game_state.current_call_amount = 60;
+        advance_after_open(&mut game_state, &game_config, &player_list, 3);
+        assert_eq!(game_state.current_turn, 1);
+        assert_eq!(game_state.players_to_act, 2);
+        let mut game_data = game_state.to_bytes();
+        let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+
+        let mut players: Vec<_> = [(0u8, 1000u64), (1, 500)]
+            .iter()
+            .map(|&(seat, chips)| {
+                let mut player_state = PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, chips)).unwrap();
+                player_state.current_bet = 60;
+                player_state.to_bytes()
+            })
+            .collect();
+
+        // Seat 1 shoves, seat 0 calls
+        let mut shover = PlayerStateMut::from_bytes(&mut players[1]).unwrap();
+        apply_bet(&mut game_state, &mut shover, &game_config, &mut player_list, 1, 500, None).unwrap();
+        assert!(player_list.is_all_in(1));
+        assert_eq!(game_state.current_turn(), 0);
+        let mut caller = PlayerStateMut::from_bytes(&mut players[0]).unwrap();
+        apply_bet(&mut game_state, &mut caller, &game_config, &mut player_list, 0, 500, None).unwrap();
+
+        // Nobody is left to bet against: the turn and river are dealt straight away
+        let mut game_state = GameState::from_bytes(&game_data).unwrap();
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::TurnAwaiting);
+        assert!(game_state.is_everybody_all_in());
+        advance_after_open(&mut game_state, &game_config, &player_list, 4);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::RiverAwaiting);
+    }
+
+    #[test]
+    fn test_next_turn_skips_empty_and_folded_seats() {
+        use crate::test_utils::make_player_state;
+
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for i in 0..4u8 {
+            player_list.add_player([i + 1; 32]);
+        }
+        // Seat 2 left the table
+        player_list.remove_player(2);
+
+        let checked_by_seat_1 = |mut player_list: PlayerList| {
+            let mut game_state = GameState::new(0, [0u8; 32], 0);
+            game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+            game_state.start_betting_round(3);
+            game_state.current_turn = 1;
+            let mut game_data = game_state.to_bytes();
+            let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+            let mut player_data = make_player_state([2u8; 32], 1, 1000);
+            let mut player_state = PlayerStateMut::from_bytes(&mut player_data).unwrap();
+            apply_bet(&mut game_state, &mut player_state, &game_config, &mut player_list, 1, 0, None)
+                .unwrap();
+            game_state.current_turn()
+        };
+
+        assert_eq!(checked_by_seat_1(player_list), 3);
+
+        // Seat 3's fold is skipped too, even without the PlayerStates
+        player_list.mark_folded(3);
+        assert_eq!(checked_by_seat_1(player_list), 0);
+    }
+
+    #[test]
+    fn test_dead_button_hand_reaches_the_flop() {
+        use crate::instructions::{
+            deal_community::assert_dealer_turn,
+            place_blind::{advance_blinds, validate_blind_seat},
+            reveal::complete_reveal,
+            start_next_game::prepare_next_hand,
+        };
+        use crate::test_utils::{make_player_state, seated_player_list};
+        use crate::utils::finish_seat_step;
+
+        // Button 0, SB 1, BB 2 last hand; the small blind has since left
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        game_config.current_players = 3;
+        let mut player_list = seated_player_list(4);
+        player_list.remove_player(1);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        prepare_next_hand(&mut game_config, &mut game_state, &mut player_list, 0);
+
+        // BB moves to seat 3, so the button is dead on the empty seat 1
+        assert_eq!(game_config.dead_button, 1);
+        assert_eq!(game_config.dealer_seat(&player_list), 2);
+
+        // Once every seat has locked, the small blind on seat 2 posts first
+        let first_blind = game_config.first_blind_seat(&player_list);
+        while !finish_seat_step(&mut game_state, &player_list, 4, first_blind) {}
+        assert_eq!(game_state.current_turn, 2);
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, true), Ok(()));
+        advance_blinds(&mut game_state, &game_config, &player_list, true);
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 3, false), Ok(()));
+        advance_blinds(&mut game_state, &game_config, &player_list, false);
+        game_state.pot = 30;
+        game_state.current_call_amount = 20;
+
+        // Six hole cards drawn from UTG on seat 0 start pre-flop betting there
+        assert_eq!(game_state.current_turn, 0);
+        for _ in 0..6 {
+            game_state.cards_drawn += 1;
+            complete_reveal(&mut game_state, &game_config, &mut player_list);
+        }
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Betting);
+        assert_eq!(game_state.current_turn, 0);
+
+        let mut players: Vec<_> = [(0u8, 0u64), (2, 10), (3, 20)]
+            .iter()
+            .map(|&(seat, blind)| {
+                let mut player_state =
+                    PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000 - blind)).unwrap();
+                player_state.current_bet = blind;
+                player_state.to_bytes()
+            })
+            .collect();
+        let mut game_data = game_state.to_bytes();
+        let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+
+        // UTG calls, the small blind completes and the big blind checks
+        for (player, seat, amount) in [(0, 0, 20), (1, 2, 10), (2, 3, 0)] {
+            assert_eq!(game_state.current_turn(), seat);
+            let mut player_state = PlayerStateMut::from_bytes(&mut players[player]).unwrap();
+            apply_bet(&mut game_state, &mut player_state, &game_config, &mut player_list, seat, amount, None)
+                .unwrap();
+        }
+
+        // The flop is dealt by the first occupied seat after the dead button
+        let game_state = GameState::from_bytes(&game_data).unwrap();
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::FlopAwaiting);
+        assert_eq!(game_state.current_turn, 2);
+        assert!(assert_dealer_turn(&game_state, &game_config, &player_list).is_ok());
+    }
+
+    #[test]
+    fn test_capped_bet_is_validated_after_capping() {
+        use crate::test_utils::{make_player_state, seated_player_list};
+
+        // Capped heads-up table after the flop: nothing to call, seat 0 has 15 behind
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 2, 10, 100, 0);
+        game_config.cap_to_effective_stack = 1;
+        let mut player_list = seated_player_list(2);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.pot = 40;
+        game_state.current_turn = 1;
+        game_state.start_betting_round(2);
+        let mut game_data = game_state.to_bytes();
+        let mut game_state = GameStateMut::from_bytes(&mut game_data).unwrap();
+        let mut table = [SeatBetting::default(); MAX_PLAYERS as usize];
+        table[0] = seat(15, 0);
+        table[1] = seat(1000, 0);
+        let mut bettor_data = make_player_state([2; 32], 1, 1000);
+        let mut bettor = PlayerStateMut::from_bytes(&mut bettor_data).unwrap();
+
+        // Under the big blind and not cut by the cap: an illegal bet either way
+        assert_eq!(
+            apply_bet(&mut game_state, &mut bettor, &game_config, &mut player_list, 1, 12, Some(table)),
+            Err(PokerError::InvalidBetAmount.into())
+        );
+
+        // Cut to the 15 seat 0 can call, which puts it all-in: allowed short
+        apply_bet(&mut game_state, &mut bettor, &game_config, &mut player_list, 1, 50, Some(table)).unwrap();
+        assert_eq!(bettor.chips(), 985);
+        assert_eq!(game_state.pot(), 55);
+        assert_eq!(game_state.current_call_amount(), 15);
+    }
+
+    #[test]
+    fn test_min_raise_defaults_to_big_blind() {
+        assert_eq!(min_raise(0, 20), 20);
+        assert_eq!(min_raise(60, 20), 60);
+    }
+
+    #[test]
+    fn test_cap_to_effective_stack() {
+        // Seat 0 (1000 chips) bets into opponents with 300 and 250 + 50 in
+        let seats = [seat(1000, 0), seat(300, 0), seat(250, 50)];
+        assert_eq!(cap_to_effective_stack(1000, 0, 0, &seats), 300);
+        // Bets the opponents can cover are untouched
+        assert_eq!(cap_to_effective_stack(200, 0, 0, &seats), 200);
+        // Chips already in the pot this round count towards the cap
+        assert_eq!(cap_to_effective_stack(1000, 100, 0, &seats), 200);
+
+        // Folded opponents don't count
+        let mut with_folded = seats;
+        with_folded[1].is_folded = true;
+        assert_eq!(cap_to_effective_stack(1000, 0, 0, &with_folded), 300);
+        with_folded[2].is_folded = true;
+        assert_eq!(cap_to_effective_stack(1000, 0, 0, &with_folded), 0);
+    }
+
+    #[test]
+    fn test_capped_vs_uncapped_bet() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let seats = [seat(1000, 0), seat(300, 0), seat(250, 50)];
+
+        // Uncapped tables allow over-betting, with or without the table accounts
+        assert_eq!(capped_bet_amount(&game_config, 1000, 0, 0, Some(&seats)), Ok(1000));
+        assert_eq!(capped_bet_amount(&game_config, 1000, 0, 0, None), Ok(1000));
+
+        game_config.cap_to_effective_stack = 1;
+        assert_eq!(capped_bet_amount(&game_config, 1000, 0, 0, Some(&seats)), Ok(300));
+        assert_eq!(
+            capped_bet_amount(&game_config, 1000, 0, 0, None),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Cancel game instruction - abandon a table that never started
+//!
+//! A table that never fills up to `MIN_PLAYERS` would otherwise hold its
+//! players' deposits in the vault. The authority can cancel it while it is
+//! still waiting for players: every seated player gets their chips back,
+//! their seat is cleared and the game is marked `Finished`, ready for
+//! `CloseGame`.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    msg, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
+
+use crate::{
+    constants::{GAME_CONFIG_SEED, MAX_PLAYERS},
+    error::PokerError,
+    state::*,
+    utils::{derive_vault_pda, validate_owner},
+};
+
+pub fn process_cancel_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let mut iter = accounts.iter();
+    let authority = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let vault = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let _token_program = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // Remaining accounts: (player_state, player token account) for every
+    // seated player, in seat order
+
+    if !authority.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let mut game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if game_config.authority != *authority.key() {
+        return Err(PokerError::InvalidAuthority.into());
+    }
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if game_state.game_phase() != GamePhase::WaitingForPlayers {
+        return Err(PokerError::InvalidGamePhase.into());
+    }
+
+    let mut player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Refunds come out of this game's vault only
+    let (expected_vault, _) = derive_vault_pda(&game_config.game_id, program_id);
+    if vault.key() != &expected_vault {
+        return Err(PokerError::InvalidPDA.into());
+    }
+
+    let bump_slice = [game_config.bump];
+    for seat in 0..MAX_PLAYERS {
+        let Some(&seated) = player_list.get_player(seat) else {
+            continue;
+        };
+        let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let player_token_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        validate_owner(player_state_acc, program_id)?;
+
+        let mut player_state = unsafe {
+            PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+                .ok_or(PokerError::InvalidAccountData)?
+        };
+        if player_state.player != seated || player_state.game_id != game_config.game_id {
+            return Err(PokerError::InvalidAccountData.into());
+        }
+        // The refund may only go to a token account the player owns
+        let token_owner = unsafe { player_token_acc.borrow_data_unchecked() }.get(32..64);
+        if token_owner != Some(&seated[..]) {
+            return Err(PokerError::InvalidAccountData.into());
+        }
+
+        let refund = unseat_for_refund(&mut game_config, &mut player_list, &mut player_state, seat);
+        if refund > 0 {
+            let seeds: [Seed; 3] = [
+                Seed::from(GAME_CONFIG_SEED),
+                Seed::from(&game_config.game_id[..]),
+                Seed::from(bump_slice.as_slice()),
+            ];
+            let signer = Signer::from(&seeds);
+
+            Transfer {
+                from: vault,
+                to: player_token_acc,
+                authority: game_config_acc,
+                amount: refund,
+            }.invoke_signed(&[signer])?;
+            msg!("DepositRefunded");
+        }
+
+        unsafe {
+            player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+                .copy_from_slice(&player_state.to_bytes());
+        }
+    }
+
+    finish_cancelled(&mut game_state, &mut game_config);
+
+    unsafe {
+        game_config_acc.borrow_mut_data_unchecked()[..GAME_CONFIG_SIZE]
+            .copy_from_slice(&game_config.to_bytes());
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
+    }
+
+    msg!("GameCancelled");
+    Ok(())
+}
+
+/// Free a player's seat and return the chips owed back to them
+fn unseat_for_refund(
+    game_config: &mut GameConfig,
+    player_list: &mut PlayerList,
+    player_state: &mut PlayerState,
+    seat: u8,
+) -> u64 {
+    let refund = player_state.chips;
+    player_list.remove_player(seat);
+    game_config.current_players = game_config.current_players.saturating_sub(1);
+    player_state.clear();
+    refund
+}
+
+/// Close the table to new players and end the game
+fn finish_cancelled(game_state: &mut GameState, game_config: &mut GameConfig) {
+    game_state.game_phase = GamePhase::Finished as u8;
+    game_config.set_accepting_players(false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::join_game::take_seat;
+
+    #[test]
+    fn test_cancel_one_player_table_refunds_deposit() {
+        let authority = [9u8; 32];
+        let mut game_config = GameConfig::new(0, [1u8; 32], authority, [0u8; 32], 4, 10, 100, 0);
+        let mut game_state = GameState::new(0, [1u8; 32], 0);
+        let mut player_list = PlayerList::new(0, [1u8; 32]);
+        let seat = take_seat(&mut game_config, &mut player_list, &authority).unwrap();
+        let mut player_state = PlayerState::new(0, [1u8; 32], authority, seat, 500, [0u8; 32]);
+
+        let refund = unseat_for_refund(&mut game_config, &mut player_list, &mut player_state, seat);
+        finish_cancelled(&mut game_state, &mut game_config);
+
+        // The whole deposit goes back and nobody is left seated
+        assert_eq!(refund, 500);
+        assert_eq!(player_state.chips, 0);
+        assert_eq!(player_list.get_player(seat), None);
+        assert_eq!(game_config.current_players, 0);
+        assert!(!game_config.is_accepting_players());
+        assert_eq!(game_state.game_phase(), GamePhase::Finished);
+    }
+}
@@ -10,12 +10,24 @@ use pinocchio::{
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    error::PokerError,
+    poker::{describe_hand, distribute_chips, pot_breakdown, HandDescription, HandEnum},
+    state::*,
+    utils::{
+        derive_vault_pda, load_player_stats_for, players_in_hand, validate_owner,
+        validate_token_balance,
+    },
+};
 
 const MAX_PLAYERS_USIZE: usize = MAX_PLAYERS as usize;
 
+/// Winning hand type and its tiebreak ordering
+type WinningHand = (HandEnum, [i8; 5]);
+
 pub fn process_claim_pot(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
@@ -29,10 +41,7 @@ pub fn process_claim_pot(
     let _token_program = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
 
     // Collect all player state accounts
-    let mut player_states_accounts: [Option<&AccountInfo>; MAX_PLAYERS_USIZE] = [None; MAX_PLAYERS_USIZE];
-    for i in 0..MAX_PLAYERS_USIZE {
-        player_states_accounts[i] = iter.next();
-    }
+    let player_states_accounts = collect_player_states(&mut iter);
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -53,51 +62,120 @@ pub fn process_claim_pot(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Validate state
-    if game_state.texas_state() != TexasHoldEmState::ClaimPot {
-        return Err(PokerError::InvalidTexasState.into());
-    }
-
-    // Check if already claimed
-    if game_state.pot_claimed != 0 {
-        return Err(PokerError::PotAlreadyClaimed.into());
-    }
-
-    // Determine winner(s)
-    let (winners, _winning_hand) = determine_winners(
+    settle_pot(
+        program_id,
+        game_config_acc,
+        &game_config,
+        &mut game_state,
+        pot_account,
         &player_list,
         &player_states_accounts,
-        game_config.max_players,
-        &game_state,
+        &mut iter,
     )?;
 
-    if winners.is_empty() {
-        return Err(PokerError::NoWinner.into());
+    let clock = Clock::get()?;
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+    }
+
+    msg!("PotClaimed");
+    Ok(())
+}
+
+/// Take one PlayerState slot per seat from the remaining accounts
+pub(crate) fn collect_player_states<'a>(
+    iter: &mut impl Iterator<Item = &'a AccountInfo>,
+) -> [Option<&'a AccountInfo>; MAX_PLAYERS_USIZE] {
+    let mut player_states_accounts: [Option<&AccountInfo>; MAX_PLAYERS_USIZE] = [None; MAX_PLAYERS_USIZE];
+    for slot in player_states_accounts.iter_mut() {
+        *slot = iter.next();
     }
+    player_states_accounts
+}
+
+/// Pay the pot out of the vault and finish the hand
+///
+/// Shared by `ClaimPot` and by the last `SubmitBestHand` or `Muck` on an
+/// auto-pay table. `payee_token_accounts` yields one token account per paid
+/// seat, in seat order at showdown, then on a stats table every seated
+/// player's PlayerStats in seat order.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_pot<'a>(
+    program_id: &Pubkey,
+    game_config_acc: &AccountInfo,
+    game_config: &GameConfig,
+    game_state: &mut GameState,
+    pot_account: &AccountInfo,
+    player_list: &PlayerList,
+    player_states_accounts: &[Option<&AccountInfo>; MAX_PLAYERS_USIZE],
+    payee_token_accounts: &mut impl Iterator<Item = &'a AccountInfo>,
+) -> ProgramResult {
+    // The pot must be paid out of this game's vault, not an arbitrary token account
+    validate_vault(pot_account.key(), &game_config.game_id, program_id, derive_vault_pda)?;
+
+    assert_claimable(game_state)?;
+
+    // Determine winner(s)
+    let seat_states = load_seat_states(program_id, player_list, player_states_accounts, game_config)?;
+    let contenders = showdown_contenders(&seat_states);
+    let (winners, winning_hand) =
+        determine_winners(&contenders, player_list, game_config.deck_size, game_state);
 
     // Calculate pot distribution
     let total_pot = game_state.pot;
-    let num_winners = winners.len() as u64;
-    let share_per_winner = total_pot / num_winners;
-    let remainder = total_pot % num_winners;
+
+    // Vault must actually hold the pot before we start transferring
+    validate_token_balance(unsafe { pot_account.borrow_data_unchecked() }, total_pot)?;
+
+    let refunded = winners.is_empty();
+    let (winners, payouts) = if refunded {
+        // Every seat folded or emptied: hand the chips back to whoever put
+        // them in instead of stranding them in the vault
+        let contributions = seat_contributions(&seat_states);
+        let refunds = refund_pot(total_pot, &contributions).ok_or(PokerError::NoWinner)?;
+        msg!("NoWinner: PotRefunded");
+        (contributions.iter().map(|&(seat, _)| seat).collect(), refunds)
+    } else {
+        match winning_hand {
+            // Uncontested pots (everyone else folded) have no shown hand to
+            // describe and go to the last player in whole
+            None => {
+                let odd_chip_winner =
+                    select_odd_chip_winner(&winners, game_config.dealer_index, player_list);
+                let payouts =
+                    split_pot(total_pot, &winners, odd_chip_winner, game_config.chip_unit());
+                (winners, payouts)
+            }
+            Some((hand, ranked)) => {
+                let mut event = HandDescription::new();
+                event.push_str("WinningHand: ");
+                event.push_str(describe_hand(hand, &ranked).as_str());
+                msg!(event.as_str());
+
+                let contributions = seat_contributions(&seat_states);
+                pay_side_pots(total_pot, &contributions, &contenders, game_config, player_list)
+                    .into_iter()
+                    .unzip()
+            }
+        }
+    };
 
     // PDA signer components (reused in loop)
     let bump_slice = [game_config.bump];
 
     // Transfer to each winner
-    for (i, winner_idx) in winners.iter().enumerate() {
-        let _winner_pubkey = player_list.get_player(*winner_idx)
+    for (winner_idx, &amount) in winners.iter().zip(&payouts) {
+        let winner_pubkey = player_list.get_player(*winner_idx)
             .ok_or(PokerError::NotAPlayer)?;
 
         // Find winner's token account in remaining accounts
-        let winner_token_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
-
-        // Calculate this winner's share (first winner gets remainder)
-        let amount = if i == 0 {
-            share_per_winner + remainder
-        } else {
-            share_per_winner
-        };
+        let winner_token_acc = payee_token_accounts.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        // Whoever sends the settling transaction supplies these accounts, so
+        // winnings and refunds may only go to a token account the seat owns
+        validate_payee(unsafe { winner_token_acc.borrow_data_unchecked() }, winner_pubkey)?;
 
         if amount > 0 {
             // Build signer for this transfer (must be rebuilt each iteration)
@@ -120,93 +198,324 @@ pub fn process_claim_pot(
         }
     }
 
-    // Mark pot as claimed
+    // On a stats table every seat's PlayerStats follows, in seat order
+    if game_config.stats_enabled() {
+        for (seat, player_state) in seat_states.iter().enumerate() {
+            let Some(player_state) = player_state else {
+                continue;
+            };
+            let stats_acc = payee_token_accounts.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let mut stats = load_player_stats_for(
+                stats_acc,
+                program_id,
+                &game_config.game_id,
+                &player_state.player,
+            )?;
+            let (payout, won) = seat_result(seat as u8, &winners, &payouts, refunded);
+            stats.record_hand(player_state.current_bet, payout, won);
+            unsafe {
+                stats_acc.borrow_mut_data_unchecked()[..PLAYER_STATS_SIZE]
+                    .copy_from_slice(&stats.to_bytes());
+            }
+        }
+    }
+
+    mark_pot_claimed(game_state);
+    Ok(())
+}
+
+/// What a seat was paid and whether that counts as winning the hand
+///
+/// A refund of an uncontested, unwon pot is not a win.
+fn seat_result(seat: u8, winners: &[u8], payouts: &[u64], refunded: bool) -> (u64, bool) {
+    match winners.iter().position(|&paid| paid == seat) {
+        Some(i) => (payouts[i], !refunded),
+        None => (0, false),
+    }
+}
+
+/// Check that a payee token account is owned by the seat being paid
+///
+/// The owner sits at bytes 32..64 of an SPL token account.
+fn validate_payee(token_data: &[u8], payee: &Pubkey) -> Result<(), PokerError> {
+    if token_data.get(32..64) != Some(&payee[..]) {
+        return Err(PokerError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Check the hand is at showdown and its pot hasn't been paid yet
+pub(crate) fn assert_claimable(game_state: &GameState) -> Result<(), PokerError> {
+    if game_state.texas_state() != TexasHoldEmState::ClaimPot {
+        return Err(PokerError::InvalidTexasState);
+    }
+    // Check if already claimed
+    if game_state.pot_claimed != 0 {
+        return Err(PokerError::PotAlreadyClaimed);
+    }
+    Ok(())
+}
+
+/// Record the pot as paid and end the hand
+pub(crate) fn mark_pot_claimed(game_state: &mut GameState) {
     game_state.pot_claimed = 1;
     game_state.pot = 0;  // Note: pot is the serialized field, pot_size is an alias
 
-    let clock = Clock::get()?;
-    game_state.last_action_timestamp = clock.unix_timestamp;
-
     // Move to next game state
     game_state.texas_state = TexasHoldEmState::Finished as u8;
+}
 
-    unsafe {
-        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
-            .copy_from_slice(&game_state.to_bytes());
+/// Amount paid to each winner, in the order of `winners`
+///
+/// Shares are whole chips of `chip_unit` token units; whatever does not
+/// divide evenly goes to `odd_chip_winner`.
+fn split_pot(total_pot: u64, winners: &[u8], odd_chip_winner: u8, chip_unit: u64) -> Vec<u64> {
+    let (share, remainder) = distribute_chips(total_pot, winners.len(), chip_unit);
+    winners
+        .iter()
+        .map(|&seat| if seat == odd_chip_winner { share + remainder } else { share })
+        .collect()
+}
+
+/// Chips each seat put into the pot this hand, for seats that put any in
+///
+/// `current_bet` is never reset between streets, so it holds the seat's
+/// whole contribution. Folded seats count too.
+fn seat_contributions(seat_states: &[Option<PlayerState>; MAX_PLAYERS_USIZE]) -> Vec<(u8, u64)> {
+    seat_states
+        .iter()
+        .enumerate()
+        .filter_map(|(seat, state)| Some((seat as u8, state.as_ref()?.current_bet)))
+        .filter(|&(_, amount)| amount > 0)
+        .collect()
+}
+
+/// Refund `total_pot` in proportion to each seat's contribution
+///
+/// Payouts are in the order of `contributions`; rounding dust goes to the
+/// first contributor. `None` if nobody contributed.
+fn refund_pot(total_pot: u64, contributions: &[(u8, u64)]) -> Option<Vec<u64>> {
+    let total: u128 = contributions.iter().map(|&(_, amount)| amount as u128).sum();
+    if total == 0 {
+        return None;
     }
+    let mut refunds: Vec<u64> = contributions
+        .iter()
+        .map(|&(_, amount)| (total_pot as u128 * amount as u128 / total) as u64)
+        .collect();
+    refunds[0] += total_pot - refunds.iter().sum::<u64>();
+    Some(refunds)
+}
 
-    msg!("PotClaimed");
+/// Pick the winner who receives the odd chips of a split pot
+///
+/// The remainder goes to the first winner clockwise from the dealer. As a
+/// secondary rule, winners at the same distance (only possible with a
+/// duplicated seat) are ordered by lowest pubkey, so the result never depends
+/// on the order of `winners`.
+pub fn select_odd_chip_winner(winners: &[u8], dealer: u8, player_list: &PlayerList) -> u8 {
+    const NO_PLAYER: Pubkey = [0xFF; 32];
+    winners
+        .iter()
+        .copied()
+        .min_by_key(|&seat| {
+            let distance = (seat as i16 - dealer as i16 - 1).rem_euclid(MAX_PLAYERS as i16);
+            (distance, *player_list.get_player(seat).unwrap_or(&NO_PLAYER))
+        })
+        .unwrap_or(0)
+}
+
+/// Check that `pot_account` is the vault PDA derived from `VAULT_SEED` and the game ID
+///
+/// The derivation is passed in so tests can run without the PDA syscalls.
+fn validate_vault(
+    pot_account: &Pubkey,
+    game_id: &[u8; 32],
+    program_id: &Pubkey,
+    derive: impl Fn(&[u8; 32], &Pubkey) -> (Pubkey, u8),
+) -> ProgramResult {
+    let (vault, _) = derive(game_id, program_id);
+    if *pot_account != vault {
+        return Err(PokerError::InvalidPDA.into());
+    }
     Ok(())
 }
 
 fn determine_winners(
+    contenders: &[(u8, PlayerState)],
     player_list: &PlayerList,
-    player_states: &[Option<&AccountInfo>; MAX_PLAYERS_USIZE],
-    max_players: u8,
+    deck_size: u8,
     game_state: &GameState,
-) -> Result<(Vec<u8>, u8), ProgramError> {
-    let mut best_hand: u8 = 0;
-    let mut best_cards: [i8; 5] = [-1; 5];
-    let mut winners: Vec<u8> = Vec::new();
-
-    // Check if only one player remaining (others folded)
-    let players_remaining = max_players - game_state.num_folded_players;
+) -> (Vec<u8>, Option<WinningHand>) {
+    // Check if only one player remaining (others folded, e.g. a walk)
+    let players_remaining = players_in_hand(player_list, game_state.num_folded_players);
     if players_remaining == 1 {
-        // Find the non-folded player
-        for i in 0..max_players {
-            if let Some(state_acc) = player_states[i as usize] {
-                let player_state = unsafe {
-                    PlayerState::from_bytes(state_acc.borrow_data_unchecked())
-                        .ok_or(PokerError::InvalidAccountData)?
-                };
-                if !player_state.is_folded() {
-                    return Ok((vec![i], 0));
-                }
-            }
+        if let Some(&(winner, _)) = contenders.first() {
+            return (vec![winner], None);
         }
     }
 
     // Compare submitted hands
-    for i in 0..max_players {
-        if player_list.get_player(i).is_none() {
+    let (winners, winning_hand) = rank_submitted_hands(contenders.iter().copied(), deck_size);
+    (winners, Some(winning_hand))
+}
+
+/// Every occupied seat's PlayerState, read from the trailing accounts
+///
+/// Who is still in the hand, what each seat put in and which hand wins each
+/// side pot are all decided from these, so each occupied seat must supply a
+/// program-owned PlayerState of this game belonging to the seated player. A
+/// missing or substituted account refuses the settlement.
+fn load_seat_states(
+    program_id: &Pubkey,
+    player_list: &PlayerList,
+    player_states: &[Option<&AccountInfo>; MAX_PLAYERS_USIZE],
+    game_config: &GameConfig,
+) -> Result<[Option<PlayerState>; MAX_PLAYERS_USIZE], ProgramError> {
+    let mut seat_states = [None; MAX_PLAYERS_USIZE];
+    for seat in 0..game_config.max_players {
+        if !player_list.is_seat_occupied(seat) {
             continue;
         }
+        let state_acc = player_states[seat as usize].ok_or(ProgramError::NotEnoughAccountKeys)?;
+        validate_owner(state_acc, program_id)?;
+        let player_state = unsafe {
+            PlayerState::from_bytes(state_acc.borrow_data_unchecked())
+                .ok_or(PokerError::InvalidAccountData)?
+        };
+        seat_states[seat as usize] = Some(player_state);
+    }
+    check_seat_states(&seat_states, player_list, game_config)?;
+    Ok(seat_states)
+}
 
-        if let Some(state_acc) = player_states[i as usize] {
-            let player_state = unsafe {
-                PlayerState::from_bytes(state_acc.borrow_data_unchecked())
-                    .ok_or(PokerError::InvalidAccountData)?
-            };
+/// Check each occupied seat holds the seated player's PlayerState of this game
+fn check_seat_states(
+    seat_states: &[Option<PlayerState>; MAX_PLAYERS_USIZE],
+    player_list: &PlayerList,
+    game_config: &GameConfig,
+) -> Result<(), PokerError> {
+    for seat in 0..game_config.max_players {
+        let Some(seated) = player_list.get_player(seat) else {
+            continue;
+        };
+        match &seat_states[seat as usize] {
+            Some(state) if &state.player == seated && state.game_id == game_config.game_id => {}
+            _ => return Err(PokerError::InvalidAccountData),
+        }
+    }
+    Ok(())
+}
 
-            // Skip folded players
-            if player_state.is_folded() {
-                continue;
-            }
+/// Seats still in the hand at showdown, with their PlayerState
+fn showdown_contenders(
+    seat_states: &[Option<PlayerState>; MAX_PLAYERS_USIZE],
+) -> Vec<(u8, PlayerState)> {
+    seat_states
+        .iter()
+        .enumerate()
+        .filter_map(|(seat, state)| Some((seat as u8, (*state)?)))
+        .filter(|(_, state)| !state.is_folded())
+        .collect()
+}
+
+/// Pay a showdown pot out layer by layer
+///
+/// Each main or side pot from `pot_breakdown` goes to the best hand among
+/// the seats that matched it, so a short all-in only wins what it put in
+/// from each opponent. Chips the contributions don't account for go with
+/// the main pot. Returns `(seat, payout)` for every paid seat, in seat order.
+fn pay_side_pots(
+    total_pot: u64,
+    contributions: &[(u8, u64)],
+    contenders: &[(u8, PlayerState)],
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) -> Vec<(u8, u64)> {
+    let mut committed = [(0u64, true); MAX_PLAYERS_USIZE];
+    for &(seat, amount) in contributions {
+        committed[seat as usize].0 = amount;
+    }
+    for &(seat, _) in contenders {
+        committed[seat as usize].1 = false;
+    }
+    let mut layers = pot_breakdown(&committed[..game_config.max_players as usize]);
+    let layered: u64 = layers.iter().map(|&(amount, _)| amount).sum();
+    match layers.first_mut() {
+        Some(main) if layered <= total_pot => main.0 += total_pot - layered,
+        // Contributions that don't add up to the pot: one pot for everyone
+        _ => layers = vec![(total_pot, u8::MAX)],
+    }
+
+    let mut paid = [0u64; MAX_PLAYERS_USIZE];
+    for (amount, eligible) in layers {
+        let in_layer = contenders.iter().copied().filter(|&(seat, _)| eligible & (1 << seat) != 0);
+        let (mut winners, _) = rank_submitted_hands(in_layer, game_config.deck_size);
+        if winners.is_empty() {
+            // Dead money nobody still in matched goes to the best hand
+            winners = rank_submitted_hands(contenders.iter().copied(), game_config.deck_size).0;
+        }
+        if winners.is_empty() {
+            continue;
+        }
+        let odd_chip_winner = select_odd_chip_winner(&winners, game_config.dealer_index, player_list);
+        let shares = split_pot(amount, &winners, odd_chip_winner, game_config.chip_unit());
+        for (&seat, share) in winners.iter().zip(shares) {
+            paid[seat as usize] += share;
+        }
+    }
+
+    (0..game_config.max_players)
+        .filter(|&seat| paid[seat as usize] > 0)
+        .map(|seat| (seat, paid[seat as usize]))
+        .collect()
+}
 
-            let hand = player_state.submitted_hand;
-            let cards = player_state.hand_cards;
+/// Seats holding the best submitted hand, and that hand
+///
+/// Folded players keep the default `submitted_hand`, so they are dropped
+/// before any comparison rather than relying on it ranking last.
+fn rank_submitted_hands(
+    contenders: impl IntoIterator<Item = (u8, PlayerState)>,
+    deck_size: u8,
+) -> (Vec<u8>, WinningHand) {
+    // Strength is lower-is-better, so any submitted hand beats the start
+    let mut best_hand: u8 = u8::MAX;
+    let mut best_enum = HandEnum::HighCard;
+    let mut best_cards: [i8; 5] = [-1; 5];
+    let mut winners: Vec<u8> = Vec::new();
+
+    for (i, player_state) in contenders {
+        // Skip folded players
+        if player_state.is_folded() {
+            continue;
+        }
 
-            if hand > best_hand {
-                best_hand = hand;
+        let hand_enum = HandEnum::from(player_state.submitted_hand);
+        let hand = hand_enum.strength(deck_size);
+        let cards = player_state.hand_cards;
+
+        if hand < best_hand {
+            best_hand = hand;
+            best_enum = hand_enum;
+            best_cards = cards;
+            winners.clear();
+            winners.push(i);
+        } else if hand == best_hand {
+            // Compare card values for tiebreaker
+            let comparison = compare_hands(cards, best_cards);
+            if comparison > 0 {
                 best_cards = cards;
                 winners.clear();
                 winners.push(i);
-            } else if hand == best_hand {
-                // Compare card values for tiebreaker
-                let comparison = compare_hands(cards, best_cards);
-                if comparison > 0 {
-                    best_cards = cards;
-                    winners.clear();
-                    winners.push(i);
-                } else if comparison == 0 {
-                    // Tie - add to winners
-                    winners.push(i);
-                }
+            } else if comparison == 0 {
+                // Tie - add to winners
+                winners.push(i);
             }
         }
     }
 
-    Ok((winners, best_hand))
+    (winners, (best_enum, best_cards))
 }
 
 fn compare_hands(hand1: [i8; 5], hand2: [i8; 5]) -> i8 {
@@ -220,3 +529,448 @@ fn compare_hands(hand1: [i8; 5], hand2: [i8; 5]) -> i8 {
     }
     0 // Exact tie
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seated_player_list;
+
+    // Stand-in for the PDA derivation: vault address = game_id tagged with the program ID
+    fn fake_derive_vault(game_id: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+        let mut vault = *game_id;
+        vault[0] = program_id[0];
+        (vault, 255)
+    }
+
+    #[test]
+    fn test_odd_chip_two_way_tie() {
+        let player_list = seated_player_list(4);
+        // First winner left of the dealer takes the odd chip
+        assert_eq!(select_odd_chip_winner(&[1, 3], 0, &player_list), 1);
+        assert_eq!(select_odd_chip_winner(&[3, 1], 0, &player_list), 1);
+        // Wraps around the table past the last seat
+        assert_eq!(select_odd_chip_winner(&[1, 3], 2, &player_list), 3);
+        assert_eq!(select_odd_chip_winner(&[1, 2], 3, &player_list), 1);
+        // The dealer is last in line
+        assert_eq!(select_odd_chip_winner(&[0, 2], 0, &player_list), 2);
+    }
+
+    #[test]
+    fn test_odd_chip_three_way_tie() {
+        let player_list = seated_player_list(6);
+        assert_eq!(select_odd_chip_winner(&[0, 2, 4], 1, &player_list), 2);
+        assert_eq!(select_odd_chip_winner(&[4, 0, 2], 4, &player_list), 0);
+        assert_eq!(select_odd_chip_winner(&[5, 3, 1], 5, &player_list), 1);
+        // Any ordering of the winners picks the same seat
+        for winners in [[0, 2, 4], [2, 4, 0], [4, 2, 0]] {
+            assert_eq!(select_odd_chip_winner(&winners, 3, &player_list), 4);
+        }
+    }
+
+    #[test]
+    fn test_split_pot_in_whole_chips() {
+        // 3 decimals per chip: 10.5 chips between three winners
+        let mut config = GameConfig::new(0, [7u8; 32], [0u8; 32], [0u8; 32], 4, 1000, 10_000, 0);
+        config.chip_decimals = 3;
+        let chip_unit = config.chip_unit();
+
+        let odd_chip_winner = select_odd_chip_winner(&[0, 2, 3], 1, &seated_player_list(4));
+        assert_eq!(odd_chip_winner, 2);
+
+        let payouts = split_pot(10_500, &[0, 2, 3], odd_chip_winner, chip_unit);
+        assert_eq!(payouts, vec![3_000, 4_500, 3_000]);
+        assert_eq!(payouts.iter().sum::<u64>(), 10_500);
+
+        // Zero decimals keeps plain division by token units
+        assert_eq!(split_pot(7, &[1, 3], 3, 1), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_winning_pot_updates_stats() {
+        // Seats 0 and 2 put in 50 each, seat 2 takes the whole pot
+        let payouts = split_pot(100, &[2], 2, 1);
+        let mut winner = PlayerStats::new(0, [7u8; 32], [8u8; 32]);
+        let mut loser = PlayerStats::new(0, [7u8; 32], [10u8; 32]);
+
+        let (payout, won) = seat_result(2, &[2], &payouts, false);
+        winner.record_hand(50, payout, won);
+        let (payout, won) = seat_result(0, &[2], &payouts, false);
+        loser.record_hand(50, payout, won);
+
+        assert_eq!((winner.hands_played, winner.hands_won, winner.net_chips), (1, 1, 50));
+        assert_eq!((loser.hands_played, loser.hands_won, loser.net_chips), (1, 0, -50));
+
+        // A seat that folded is counted at settlement too, from what it put in
+        let mut folder = PlayerStats::new(0, [7u8; 32], [9u8; 32]);
+        let (payout, won) = seat_result(1, &[2], &payouts, false);
+        folder.record_hand(10, payout, won);
+        assert_eq!((folder.hands_played, folder.hands_won, folder.net_chips), (1, 0, -10));
+
+        // Getting chips back from a refunded pot is not a win
+        assert_eq!(seat_result(2, &[0, 2], &[60, 40], true), (40, false));
+    }
+
+    #[test]
+    fn test_walk_awards_blinds_to_big_blind() {
+        use crate::instructions::fold::apply_fold;
+        use crate::test_utils::{make_game_config, make_game_state, make_player_state};
+
+        // 6-handed, dealer seat 0: SB seat 1, BB seat 2, UTG seat 3
+        let game_config = GameConfig::from_bytes(&make_game_config(6, 10)).unwrap();
+        let blinds = game_config.small_blind + game_config.big_blind();
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 3, blinds)).unwrap();
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+        game_state.start_betting_round(6);
+
+        // Everyone folds around to the big blind
+        for seat in [3, 4, 5, 0, 1] {
+            assert_ne!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+            game_state.current_turn = seat;
+            apply_fold(&mut game_state, &game_config, &mut seated_player_list(6), seat, None);
+        }
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+        assert_eq!(game_state.num_folded_players, 5);
+
+        let mut seat_states = [None; MAX_PLAYERS_USIZE];
+        for seat in 0..6u8 {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000)).unwrap();
+            state.is_folded = (seat != game_config.bb_seat(&seated_player_list(6))) as u8;
+            seat_states[seat as usize] = Some(state);
+        }
+        let contenders = showdown_contenders(&seat_states);
+        assert_eq!(contenders.len(), 1);
+        let winner = contenders[0].0;
+        assert_eq!(winner, game_config.bb_seat(&seated_player_list(6)));
+
+        let odd_chip_winner =
+            select_odd_chip_winner(&[winner], game_config.dealer_index, &seated_player_list(6));
+        let payouts = split_pot(blinds, &[winner], odd_chip_winner, game_config.chip_unit());
+        assert_eq!(payouts, vec![30]);
+    }
+
+    #[test]
+    fn test_folded_player_leaving_heads_up_leaves_pot_to_opponent() {
+        use crate::instructions::{fold::apply_fold, leave::vacate_seat};
+        use crate::test_utils::{make_game_config, make_game_state, make_player_state};
+
+        // Heads-up pre-flop with the blinds in
+        let mut game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        game_config.current_players = 2;
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 1, 30)).unwrap();
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+        game_state.start_betting_round(2);
+        let mut player_list = seated_player_list(2);
+        let stay = PlayerState::from_bytes(&make_player_state([1; 32], 0, 990)).unwrap();
+        let mut leaver = PlayerState::from_bytes(&make_player_state([2; 32], 1, 980)).unwrap();
+
+        // Seat 1 folds, then leaves the table
+        apply_fold(&mut game_state, &game_config, &mut player_list, 1, None);
+        leaver.is_folded = 1;
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut leaver, 1);
+        assert_eq!(game_config.current_players, 1);
+
+        // The hand is still waiting on its pot, which seat 0 takes uncontested
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+        assert_eq!(assert_claimable(&game_state), Ok(()));
+        let mut seat_states = [None; MAX_PLAYERS_USIZE];
+        seat_states[0] = Some(stay);
+        assert_eq!(check_seat_states(&seat_states, &player_list, &game_config), Ok(()));
+        let contenders = showdown_contenders(&seat_states);
+        let (winners, winning_hand) =
+            determine_winners(&contenders, &player_list, game_config.deck_size, &game_state);
+        assert_eq!((winners.as_slice(), winning_hand), (&[0u8][..], None));
+        let odd_chip_winner = select_odd_chip_winner(&winners, game_config.dealer_index, &player_list);
+        assert_eq!(split_pot(game_state.pot, &winners, odd_chip_winner, game_config.chip_unit()), vec![30]);
+
+        mark_pot_claimed(&mut game_state);
+        assert_eq!(game_state.pot, 0);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Finished);
+    }
+
+    #[test]
+    fn test_player_still_in_leaving_heads_up_forfeits_pot() {
+        use crate::instructions::leave::{abort_short_handed, vacate_seat};
+        use crate::test_utils::{make_game_config, make_game_state, make_player_state};
+
+        // Heads-up on the flop, 40 in the pot and neither player folded
+        let mut game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        game_config.current_players = 2;
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 1, 40)).unwrap();
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        let mut player_list = seated_player_list(2);
+        let stay = PlayerState::from_bytes(&make_player_state([1; 32], 0, 980)).unwrap();
+        let mut leaver = PlayerState::from_bytes(&make_player_state([2; 32], 1, 980)).unwrap();
+        leaver.current_bet = 20;
+
+        // Seat 1 walks away mid-hand: one player can't go on, so the hand ends
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut leaver, 1);
+        assert_eq!(game_config.current_players, 1);
+        assert!(abort_short_handed(&game_config, &mut game_state));
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+
+        // Seat 0 takes the whole pot, the leaver's chips in it included
+        assert_eq!(assert_claimable(&game_state), Ok(()));
+        let mut seat_states = [None; MAX_PLAYERS_USIZE];
+        seat_states[0] = Some(stay);
+        assert_eq!(check_seat_states(&seat_states, &player_list, &game_config), Ok(()));
+        let contenders = showdown_contenders(&seat_states);
+        let (winners, winning_hand) =
+            determine_winners(&contenders, &player_list, game_config.deck_size, &game_state);
+        assert_eq!((winners.as_slice(), winning_hand), (&[0u8][..], None));
+        let odd_chip_winner = select_odd_chip_winner(&winners, game_config.dealer_index, &player_list);
+        assert_eq!(split_pot(game_state.pot, &winners, odd_chip_winner, game_config.chip_unit()), vec![40]);
+
+        // Nothing more to abort once the hand is at ClaimPot
+        assert!(!abort_short_handed(&game_config, &mut game_state));
+    }
+
+    #[test]
+    fn test_settling_requires_every_seated_player_state() {
+        use crate::test_utils::{make_game_config, make_player_state};
+
+        let game_config = GameConfig::from_bytes(&make_game_config(3, 10)).unwrap();
+        let player_list = seated_player_list(3);
+        let mut seat_states = [None; MAX_PLAYERS_USIZE];
+        for seat in 0..3u8 {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000)).unwrap();
+            state.is_folded = (seat != 1) as u8;
+            seat_states[seat as usize] = Some(state);
+        }
+        assert_eq!(check_seat_states(&seat_states, &player_list, &game_config), Ok(()));
+
+        // Leaving out the one seat still in can't turn the pot into a refund
+        seat_states[1] = None;
+        assert_eq!(
+            check_seat_states(&seat_states, &player_list, &game_config),
+            Err(PokerError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_folded_default_hand_never_wins() {
+        use crate::test_utils::make_player_state;
+
+        let hand = |seat: u8, submitted: HandEnum, cards: [i8; 5], folded: bool| {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000)).unwrap();
+            state.submitted_hand = submitted as u8;
+            state.hand_cards = cards;
+            state.is_folded = folded as u8;
+            (seat, state)
+        };
+
+        // Seat 0 folded during reveals and kept the default HighCard with
+        // ace-high kickers that would beat seat 2's weak high card
+        let folded_default = PlayerState::from_bytes(&make_player_state([1; 32], 0, 1000)).unwrap();
+        let contenders = [
+            hand(0, HandEnum::from(folded_default.submitted_hand), [12, 11, 10, 9, 7], true),
+            hand(1, HandEnum::HighCard, [9, 7, 5, 3, 1], false),
+            hand(2, HandEnum::HighCard, [10, 7, 5, 3, 1], false),
+        ];
+        let (winners, (best_enum, best_cards)) = rank_submitted_hands(contenders, DECK_SIZE as u8);
+        assert_eq!(winners, vec![2]);
+        assert_eq!(best_enum, HandEnum::HighCard);
+        assert_eq!(best_cards, [10, 7, 5, 3, 1]);
+
+        // Everyone else folded: no submitted hand is ever picked from a folded seat
+        let all_folded = [hand(0, HandEnum::HighCard, [12, 11, 10, 9, 7], true)];
+        assert!(rank_submitted_hands(all_folded, DECK_SIZE as u8).0.is_empty());
+    }
+
+    #[test]
+    fn test_short_all_in_wins_only_main_pot() {
+        use crate::test_utils::make_player_state;
+
+        let hand = |seat: u8, submitted: HandEnum, cards: [i8; 5]| {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 0)).unwrap();
+            state.submitted_hand = submitted as u8;
+            state.hand_cards = cards;
+            (seat, state)
+        };
+        let game_config = GameConfig::new(0, [7u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let player_list = seated_player_list(3);
+
+        // Seat 0 is all-in for 100 with the best hand; seats 1 and 2 put in 300
+        let contributions = [(0, 100), (1, 300), (2, 300)];
+        let contenders = [
+            hand(0, HandEnum::Flush, [8, 6, 4, 2, 0]),
+            hand(1, HandEnum::Pair, [12, 12, 11, 10, 9]),
+            hand(2, HandEnum::HighCard, [12, 11, 10, 9, 7]),
+        ];
+        let payouts = pay_side_pots(700, &contributions, &contenders, &game_config, &player_list);
+
+        // Seat 0 takes the 300 main pot it matched, seat 1 the 400 side pot
+        assert_eq!(payouts, vec![(0, 300), (1, 400)]);
+
+        // Without the short stack in the way, the best hand takes everything
+        let contributions = [(0, 300), (1, 300), (2, 300)];
+        let payouts = pay_side_pots(900, &contributions, &contenders, &game_config, &player_list);
+        assert_eq!(payouts, vec![(0, 900)]);
+    }
+
+    #[test]
+    fn test_side_pots_reject_substituted_player_state() {
+        use crate::test_utils::{make_game_config, make_player_state};
+
+        let game_config = GameConfig::from_bytes(&make_game_config(3, 10)).unwrap();
+        let player_list = seated_player_list(3);
+        let hand = |seat: u8, bet: u64, submitted: HandEnum, cards: [i8; 5]| {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 0)).unwrap();
+            state.current_bet = bet;
+            state.submitted_hand = submitted as u8;
+            state.hand_cards = cards;
+            Some(state)
+        };
+
+        // Seat 0 is all-in for 100 with the best hand; seats 1 and 2 put in 300
+        let mut seat_states = [None; MAX_PLAYERS_USIZE];
+        seat_states[0] = hand(0, 100, HandEnum::Flush, [8, 6, 4, 2, 0]);
+        seat_states[1] = hand(1, 300, HandEnum::Pair, [12, 12, 11, 10, 9]);
+        seat_states[2] = hand(2, 300, HandEnum::HighCard, [12, 11, 10, 9, 7]);
+        assert_eq!(check_seat_states(&seat_states, &player_list, &game_config), Ok(()));
+        let payouts = pay_side_pots(
+            700,
+            &seat_contributions(&seat_states),
+            &showdown_contenders(&seat_states),
+            &game_config,
+            &player_list,
+        );
+        assert_eq!(payouts, vec![(0, 300), (1, 400)]);
+
+        // Seat 2 passes seat 0's state from another game, with a bigger
+        // contribution, to take the main pot with the flush
+        let mut substituted = hand(0, 300, HandEnum::Flush, [8, 6, 4, 2, 0]).unwrap();
+        substituted.game_id = [0xEE; 32];
+        let mut forged = seat_states;
+        forged[0] = Some(substituted);
+        assert_eq!(
+            check_seat_states(&forged, &player_list, &game_config),
+            Err(PokerError::InvalidAccountData)
+        );
+
+        // Or seat 1's own state in place of seat 2's
+        let mut forged = seat_states;
+        forged[2] = seat_states[1];
+        assert_eq!(
+            check_seat_states(&forged, &player_list, &game_config),
+            Err(PokerError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_stronger_hand_category_wins() {
+        use crate::test_utils::make_player_state;
+
+        let hand = |seat: u8, submitted: HandEnum, cards: [i8; 5]| {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000)).unwrap();
+            state.submitted_hand = submitted as u8;
+            state.hand_cards = cards;
+            (seat, state)
+        };
+
+        // A pair of twos beats ace-high
+        let contenders = [
+            hand(0, HandEnum::HighCard, [12, 11, 10, 9, 7]),
+            hand(1, HandEnum::Pair, [0, 0, 5, 3, 1]),
+        ];
+        let (winners, (best_enum, _)) = rank_submitted_hands(contenders, DECK_SIZE as u8);
+        assert_eq!((winners, best_enum), (vec![1], HandEnum::Pair));
+
+        // A flush beats a pair of aces, in whichever seat order
+        let contenders = [
+            hand(0, HandEnum::Flush, [8, 6, 4, 2, 0]),
+            hand(1, HandEnum::Pair, [12, 12, 11, 10, 9]),
+            hand(2, HandEnum::HighCard, [12, 11, 10, 9, 7]),
+        ];
+        let (winners, (best_enum, best_cards)) = rank_submitted_hands(contenders, DECK_SIZE as u8);
+        assert_eq!((winners, best_enum, best_cards), (vec![0], HandEnum::Flush, [8, 6, 4, 2, 0]));
+    }
+
+    #[test]
+    fn test_no_winner_refunds_contributors() {
+        use crate::test_utils::make_player_state;
+
+        // Degenerate end of hand: every seat shows up folded
+        let folded = |seat: u8, bet: u64| {
+            let mut state =
+                PlayerState::from_bytes(&make_player_state([seat + 1; 32], seat, 1000)).unwrap();
+            state.is_folded = 1;
+            state.current_bet = bet;
+            (seat, state)
+        };
+        let contenders = [folded(0, 100), folded(1, 0), folded(2, 300)];
+        let (winners, _) = rank_submitted_hands(contenders, DECK_SIZE as u8);
+        assert!(winners.is_empty());
+
+        // The pot goes back in proportion to what each seat put in
+        let contributions: Vec<(u8, u64)> = contenders
+            .iter()
+            .filter(|(_, state)| state.current_bet > 0)
+            .map(|(seat, state)| (*seat, state.current_bet))
+            .collect();
+        assert_eq!(refund_pot(400, &contributions), Some(vec![100, 300]));
+        // Rounding dust goes to the first contributor
+        assert_eq!(refund_pot(401, &contributions), Some(vec![101, 300]));
+        assert_eq!(refund_pot(10, &[(0, 1), (1, 1), (2, 1)]), Some(vec![4, 3, 3]));
+
+        // Nothing to base a refund on
+        assert_eq!(refund_pot(400, &[]), None);
+    }
+
+    #[test]
+    fn test_payee_must_own_token_account() {
+        let winner = [3u8; 32];
+        let mut token_account = [0u8; TOKEN_ACCOUNT_SIZE];
+        token_account[32..64].copy_from_slice(&winner);
+        assert_eq!(validate_payee(&token_account, &winner), Ok(()));
+
+        // A settler can't redirect the winnings to their own account
+        token_account[32..64].copy_from_slice(&[4u8; 32]);
+        assert_eq!(validate_payee(&token_account, &winner), Err(PokerError::InvalidAccountData));
+
+        // Nor pass something too short to be a token account
+        assert_eq!(validate_payee(&[0u8; 40], &winner), Err(PokerError::InvalidAccountData));
+
+        // Refunds are held to the same rule: each contributor's account
+        let contributor = [5u8; 32];
+        token_account[32..64].copy_from_slice(&contributor);
+        assert_eq!(validate_payee(&token_account, &contributor), Ok(()));
+        assert_eq!(validate_payee(&token_account, &winner), Err(PokerError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_validate_vault_accepts_game_vault() {
+        let game_id = [7u8; 32];
+        let program_id = [9u8; 32];
+        let (vault, _) = fake_derive_vault(&game_id, &program_id);
+        assert!(validate_vault(&vault, &game_id, &program_id, fake_derive_vault).is_ok());
+    }
+
+    #[test]
+    fn test_validate_vault_rejects_spoofed_pot() {
+        let game_id = [7u8; 32];
+        let program_id = [9u8; 32];
+        let spoofed = [0xEEu8; 32];
+        assert_eq!(
+            validate_vault(&spoofed, &game_id, &program_id, fake_derive_vault),
+            Err(PokerError::InvalidPDA.into())
+        );
+
+        // Another game's vault is rejected too
+        let (other_vault, _) = fake_derive_vault(&[8u8; 32], &program_id);
+        assert_eq!(
+            validate_vault(&other_vault, &game_id, &program_id, fake_derive_vault),
+            Err(PokerError::InvalidPDA.into())
+        );
+    }
+}
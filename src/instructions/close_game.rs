@@ -1,13 +1,14 @@
 //! Close game instruction
 //!
 //! Closes all game PDA accounts and returns rent to the authority.
-//! Can only be called when the game is finished or by authority to abort.
+//! Can only be called between hands with an empty pot, once every player has
+//! left and taken their chips with them.
 
 use pinocchio::{account_info::AccountInfo, msg, program_error::ProgramError, ProgramResult};
 
 use crate::{
     error::PokerError,
-    state::{GameConfig, GameState, TexasHoldEmState},
+    state::{GameConfig, GamePhase, GameState},
 };
 
 /// Close a PDA account by transferring all lamports to the destination
@@ -46,9 +47,6 @@ pub fn process_close_game(
     let mut game_id = [0u8; 32];
     game_id.copy_from_slice(&data[0..32]);
 
-    // Optional force_close flag (only authority can use)
-    let force_close = data.len() > 32 && data[32] != 0;
-
     // Parse accounts
     let mut iter = accounts.iter();
     let authority = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -79,20 +77,14 @@ pub fn process_close_game(
         return Err(PokerError::InvalidGameId.into());
     }
 
-    // Check game state - must be finished or force_close by authority
-    if !force_close {
-        let state_data = unsafe { game_state_acc.borrow_data_unchecked() };
-        let game_state =
-            GameState::from_bytes(state_data).ok_or(ProgramError::InvalidAccountData)?;
-
-        // Game must be in Finished state or StartNext (if all players left)
-        let is_finished = game_state.texas_state == TexasHoldEmState::Finished as u8
-            || game_state.texas_state == TexasHoldEmState::StartNext as u8;
+    // Check game state - must be idle with nobody seated
+    let state_data = unsafe { game_state_acc.borrow_data_unchecked() };
+    let game_state =
+        GameState::from_bytes(state_data).ok_or(ProgramError::InvalidAccountData)?;
 
-        if !is_finished && game_config.current_players > 0 {
-            msg!("CloseGame: game not finished and players still active");
-            return Err(PokerError::GameNotFinished.into());
-        }
+    if let Err(err) = assert_closable(&game_state, &game_config) {
+        msg!("CloseGame: hand in progress, chips in the pot or players still seated");
+        return Err(err.into());
     }
 
     msg!("CloseGame: closing accounts");
@@ -108,3 +100,46 @@ pub fn process_close_game(
     msg!("Game closed, rent returned to authority");
     Ok(())
 }
+
+/// Only an idle, empty table may be closed
+///
+/// Closing mid-hand would wipe the pot and every seat's place in it, so the
+/// game must be between hands (or over), with nothing in the pot and nobody
+/// left seated: a seated player's stack is still in the vault.
+pub(crate) fn assert_closable(game_state: &GameState, game_config: &GameConfig) -> Result<(), PokerError> {
+    let idle = matches!(game_state.game_phase(), GamePhase::Finished | GamePhase::WaitingForPlayers);
+    if !idle || game_state.pot != 0 || game_config.current_players != 0 {
+        return Err(PokerError::InvalidGamePhase);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{BettingRoundState, TexasHoldEmState};
+
+    #[test]
+    fn test_close_rejected_during_betting() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 2, 10, 20, 1000);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+        game_state.pot = 30;
+        game_config.current_players = 2;
+        assert_eq!(assert_closable(&game_state, &game_config), Err(PokerError::InvalidGamePhase));
+
+        // Between hands, but chips are still in the pot or players still seated
+        game_state.game_phase = GamePhase::WaitingForPlayers as u8;
+        assert_eq!(assert_closable(&game_state, &game_config), Err(PokerError::InvalidGamePhase));
+        game_state.pot = 0;
+        assert_eq!(assert_closable(&game_state, &game_config), Err(PokerError::InvalidGamePhase));
+
+        // Everyone has left
+        game_config.current_players = 0;
+        assert_eq!(assert_closable(&game_state, &game_config), Ok(()));
+        game_state.game_phase = GamePhase::Finished as u8;
+        assert_eq!(assert_closable(&game_state, &game_config), Ok(()));
+    }
+}
@@ -0,0 +1,148 @@
+//! Commit lock keys instruction - publish the lock public key for each card
+//!
+//! Each card is locked with its own key `k`. Before the deck is dealt, every
+//! player stores `k * G` for each card in their PlayerState. A later reveal
+//! key must take that point back to the generator, so each player is held
+//! to one reveal key per card, fixed before any card is drawn.
+//!
+//! The commitment is not checked against the lock actually applied to the
+//! deck (that would take a pairing per card). A player who locks with other
+//! keys still garbles the card; the bad point is caught when the card is
+//! looked up in the deck mapping (`IllegalCard` at `OpenCommunityCard` or
+//! `SubmitBestHand`).
+//!
+//! Data: first_index(1) + compressed lock public key(32) per card. The deck
+//! doesn't fit in one transaction, so keys are sent in runs of up to
+//! `CARDS_PER_PART`.
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    constants::{CARDS_PER_PART, DECK_SIZE},
+    crypto::bn254::COMPRESSED_G1_SIZE,
+    error::PokerError,
+    state::*,
+    utils::assert_card_index,
+};
+
+pub fn process_commit_lock_keys(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (first, keys) = parse_lock_keys(data)?;
+
+    let mut iter = accounts.iter();
+    let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !player.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Keys are fixed before any card is dealt
+    if game_state.game_phase() != GamePhase::Shuffling {
+        return Err(PokerError::InvalidGamePhase.into());
+    }
+
+    let mut player_state = unsafe {
+        PlayerStateMut::from_bytes(player_state_acc.borrow_mut_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if player_state.player() != player.key() || player_state.game_id() != &game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+
+    // Locking fixes the keys for the rest of the hand
+    if player_state.is_lock_committed() {
+        return Err(PokerError::Part1AlreadySubmitted.into());
+    }
+
+    player_state.set_lock_public_keys(first as usize, keys)?;
+
+    msg!("LockKeysCommitted");
+    Ok(())
+}
+
+/// Split instruction data into the first card index and its run of keys
+fn parse_lock_keys(data: &[u8]) -> Result<(u8, &[[u8; COMPRESSED_G1_SIZE]]), ProgramError> {
+    let (&first, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    let (keys, remainder) = rest.as_chunks::<COMPRESSED_G1_SIZE>();
+    if !remainder.is_empty() || keys.is_empty() || keys.len() > CARDS_PER_PART {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    assert_card_index(first)?;
+    if first as usize + keys.len() > DECK_SIZE {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    Ok((first, keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::bn254::{bn254_g1_compress, bn254_mul, mod_inverse_bn254, verify_inverse_key};
+    use crate::state::player_state::PLAYER_STATE_ACCOUNT_SIZE;
+    use crate::test_utils::make_player_state;
+
+    fn scalar(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    fn lock_public_key(lock_key: &[u8; 32]) -> [u8; 32] {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        bn254_g1_compress(&bn254_mul(&generator, lock_key).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_committed_keys_check_reveal_keys() {
+        let keys = [lock_public_key(&scalar(3)), lock_public_key(&scalar(7))];
+        let mut data = vec![50u8];
+        data.extend_from_slice(keys.as_flattened());
+        let (first, parsed) = parse_lock_keys(&data).unwrap();
+        assert_eq!((first, parsed), (50, &keys[..]));
+
+        let mut account = make_player_state([1u8; 32], 0, 100).to_vec();
+        account.resize(PLAYER_STATE_ACCOUNT_SIZE, 0);
+        let mut player_state = PlayerStateMut::from_bytes(&mut account).unwrap();
+        player_state.set_lock_public_keys(first as usize, parsed).unwrap();
+
+        // The inverse of card 51's lock key passes, card 50's doesn't
+        let committed = player_state.lock_public_key(51).unwrap();
+        assert_eq!(verify_inverse_key(committed, &mod_inverse_bn254(&scalar(7)).unwrap()), Ok(()));
+        assert_eq!(
+            verify_inverse_key(committed, &mod_inverse_bn254(&scalar(3)).unwrap()),
+            Err(PokerError::InvalidDecryptProof)
+        );
+        assert_eq!(player_state.lock_public_key(DECK_SIZE), None);
+    }
+
+    #[test]
+    fn test_rejects_keys_past_the_deck() {
+        let mut data = vec![51u8];
+        data.extend_from_slice(&[0u8; 64]);
+        assert_eq!(parse_lock_keys(&data), Err(PokerError::InvalidCardIndex.into()));
+
+        assert_eq!(parse_lock_keys(&data[..1]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_lock_keys(&data[..40]), Err(ProgramError::InvalidInstructionData));
+    }
+}
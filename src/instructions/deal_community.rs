@@ -5,12 +5,35 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{error::PokerError, state::*};
+use crate::{
+    constants::{DECK_SIZE, FLOP_CARDS},
+    error::PokerError,
+    state::*,
+    utils::{assert_is_turn, assert_not_finished},
+};
 
 pub fn process_deal_community(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    deal_community(program_id, accounts, data, 1)
+}
+
+/// Deal all three flop cards at once so they share one reveal/open round
+pub fn process_deal_flop(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    deal_community(program_id, accounts, data, FLOP_CARDS)
+}
+
+fn deal_community(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     _data: &[u8],
+    count: u8,
 ) -> ProgramResult {
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -24,7 +47,7 @@ pub fn process_deal_community(
         return Err(PokerError::InvalidSigner.into());
     }
 
-    let _game_config = unsafe {
+    let game_config = unsafe {
         GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -51,56 +74,33 @@ pub fn process_deal_community(
     };
 
     // Validate state
+    assert_not_finished(game_state.game_phase())?;
     if game_state.texas_state() != TexasHoldEmState::CommunityCardsAwaiting {
         return Err(PokerError::InvalidTexasState.into());
     }
 
     // Validate turn (only dealer can deal)
-    let current_player = player_list.get_player(game_state.current_turn)
-        .ok_or(PokerError::NotAPlayer)?;
-    if current_player != player.key() {
-        return Err(PokerError::NotYourTurn.into());
-    }
+    assert_dealer_turn(&game_state, &game_config, &player_list)?;
+    assert_is_turn(&game_state, &player_list, player.key())?;
 
     // Check cards left
-    if game_state.cards_left_in_deck == 0 {
+    if game_state.cards_left_in_deck < count {
         return Err(PokerError::NoCardsLeft.into());
     }
 
     // Validate we can deal more community cards in current phase
-    match game_state.community_cards_state() {
-        CommunityCardsState::FlopAwaiting => {
-            if community_cards.card_count >= 3 {
-                return Err(PokerError::InvalidCommunityCardsState.into());
-            }
-        }
-        CommunityCardsState::TurnAwaiting => {
-            if community_cards.card_count != 3 {
-                return Err(PokerError::InvalidCommunityCardsState.into());
-            }
-        }
-        CommunityCardsState::RiverAwaiting => {
-            if community_cards.card_count != 4 {
-                return Err(PokerError::InvalidCommunityCardsState.into());
-            }
-        }
-        _ => return Err(PokerError::InvalidCommunityCardsState.into()),
-    };
-
-    // Deal ONE card at a time
-    game_state.cards_left_in_deck -= 1;
-    let card_index = game_state.cards_left_in_deck;
-
-    // Mark card as owned by "community" (dealer)
-    deck_state.set_card_owner(card_index as usize, player.key());
-    community_cards.add_card(card_index);
+    validate_deal_count(game_state.community_cards_state(), community_cards.card_count, count)?;
+    validate_board_opened(
+        game_state.community_cards_state(),
+        community_cards.card_count,
+        community_cards.opened_count,
+    )?;
 
-    // Set card_to_reveal for the reveal phase
-    game_state.card_to_reveal = card_index;
+    deal_cards(&mut game_state, &mut deck_state, &mut community_cards, player.key(), count)?;
 
     // Update state to Opening/Revealing
     game_state.community_cards_state = CommunityCardsState::Opening as u8;
-    game_state.drawing_state = DrawingState::Revealing as u8;
+    game_state.begin_reveal();
 
     // Reset revealed flags in player list for new reveal round
     player_list.reset_revealed();
@@ -124,3 +124,231 @@ pub fn process_deal_community(
     msg!("CommunityCardsStateChanged: Opening");
     Ok(())
 }
+
+/// Check that `count` more board cards fit the current community phase
+///
+/// The flop may be dealt one card at a time or all at once (`DealFlop`);
+/// the turn and river are always a single card.
+fn validate_deal_count(
+    state: CommunityCardsState,
+    card_count: u8,
+    count: u8,
+) -> Result<(), ProgramError> {
+    let allowed = match state {
+        CommunityCardsState::FlopAwaiting => match count {
+            1 => card_count < FLOP_CARDS,
+            FLOP_CARDS => card_count == 0,
+            _ => false,
+        },
+        CommunityCardsState::TurnAwaiting => count == 1 && card_count == FLOP_CARDS,
+        CommunityCardsState::RiverAwaiting => count == 1 && card_count == FLOP_CARDS + 1,
+        _ => false,
+    };
+    if !allowed {
+        return Err(PokerError::InvalidCommunityCardsState.into());
+    }
+    Ok(())
+}
+
+/// Check that the turn is still on the dealer, who deals the board
+///
+/// Every path into `CommunityCardsAwaiting` hands the turn to the dealer; a
+/// turn pointing anywhere else means the state is out of step. With a dead
+/// button the first occupied seat after it deals.
+pub(crate) fn assert_dealer_turn(
+    game_state: &GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) -> Result<(), ProgramError> {
+    if game_state.current_turn != game_config.dealer_seat(player_list) {
+        return Err(PokerError::NotYourTurn.into());
+    }
+    Ok(())
+}
+
+/// Check that every board card dealt so far has been opened
+///
+/// Cross-checks the awaited phase against `opened_count` so a phase that got
+/// ahead of the board (e.g. `RiverAwaiting` with one card open) can't deal.
+fn validate_board_opened(
+    state: CommunityCardsState,
+    card_count: u8,
+    opened_count: u8,
+) -> Result<(), ProgramError> {
+    let expected_opened = match state {
+        CommunityCardsState::FlopAwaiting => card_count,
+        CommunityCardsState::TurnAwaiting => FLOP_CARDS,
+        CommunityCardsState::RiverAwaiting => FLOP_CARDS + 1,
+        _ => return Err(PokerError::InvalidCommunityCardsState.into()),
+    };
+    if opened_count != expected_opened || opened_count != card_count {
+        return Err(PokerError::InvalidCommunityCardsState.into());
+    }
+    Ok(())
+}
+
+/// Deal `count` cards from the top of the deck to the board
+///
+/// `card_to_reveal` is left on the first card dealt, so the batch to reveal
+/// is `cards_left_in_deck..=card_to_reveal`.
+fn deal_cards(
+    game_state: &mut GameState,
+    deck_state: &mut DeckStateMut,
+    community_cards: &mut CommunityCards,
+    dealer: &Pubkey,
+    count: u8,
+) -> Result<(), ProgramError> {
+    for i in 0..count {
+        game_state.cards_left_in_deck -= 1;
+        let card_index = game_state.cards_left_in_deck;
+
+        // Never hand out a card that is already someone's hole card
+        validate_undealt(deck_state, community_cards, card_index)?;
+
+        // Mark card as owned by "community" (dealer)
+        deck_state.set_card_owner(card_index as usize, dealer);
+        community_cards.add_card(card_index);
+
+        // Set card_to_reveal for the reveal phase
+        if i == 0 {
+            game_state.card_to_reveal = card_index;
+        }
+    }
+    Ok(())
+}
+
+/// Ensure a deck index has not already been dealt as a hole or community card
+fn validate_undealt(
+    deck_state: &DeckStateMut,
+    community_cards: &CommunityCards,
+    card_index: u8,
+) -> Result<(), ProgramError> {
+    if card_index as usize >= DECK_SIZE {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    if deck_state.card_has_owner(card_index as usize)
+        || community_cards.is_community_card(card_index)
+    {
+        return Err(PokerError::CardAlreadyDealt.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deal_flop_in_one_instruction() {
+        let mut deck_data = vec![0u8; DECK_STATE_SIZE];
+        let mut deck_state = DeckStateMut::from_bytes(&mut deck_data).unwrap();
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        // Two players' hole cards are gone from the top of the deck
+        game_state.cards_left_in_deck = DECK_SIZE as u8 - 4;
+        let dealer = [1u8; 32];
+
+        assert!(validate_deal_count(CommunityCardsState::FlopAwaiting, 0, FLOP_CARDS).is_ok());
+        deal_cards(&mut game_state, &mut deck_state, &mut community_cards, &dealer, FLOP_CARDS)
+            .unwrap();
+
+        assert_eq!(community_cards.card_count, 3);
+        assert_eq!(&community_cards.card_indices[..3], &[47, 46, 45]);
+        assert!((45..48).all(|index| deck_state.get_card_owner(index) == &dealer));
+
+        // All three cards are revealed together, first dealt first
+        assert_eq!(game_state.card_to_reveal, 47);
+        assert_eq!(game_state.pending_reveal_count(), 3);
+        assert_eq!(game_state.pending_reveal_cards().collect::<Vec<_>>(), vec![47, 46, 45]);
+
+        // The flop can't be dealt again, and the turn is still one card
+        assert_eq!(
+            validate_deal_count(CommunityCardsState::FlopAwaiting, 3, FLOP_CARDS),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+        assert!(validate_deal_count(CommunityCardsState::TurnAwaiting, 3, 1).is_ok());
+        assert_eq!(
+            validate_deal_count(CommunityCardsState::TurnAwaiting, 3, FLOP_CARDS),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+        // A partly dealt flop finishes one card at a time
+        assert_eq!(
+            validate_deal_count(CommunityCardsState::FlopAwaiting, 1, FLOP_CARDS),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+        assert!(validate_deal_count(CommunityCardsState::FlopAwaiting, 2, 1).is_ok());
+    }
+
+    #[test]
+    fn test_turn_before_flop_opened_rejected() {
+        // Flop dealt but only one card opened
+        assert_eq!(
+            validate_board_opened(CommunityCardsState::TurnAwaiting, 3, 1),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+        // River awaited with a single card on the board
+        assert_eq!(
+            validate_board_opened(CommunityCardsState::RiverAwaiting, 1, 1),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+        // Flop still being dealt one card at a time needs each card opened first
+        assert_eq!(
+            validate_board_opened(CommunityCardsState::FlopAwaiting, 2, 1),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+
+        assert!(validate_board_opened(CommunityCardsState::FlopAwaiting, 0, 0).is_ok());
+        assert!(validate_board_opened(CommunityCardsState::FlopAwaiting, 2, 2).is_ok());
+        assert!(validate_board_opened(CommunityCardsState::TurnAwaiting, 3, 3).is_ok());
+        assert!(validate_board_opened(CommunityCardsState::RiverAwaiting, 4, 4).is_ok());
+    }
+
+    #[test]
+    fn test_deal_rejected_when_turn_is_off_the_dealer() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        game_config.dealer_index = 2;
+        let player_list = crate::test_utils::seated_player_list(4);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
+
+        game_state.current_turn = 2;
+        assert!(assert_dealer_turn(&game_state, &game_config, &player_list).is_ok());
+
+        // Corrupted turn pointing at a non-dealer seat
+        game_state.current_turn = 3;
+        assert_eq!(
+            assert_dealer_turn(&game_state, &game_config, &player_list),
+            Err(PokerError::NotYourTurn.into())
+        );
+    }
+
+    #[test]
+    fn test_community_cards_never_collide_with_hole_cards() {
+        let mut deck_data = vec![0u8; DECK_STATE_SIZE];
+        let mut deck_state = DeckStateMut::from_bytes(&mut deck_data).unwrap();
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+
+        // Two players draw their hole cards from the top of the deck
+        let mut cards_left = DECK_SIZE as u8;
+        for player in [[1u8; 32], [2u8; 32], [1u8; 32], [2u8; 32]] {
+            cards_left -= 1;
+            deck_state.set_card_owner(cards_left as usize, &player);
+        }
+
+        // The board is dealt from what remains
+        for _ in 0..5 {
+            cards_left -= 1;
+            assert!(validate_undealt(&deck_state, &community_cards, cards_left).is_ok());
+            deck_state.set_card_owner(cards_left as usize, &[1u8; 32]);
+            community_cards.add_card(cards_left);
+        }
+
+        // Every hole and community index is now rejected
+        for index in cards_left..DECK_SIZE as u8 {
+            assert_eq!(
+                validate_undealt(&deck_state, &community_cards, index),
+                Err(PokerError::CardAlreadyDealt.into())
+            );
+        }
+    }
+}
@@ -85,8 +85,8 @@ pub fn process_draw(
     player_state.hole_cards_count += 1;
 
     game_state.cards_drawn += 1;
-    game_state.drawing_state = DrawingState::Revealing as u8;
     game_state.card_to_reveal = card_index;
+    game_state.begin_reveal();
 
     // Reset revealed bitmap for this card
     player_list.reset_revealed();
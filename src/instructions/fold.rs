@@ -5,10 +5,17 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{error::PokerError, state::*};
+use crate::{
+    error::PokerError,
+    state::*,
+    utils::{
+        assert_can_act, assert_is_turn, assert_not_all_in, assert_not_finished, first_in_hand_from,
+        next_to_bet, players_in_hand, read_table_betting,
+    },
+};
 
 pub fn process_fold(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
@@ -18,6 +25,8 @@ pub fn process_fold(
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // Remaining accounts (optional): every seat's PlayerState, so the round can
+    // close as soon as all but one player are all-in
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -34,74 +43,112 @@ pub fn process_fold(
     };
 
     let mut player_state = unsafe {
-        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+        PlayerStateMut::from_bytes(player_state_acc.borrow_mut_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Validate state
-    if game_state.texas_state() != TexasHoldEmState::Betting {
-        return Err(PokerError::InvalidTexasState.into());
-    }
+    // Validate state (blinds are posted via place_blind, not bet/fold)
+    assert_not_finished(game_state.game_phase())?;
+    assert_can_act(game_state.texas_state(), game_state.betting_round_state())?;
 
     // Validate turn
-    let current_player = player_list.get_player(game_state.current_turn)
-        .ok_or(PokerError::NotAPlayer)?;
-    if current_player != player.key() {
-        return Err(PokerError::NotYourTurn.into());
+    let seat = assert_is_turn(&game_state, &player_list, player.key())?;
+
+    // Only the signer's own seat at this table can be folded
+    if player_state.player() != player.key() || player_state.game_id() != &game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
     }
 
-    // Validate not already folded
+    // Validate not already folded or all-in
     if player_state.is_folded() {
         return Err(PokerError::AlreadyFolded.into());
     }
+    assert_not_all_in(player_state.chips())?;
 
     // Mark as folded
-    player_state.is_folded = 1;
-    game_state.num_folded_players += 1;
+    player_state.set_folded(true);
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if only one player left
-    let players_remaining = game_config.max_players - game_state.num_folded_players;
-    if players_remaining == 1 {
-        // Early end - last player wins
-        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
-        msg!("EarlyEnd: Only one player remaining");
-    } else if game_state.last_to_call == *player.key() {
-        // Betting round complete
-        finish_betting_round(&mut game_state, &game_config);
-    } else {
-        // Next turn
-        game_state.current_turn = next_active_player(
-            game_state.current_turn,
-            game_config.max_players,
-            game_state.num_folded_players,
-        );
-    }
+    let table = read_table_betting(
+        iter.as_slice(),
+        program_id,
+        &game_config.game_id,
+        &player_list,
+        game_config.max_players,
+    );
+    let seats = table.as_ref().map(|seats| &seats[..game_config.max_players as usize]);
+    apply_fold(&mut game_state, &game_config, &mut player_list, seat, seats);
 
     unsafe {
         game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
             .copy_from_slice(&game_state.to_bytes());
-        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
-            .copy_from_slice(&player_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
     msg!("PlayerFolded");
     Ok(())
 }
 
-fn next_active_player(current: u8, max: u8, _folded: u8) -> u8 {
-    // Simplified - in production would skip folded players
-    (current + 1) % max
+/// Count a fold and move the hand forward
+///
+/// When the last opponent folds the hand ends without a showdown and goes
+/// straight to ClaimPot. Pre-flop, with everyone folding around to the big
+/// blind, this is a walk: the big blind collects the blinds.
+///
+/// A fold can satisfy several exits at once, so they are checked in order:
+/// early end (one player left), then the all-in fast-forward (nobody left who
+/// can bet, flagged with `is_everybody_all_in`), then normal round completion.
+pub(crate) fn apply_fold(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &mut PlayerList,
+    seat: u8,
+    seats: Option<&[SeatBetting]>,
+) {
+    game_state.num_folded_players += 1;
+    player_list.mark_folded(seat);
+    game_state.record_action(seat, BetActionType::Fold, 0);
+
+    // Check if only one player left
+    let players_remaining = players_in_hand(player_list, game_state.num_folded_players);
+    game_state.note_player_acted(false, players_remaining);
+    if players_remaining == 1 {
+        // Early end - last player wins
+        if game_state.betting_round_state() == BettingRoundState::PreFlop {
+            msg!("Walk: blinds go to the last player");
+        }
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+        msg!("EarlyEnd: Only one player remaining");
+    } else if seats.is_some_and(no_further_betting) {
+        // Remaining players are all-in - run out the board without betting
+        game_state.is_everybody_all_in = 1;
+        msg!("AllIn: no further betting this hand");
+        finish_betting_round(game_state, game_config, player_list);
+    } else if game_state.is_betting_round_complete(seats) {
+        // Betting round complete
+        finish_betting_round(game_state, game_config, player_list);
+    } else {
+        game_state.current_turn = next_to_bet(seat, player_list, game_config.max_players);
+    }
 }
 
-fn finish_betting_round(game_state: &mut GameState, game_config: &GameConfig) {
+/// Close the betting round: deal the next street, or go to showdown after the river
+///
+/// The dealer deals the next street; showdown opens from the first seat
+/// still in the hand at or after the dealer.
+pub(crate) fn finish_betting_round(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) {
     match game_state.betting_round_state() {
         BettingRoundState::PreFlop => {
             game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
@@ -117,8 +164,103 @@ fn finish_betting_round(game_state: &mut GameState, game_config: &GameConfig) {
         }
         BettingRoundState::Showdown => {
             game_state.texas_state = TexasHoldEmState::Revealing as u8;
+            game_state.current_turn =
+                first_in_hand_from(player_list, game_config.max_players, game_config.dealer_index);
+            return;
         }
         _ => {}
     }
-    game_state.current_turn = game_config.dealer_index;
+    game_state.current_turn = game_config.dealer_seat(player_list);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seated_player_list;
+
+    fn config(max_players: u8) -> GameConfig {
+        GameConfig::new(0, [0; 32], [0; 32], [0; 32], max_players, 10, 100, 0)
+    }
+
+    fn seat(is_folded: bool, chips: u64, current_bet: u64) -> SeatBetting {
+        SeatBetting { is_folded, chips, current_bet }
+    }
+
+    fn post_flop(players_to_act: u8) -> GameState {
+        let mut game_state = GameState::new(0, [0; 32], 0);
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.start_betting_round(players_to_act);
+        game_state
+    }
+
+    #[test]
+    fn test_fold_closes_round_leaving_all_in_players() {
+        // Seats 0 and 1 are all-in; seat 2 was the last to call and folds
+        let mut game_state = post_flop(1);
+        let seats = [seat(false, 0, 300), seat(false, 0, 500), seat(true, 800, 100)];
+        apply_fold(&mut game_state, &config(3), &mut seated_player_list(3), 2, Some(&seats));
+
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::TurnAwaiting);
+        assert!(game_state.is_everybody_all_in());
+
+        // The same fold without seat info is an ordinary round close
+        let mut game_state = post_flop(1);
+        apply_fold(&mut game_state, &config(3), &mut seated_player_list(3), 2, None);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::TurnAwaiting);
+        assert!(!game_state.is_everybody_all_in());
+    }
+
+    #[test]
+    fn test_fold_to_all_in_players_runs_out_board() {
+        use crate::instructions::open_community_card::advance_after_open;
+
+        // Pre-flop: seats 0 and 1 are all-in, seat 2 folds to them
+        let mut game_state = GameState::new(0, [0; 32], 0);
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+        game_state.start_betting_round(1);
+        let seats = [seat(false, 0, 300), seat(false, 0, 500), seat(true, 800, 100)];
+        let game_config = config(3);
+        apply_fold(&mut game_state, &game_config, &mut seated_player_list(3), 2, Some(&seats));
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::FlopAwaiting);
+
+        // Every street is dealt without a betting round in between
+        let player_list = PlayerList::new(0, [0; 32]);
+        advance_after_open(&mut game_state, &game_config, &player_list, 3);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::TurnAwaiting);
+        advance_after_open(&mut game_state, &game_config, &player_list, 4);
+        assert_eq!(game_state.community_cards_state(), CommunityCardsState::RiverAwaiting);
+        advance_after_open(&mut game_state, &game_config, &player_list, 5);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Revealing);
+    }
+
+    #[test]
+    fn test_early_end_takes_priority_over_all_in() {
+        // Heads-up against an all-in: the fold ends the hand outright
+        let mut game_state = post_flop(1);
+        let seats = [seat(false, 0, 500), seat(true, 400, 100)];
+        apply_fold(&mut game_state, &config(2), &mut seated_player_list(2), 1, Some(&seats));
+
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+        assert!(!game_state.is_everybody_all_in());
+    }
+
+    #[test]
+    fn test_turn_skips_earlier_fold_without_table() {
+        // Seat 1 folded earlier; seat 0 folds now and no PlayerStates are passed
+        let mut game_state = post_flop(3);
+        game_state.num_folded_players = 1;
+        let mut player_list = seated_player_list(4);
+        player_list.mark_folded(1);
+        apply_fold(&mut game_state, &config(4), &mut player_list, 0, None);
+        assert_eq!(game_state.current_turn, 2);
+
+        // Showdown opens with the first seat still in from the folded dealer
+        let mut game_state = post_flop(1);
+        game_state.betting_round_state = BettingRoundState::Showdown as u8;
+        finish_betting_round(&mut game_state, &config(4), &player_list);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Revealing);
+        assert_eq!(game_state.current_turn, 2);
+    }
 }
@@ -1,15 +1,23 @@
 //! Generate shuffle vector instruction
 //!
 //! Uses seed-based derivation to reduce transaction size from 1664 bytes to 32 bytes.
-//! Player submits a seed, on-chain derives v[i] = keccak256(seed || i) for all 52 cards.
-//! Commitment verification: keccak256(seed) must match the commitment stored at join time.
+//! Player submits a seed, on-chain derives v[i] = keccak256(seed || i) mod n for every card
+//! of the table's deck (52, or 36 for a short deck).
+//! Commitment verification: keccak256(seed || player) must match the commitment stored at
+//! join time. Binding the player's pubkey makes a commitment useless to anyone else.
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, crypto::*, error::PokerError, state::*};
+use crate::{
+    crypto::*,
+    error::PokerError,
+    poker::deck_card_id,
+    state::*,
+    utils::{assert_own_player_state, assert_shuffling_step, finish_seat_step},
+};
 
 pub fn process_generate(
     _program_id: &Pubkey,
@@ -17,12 +25,7 @@ pub fn process_generate(
     data: &[u8],
 ) -> ProgramResult {
     // Data: 32-byte seed (reduced from 1664 bytes)
-    if data.len() < 32 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    // Extract seed from instruction data
-    let seed: &[u8; 32] = unsafe { &*(data.as_ptr() as *const [u8; 32]) };
+    let seed = parse_seed(data)?;
 
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -46,7 +49,7 @@ pub fn process_generate(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_state = unsafe {
+    let mut player_state = unsafe {
         PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -57,9 +60,7 @@ pub fn process_generate(
     };
 
     // Validate state
-    if game_state.shuffling_state() != ShufflingState::Generating {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Generating)?;
 
     // Validate turn
     let current_player = player_list.get_player(game_state.current_turn)
@@ -68,12 +69,14 @@ pub fn process_generate(
         return Err(PokerError::NotYourTurn.into());
     }
 
-    // Verify commitment: keccak256(seed) must match stored commitment
+    // The seed commitment must be the one this player made on joining this
+    // game, and the player may contribute to the accumulator only once
+    assert_can_generate(&player_state, player.key(), &game_config.game_id)?;
+    assert_no_chunks_started(&player_state)?;
+
+    // Verify commitment: keccak256(seed || player) must match stored commitment
     // This preserves the hiding property - commitment hides the seed until reveal
-    let computed_commitment = keccak256(seed);
-    if computed_commitment != player_state.commitment {
-        return Err(PokerError::InvalidCommitment.into());
-    }
+    verify_seed_commitment(seed, player.key(), &player_state.commitment, keccak256)?;
 
     // Use zero-copy mutable reference instead of deserializing onto stack
     let mut accumulator = unsafe {
@@ -81,40 +84,293 @@ pub fn process_generate(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Derive and accumulate all 52 values on-chain
-    // v[i] = keccak256(seed || i) - PRF derivation
+    // Derive and accumulate one value per card on-chain (52, or 36 for a short deck)
     // This replaces sending 1664 bytes with 32 bytes + 52 keccak256 calls (~5300 CU)
-    for i in 0..DECK_SIZE {
-        let mut preimage = [0u8; 33];
-        preimage[0..32].copy_from_slice(seed);
-        preimage[32] = i as u8;
-        let derived_value = keccak256(&preimage);
-        accumulator.add_to_accumulator(i, &derived_value);
-    }
-
-    game_state.active_player_count += 1;
+    let deck_size = game_config.deck_size;
+    accumulate_seed_range(&mut accumulator, seed, 0, deck_size as usize, deck_size, keccak256);
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all players have generated
-    if game_state.active_player_count >= game_config.max_players {
-        game_state.shuffling_state = ShufflingState::Shuffling as u8;
-        game_state.active_player_count = 0;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-        msg!("ShufflingStateChanged: Shuffling");
-    } else {
-        // Next turn
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
-    }
+    player_state.has_generated = 1;
+    finish_generate(&mut game_state, &game_config, &player_list);
 
-    // Write back game_state only
     // Note: accumulator writes go directly to account via zero-copy
     unsafe {
         game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
             .copy_from_slice(&game_state.to_bytes());
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
     }
 
     msg!("AccumulatorUpdated");
     Ok(())
 }
+
+/// Derive and accumulate shuffle values for deck positions `[start, end)`
+///
+/// v[i] = hash(seed || i) mod n - PRF derivation, see `derive_shuffle_value`,
+/// where `i` is the card ID at that position of a `deck_size` deck (see
+/// `deck_card_id`), so a short deck never touches a Two through Five.
+/// The hash is passed in so the derivation can be exercised off-chain where
+/// the keccak syscall is unavailable.
+pub(crate) fn accumulate_seed_range(
+    accumulator: &mut AccumulatorStateMut,
+    seed: &[u8; 32],
+    start: usize,
+    end: usize,
+    deck_size: u8,
+    hash: impl Fn(&[u8]) -> [u8; 32],
+) {
+    for position in start..end {
+        let card = deck_card_id(deck_size, position);
+        let derived_value = derive_shuffle_value_with(seed, card, &hash);
+        accumulator.add_to_accumulator(card as usize, &derived_value);
+    }
+}
+
+/// Check a revealed seed against the commitment made at join time
+///
+/// The commitment is `hash(seed || player)`, so a seed committed by one
+/// player can't be replayed by another. The hash is passed in as for
+/// `accumulate_seed_range`.
+pub(crate) fn verify_seed_commitment(
+    seed: &[u8; 32],
+    player: &Pubkey,
+    commitment: &[u8; 32],
+    hash: impl Fn(&[u8]) -> [u8; 32],
+) -> Result<(), PokerError> {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(seed);
+    preimage[32..].copy_from_slice(player);
+    if hash(&preimage) != *commitment {
+        return Err(PokerError::InvalidCommitment);
+    }
+    Ok(())
+}
+
+/// Extract the seed, rejecting data that is not exactly 32 bytes
+///
+/// Trailing bytes would otherwise be silently ignored, hiding client bugs.
+fn parse_seed(data: &[u8]) -> Result<&[u8; 32], ProgramError> {
+    data.try_into().map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// Validate the signer may contribute to the accumulator from this PlayerState
+///
+/// The "contribute once" flag lives on the PlayerState, so the account has to
+/// be the signer's own for this game: switching to a fresh PlayerState would
+/// otherwise clear the flag.
+pub(crate) fn assert_can_generate(
+    player_state: &PlayerState,
+    player: &Pubkey,
+    game_id: &[u8; 32],
+) -> Result<(), PokerError> {
+    assert_own_player_state(player_state, player, game_id)?;
+    assert_not_generated(player_state)
+}
+
+/// Reject a second accumulator contribution from the same player this hand
+///
+/// Guards the accumulator even if turn handling ever lets a seat act twice.
+fn assert_not_generated(player_state: &PlayerState) -> Result<(), PokerError> {
+    if player_state.has_generated() {
+        return Err(PokerError::AlreadyGenerated);
+    }
+    Ok(())
+}
+
+/// Reject a whole-deck generate after `GenerateChunk` has started
+///
+/// The chunks already accumulated would otherwise be added a second time.
+fn assert_no_chunks_started(player_state: &PlayerState) -> Result<(), PokerError> {
+    if player_state.generate_progress != 0 {
+        return Err(PokerError::GenerateInProgress);
+    }
+    Ok(())
+}
+
+/// Record that the current player finished generating and advance the turn
+pub(crate) fn finish_generate(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) {
+    // Check if all players have generated
+    let first_seat = game_config.dealer_index + 3;
+    if finish_seat_step(game_state, player_list, game_config.max_players, first_seat) {
+        game_state.shuffling_state = ShufflingState::Shuffling as u8;
+        msg!("ShufflingStateChanged: Shuffling");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{BN254_N, DECK_SIZE};
+
+    #[test]
+    fn test_parse_seed_requires_exact_length() {
+        let data = [5u8; 33];
+        assert_eq!(parse_seed(&data[..32]), Ok(&[5u8; 32]));
+        assert_eq!(parse_seed(&data), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_seed(&data[..31]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_seed(&[]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    /// Deterministic stand-in for keccak256 (syscall unavailable in native tests)
+    fn test_hash(data: &[u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in data.iter().enumerate() {
+            out[i % 32] = out[i % 32].wrapping_mul(31).wrapping_add(*byte);
+        }
+        out
+    }
+
+    #[test]
+    fn test_commitment_bound_to_player() {
+        let seed = [7u8; 32];
+        let (alice, bob) = ([1u8; 32], [2u8; 32]);
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(&seed);
+        preimage[32..].copy_from_slice(&alice);
+        let alice_commitment = test_hash(&preimage);
+
+        assert_eq!(verify_seed_commitment(&seed, &alice, &alice_commitment, test_hash), Ok(()));
+
+        // Bob copies Alice's commitment and seed: the check fails for him
+        assert_eq!(
+            verify_seed_commitment(&seed, &bob, &alice_commitment, test_hash),
+            Err(PokerError::InvalidCommitment)
+        );
+        // A different seed fails for Alice too
+        assert_eq!(
+            verify_seed_commitment(&[8u8; 32], &alice, &alice_commitment, test_hash),
+            Err(PokerError::InvalidCommitment)
+        );
+    }
+
+    #[test]
+    fn test_double_generate_rejected() {
+        let mut player_state = PlayerState::new(0, [0u8; 32], [1u8; 32], 0, 1000, [0u8; 32]);
+        assert_eq!(assert_not_generated(&player_state), Ok(()));
+
+        player_state.has_generated = 1;
+        assert_eq!(assert_not_generated(&player_state), Err(PokerError::AlreadyGenerated));
+
+        // Cleared for the next hand
+        player_state.reset_for_next_game();
+        assert_eq!(assert_not_generated(&player_state), Ok(()));
+    }
+
+    #[test]
+    fn test_second_generate_through_fresh_player_state_rejected() {
+        let (game_id, player) = ([3u8; 32], [1u8; 32]);
+        let mut player_state = PlayerState::new(0, game_id, player, 0, 1000, [0u8; 32]);
+        assert_eq!(assert_can_generate(&player_state, &player, &game_id), Ok(()));
+        player_state.has_generated = 1;
+        assert_eq!(
+            assert_can_generate(&player_state, &player, &game_id),
+            Err(PokerError::AlreadyGenerated)
+        );
+
+        // A fresh PlayerState of another seat still reads as not generated
+        let other_seat = PlayerState::new(0, game_id, [2u8; 32], 1, 1000, [0u8; 32]);
+        assert_eq!(
+            assert_can_generate(&other_seat, &player, &game_id),
+            Err(PokerError::InvalidAccountData)
+        );
+        // Nor can the player bring their own from another table
+        let other_table = PlayerState::new(0, [9u8; 32], player, 0, 1000, [0u8; 32]);
+        assert_eq!(
+            assert_can_generate(&other_table, &player, &game_id),
+            Err(PokerError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_generate_rejected_mid_chunks() {
+        let mut player_state = PlayerState::new(0, [0u8; 32], [1u8; 32], 0, 1000, [0u8; 32]);
+        assert_eq!(assert_no_chunks_started(&player_state), Ok(()));
+
+        // The first chunk is in: the rest must come from GenerateChunk
+        player_state.generate_progress = 26;
+        assert_eq!(assert_no_chunks_started(&player_state), Err(PokerError::GenerateInProgress));
+    }
+
+    /// Real keccak256, as an off-chain client computes it
+    fn client_keccak(data: &[u8]) -> [u8; 32] {
+        use sha3::{Digest, Keccak256};
+        Keccak256::digest(data).into()
+    }
+
+    #[test]
+    fn test_accumulator_matches_client_sum() {
+        let seeds = [[1u8; 32], [2u8; 32]];
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut accumulator = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        for seed in &seeds {
+            accumulate_seed_range(&mut accumulator, seed, 0, DECK_SIZE, DECK_SIZE as u8, client_keccak);
+        }
+
+        // Client side: sum each index's derived values in the scalar field
+        for i in 0..DECK_SIZE {
+            let expected = seeds.iter().fold([0u8; 32], |sum, seed| {
+                let value = derive_shuffle_value_with(seed, i as u8, client_keccak);
+                assert!(value < BN254_N);
+                add_mod_bn254(&sum, &value)
+            });
+            assert_eq!(accumulator.get_accumulator(i), &expected);
+        }
+
+        // (keccak256([1; 32] || i) + keccak256([2; 32] || i)) mod n, computed
+        // independently; both raw hashes for card 51 are above n
+        assert_eq!(accumulator.get_accumulator(0), &[
+            0x19, 0xe1, 0xd9, 0x49, 0xf1, 0x28, 0x3c, 0x88,
+            0x24, 0xe9, 0x3e, 0xa5, 0xe2, 0x2d, 0xff, 0x66,
+            0x00, 0xdb, 0x2e, 0x8b, 0x4f, 0x90, 0x53, 0xed,
+            0x89, 0xa0, 0x02, 0x92, 0x58, 0xd3, 0xe9, 0x4b,
+        ]);
+        assert_eq!(accumulator.get_accumulator(51), &[
+            0x0a, 0x0a, 0xaf, 0xab, 0x3f, 0x34, 0x47, 0x1d,
+            0xab, 0x5b, 0x61, 0x99, 0x39, 0x22, 0x7c, 0x29,
+            0x18, 0x27, 0xf7, 0x66, 0xe2, 0xa3, 0x5c, 0xb1,
+            0xb7, 0xea, 0xfd, 0x74, 0x19, 0x11, 0x13, 0x6e,
+        ]);
+    }
+
+    #[test]
+    fn test_generate_rejected_outside_shuffling_phase() {
+        // A stale Generating substate must not let generate run mid-hand
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.game_phase = GamePhase::Opening as u8;
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.shuffling_state = ShufflingState::Generating as u8;
+        assert_eq!(
+            assert_shuffling_step(
+                game_state.game_phase(),
+                game_state.shuffling_state(),
+                ShufflingState::Generating,
+            ),
+            Err(PokerError::InvalidState.into())
+        );
+
+        game_state.game_phase = GamePhase::Shuffling as u8;
+        assert_eq!(
+            assert_shuffling_step(
+                game_state.game_phase(),
+                game_state.shuffling_state(),
+                ShufflingState::Generating,
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            assert_shuffling_step(
+                game_state.game_phase(),
+                game_state.shuffling_state(),
+                ShufflingState::Locking,
+            ),
+            Err(PokerError::InvalidShufflingState.into())
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Generate shuffle vector in chunks
+//!
+//! Same derivation as `Generate`, but only processes the client-specified
+//! range `[start, end)` of deck positions so the keccak256 calls can be
+//! spread across several transactions when compute budget is tight.
+//! Chunks must be submitted in order; progress is tracked in
+//! `PlayerState::generate_progress`. The player's turn ends once the final
+//! chunk (ending at the table's deck size, 52 or 36) has been accumulated.
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar}, ProgramResult,
+};
+
+use crate::{
+    crypto::*,
+    error::PokerError,
+    instructions::generate::{
+        accumulate_seed_range, assert_can_generate, finish_generate, verify_seed_commitment,
+    },
+    state::*,
+    utils::{assert_is_turn, assert_shuffling_step},
+};
+
+pub fn process_generate_chunk(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // Data: 32-byte seed + start (1) + end (1)
+    if data.len() < 34 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let seed: &[u8; 32] = unsafe { &*(data.as_ptr() as *const [u8; 32]) };
+    let start = data[32] as usize;
+    let end = data[33] as usize;
+
+    let mut iter = accounts.iter();
+    let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !player.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_state = unsafe {
+        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Validate state
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Generating)?;
+
+    // Validate turn
+    assert_is_turn(&game_state, &player_list, player.key())?;
+
+    // The seed commitment must be the one this player made on joining this
+    // game, and the player may contribute to the accumulator only once
+    assert_can_generate(&player_state, player.key(), &game_config.game_id)?;
+
+    // Chunks must be contiguous and stay inside the deck
+    let deck_size = game_config.deck_size as usize;
+    if start != player_state.generate_progress as usize || start >= end || end > deck_size {
+        return Err(PokerError::InvalidVectorSize.into());
+    }
+
+    // Verify commitment: keccak256(seed || player) must match stored commitment
+    verify_seed_commitment(seed, player.key(), &player_state.commitment, keccak256)?;
+
+    let mut accumulator = unsafe {
+        AccumulatorStateMut::from_bytes(accumulator_acc.borrow_mut_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    accumulate_seed_range(&mut accumulator, seed, start, end, game_config.deck_size, keccak256);
+    player_state.generate_progress = end as u8;
+
+    let clock = Clock::get()?;
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    if end == deck_size {
+        player_state.generate_progress = 0;
+        player_state.has_generated = 1;
+        finish_generate(&mut game_state, &game_config, &player_list);
+    }
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
+    }
+
+    msg!("AccumulatorChunkUpdated");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{CARDS_PER_PART, DECK_SIZE, SHORT_DECK_SIZE};
+
+    /// Deterministic stand-in for keccak256 (syscall unavailable in native tests)
+    fn test_hash(data: &[u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in data.iter().enumerate() {
+            out[i % 32] = out[i % 32].wrapping_mul(31).wrapping_add(*byte);
+        }
+        out[0] = data[32];
+        out
+    }
+
+    #[test]
+    fn test_two_chunks_match_single_shot() {
+        let seed = [7u8; 32];
+
+        let mut single_data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut single = AccumulatorStateMut::from_bytes(&mut single_data).unwrap();
+        accumulate_seed_range(&mut single, &seed, 0, DECK_SIZE, DECK_SIZE as u8, test_hash);
+
+        let mut chunked_data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut chunked = AccumulatorStateMut::from_bytes(&mut chunked_data).unwrap();
+        accumulate_seed_range(&mut chunked, &seed, 0, CARDS_PER_PART, DECK_SIZE as u8, test_hash);
+        accumulate_seed_range(&mut chunked, &seed, CARDS_PER_PART, DECK_SIZE, DECK_SIZE as u8, test_hash);
+
+        assert_eq!(single_data, chunked_data);
+    }
+
+    #[test]
+    fn test_short_deck_never_accumulates_low_ranks() {
+        use crate::poker::is_short_deck_card;
+
+        let seed = [7u8; 32];
+        let short = SHORT_DECK_SIZE as u8;
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut accumulator = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        accumulate_seed_range(&mut accumulator, &seed, 0, CARDS_PER_PART, short, test_hash);
+        accumulate_seed_range(&mut accumulator, &seed, CARDS_PER_PART, SHORT_DECK_SIZE, short, test_hash);
+
+        // Exactly the 36 short-deck cards get a value; Twos to Fives stay empty
+        for card in 0..DECK_SIZE {
+            let filled = accumulator.get_accumulator(card) != &[0u8; 32];
+            assert_eq!(filled, is_short_deck_card(card as i8), "card {card}");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Init player stats instruction - open a PlayerStats account for a player
+//!
+//! Only tables initialized with stats enabled keep them. There every seated
+//! player needs one before a pot is settled: `ClaimPot` (or the auto-pay
+//! `SubmitBestHand` or `Muck`) updates every seat's account.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{constants::*, error::PokerError, state::*};
+
+/// System program ID
+const SYSTEM_PROGRAM_ID: Pubkey = [0u8; 32];
+
+/// Create the PlayerStats PDA via CPI to System Program
+#[inline(never)]
+fn create_player_stats_account<'a>(
+    payer: &'a AccountInfo,
+    pda: &'a AccountInfo,
+    system_program: &'a AccountInfo,
+    program_id: &Pubkey,
+    game_id: &[u8; 32],
+    bump: u8,
+) -> ProgramResult {
+    let lamports = Rent::get()?.minimum_balance(PLAYER_STATS_SIZE);
+
+    // create_account: instruction index (4) + lamports (8) + space (8) + owner (32)
+    let mut ix_data = [0u8; 4 + 8 + 8 + 32];
+    ix_data[4..12].copy_from_slice(&lamports.to_le_bytes());
+    ix_data[12..20].copy_from_slice(&(PLAYER_STATS_SIZE as u64).to_le_bytes());
+    ix_data[20..52].copy_from_slice(program_id);
+
+    let account_metas = [
+        AccountMeta {
+            pubkey: payer.key(),
+            is_signer: true,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: pda.key(),
+            is_signer: true,
+            is_writable: true,
+        },
+    ];
+
+    let instruction = Instruction {
+        program_id: &SYSTEM_PROGRAM_ID,
+        accounts: &account_metas,
+        data: &ix_data,
+    };
+
+    // Signer seeds: ["stats", game_id, player, bump]
+    let bump_slice = [bump];
+    let seeds: [Seed; 4] = [
+        Seed::from(PLAYER_STATS_SEED),
+        Seed::from(game_id.as_slice()),
+        Seed::from(payer.key().as_slice()),
+        Seed::from(bump_slice.as_slice()),
+    ];
+    let signer = Signer::from(&seeds);
+
+    invoke_signed::<3>(&instruction, &[payer, pda, system_program], &[signer])
+}
+
+pub fn process_init_player_stats(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let mut iter = accounts.iter();
+    let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_stats_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let system_program = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !player.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    // The game ID comes from a real table of this program
+    if game_config_acc.owner() != program_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if !game_config.stats_enabled() {
+        return Err(PokerError::StatsNotEnabled.into());
+    }
+
+    let (expected, bump) = PlayerStats::derive_pda(&game_config.game_id, player.key(), program_id);
+    if player_stats_acc.key() != &expected {
+        return Err(PokerError::InvalidPDA.into());
+    }
+
+    create_player_stats_account(
+        player,
+        player_stats_acc,
+        system_program,
+        program_id,
+        &game_config.game_id,
+        bump,
+    )?;
+
+    let stats = PlayerStats::new(bump, game_config.game_id, *player.key());
+    unsafe {
+        player_stats_acc.borrow_mut_data_unchecked()[..PLAYER_STATS_SIZE]
+            .copy_from_slice(&stats.to_bytes());
+    }
+
+    msg!("PlayerStatsInitialized");
+    Ok(())
+}
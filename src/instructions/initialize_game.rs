@@ -14,7 +14,12 @@ use pinocchio::{
     ProgramResult,
 };
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    error::PokerError,
+    instructions::join_game::seat_player,
+    state::*,
+};
 
 /// System program ID
 const SYSTEM_PROGRAM_ID: Pubkey = [0u8; 32];
@@ -108,6 +113,16 @@ fn create_pda_token_account<'a>(
     Ok(())
 }
 
+/// Check an initialized vault token account holds `mint` under `authority`
+///
+/// SPL token account layout: mint(32) + owner(32) + ...
+fn verify_vault_data(data: &[u8], mint: &Pubkey, authority: &Pubkey) -> ProgramResult {
+    if data.len() < TOKEN_ACCOUNT_SIZE || &data[0..32] != mint || &data[32..64] != authority {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    Ok(())
+}
+
 /// Write initial deck state directly to account data (avoids 3361-byte stack allocation)
 #[inline(never)]
 fn write_deck_state_initial(data: &mut [u8], bump: u8, game_id: &[u8; 32]) {
@@ -203,9 +218,7 @@ pub fn process_initialize_game(
             .map_err(|_| ProgramError::InvalidInstructionData)?,
     );
 
-    if max_players < MIN_PLAYERS || max_players > MAX_PLAYERS {
-        return Err(PokerError::InvalidNumPlayers.into());
-    }
+    validate_max_players(max_players)?;
     if small_blind == 0 {
         return Err(PokerError::InvalidSmallBlind.into());
     }
@@ -213,6 +226,53 @@ pub fn process_initialize_game(
         return Err(PokerError::MinBuyInTooLow.into());
     }
 
+    // Optional deck size (defaults to a standard 52-card deck)
+    let deck_size = data.get(49).copied().unwrap_or(DECK_SIZE as u8);
+    if deck_size as usize != DECK_SIZE && deck_size as usize != SHORT_DECK_SIZE {
+        return Err(PokerError::InvalidDeckSize.into());
+    }
+
+    // Optional timeout (u32 LE) and slash percentage (u8), defaulting to the constants
+    let timeout_seconds = match data.get(50..54) {
+        Some(bytes) => u32::from_le_bytes(bytes.try_into().unwrap()),
+        None => DEFAULT_TIMEOUT_SECONDS,
+    };
+    let slash_percentage = data.get(54).copied().unwrap_or(DEFAULT_SLASH_PERCENTAGE);
+    validate_slash_params(timeout_seconds, slash_percentage)?;
+
+    // Optional dealer rotation flag (defaults to rotating; 0 = fixed dealer)
+    let rotate_dealer = data.get(55).copied().unwrap_or(1);
+
+    // Optional straddle flag (defaults to disabled)
+    let allow_straddle = data.get(56).copied().unwrap_or(0);
+
+    // Optional token decimals per whole chip (defaults to 0: one token unit per chip)
+    let chip_decimals = data.get(57).copied().unwrap_or(0);
+    if chip_decimals > MAX_CHIP_DECIMALS {
+        return Err(PokerError::InvalidChipDecimals.into());
+    }
+
+    // Optional effective-stack bet cap (defaults to disabled)
+    let cap_to_effective_stack = data.get(58).copied().unwrap_or(0);
+
+    // Optional buy-in floor in big blinds (defaults to 0: only min_buy_in applies)
+    let min_buy_in_big_blinds = data.get(59).copied().unwrap_or(0);
+
+    // Optional game mode (defaults to a cash game)
+    let game_mode = data.get(60).copied().unwrap_or(GameMode::CashGame as u8);
+    if game_mode > GameMode::SitAndGo as u8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Optional authority pre-seat: flag(1) + commitment(32) + deposit(8)
+    let pre_seat = parse_pre_seat(data)?;
+
+    // Optional auto-pay flag after the pre-seat section (defaults to ClaimPot)
+    let auto_pay = data.get(102).copied().unwrap_or(0);
+
+    // Optional stats flag (defaults to no PlayerStats tracking)
+    let stats_enabled = data.get(103).copied().unwrap_or(0);
+
     // Parse accounts
     let mut iter = accounts.iter();
     let authority = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -347,11 +407,16 @@ pub fn process_initialize_game(
         vault_bump,
         &rent,
     )?;
+    verify_vault_data(
+        unsafe { vault.borrow_data_unchecked() },
+        token_mint.key(),
+        game_config_acc.key(),
+    )?;
 
     msg!("InitializeGame: initializing account data");
 
     // Initialize account data
-    let game_config = GameConfig::new(
+    let mut game_config = GameConfig::new(
         config_bump,
         game_id,
         *authority.key(),
@@ -361,9 +426,41 @@ pub fn process_initialize_game(
         min_buy_in,
         clock.unix_timestamp,
     );
-    let game_state = GameState::new(state_bump, game_id, clock.unix_timestamp);
+    game_config.deck_size = deck_size;
+    game_config.timeout_seconds = timeout_seconds;
+    game_config.slash_percentage = slash_percentage;
+    game_config.rotate_dealer = rotate_dealer;
+    game_config.allow_straddle = allow_straddle;
+    game_config.chip_decimals = chip_decimals;
+    game_config.cap_to_effective_stack = cap_to_effective_stack;
+    game_config.min_buy_in_big_blinds = min_buy_in_big_blinds;
+    game_config.game_mode = game_mode;
+    game_config.auto_pay = auto_pay;
+    game_config.stats_enabled = stats_enabled;
+    let mut game_state = GameState::new(state_bump, game_id, clock.unix_timestamp);
+    game_state.cards_left_in_deck = deck_size;
     let community = CommunityCards::new(comm_bump, game_id);
-    let player_list = PlayerList::new(list_bump, game_id);
+    let mut player_list = PlayerList::new(list_bump, game_id);
+
+    // Seat the authority in the same transaction instead of a separate JoinGame
+    // Extra accounts: authority player_state, authority token account
+    if let Some((commitment, deposit_amount)) = pre_seat {
+        let authority_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let authority_token_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        seat_player(
+            program_id,
+            authority,
+            authority_state_acc,
+            authority_token_acc,
+            vault,
+            system_program,
+            &mut game_config,
+            &mut player_list,
+            &commitment,
+            deposit_amount,
+        )?;
+        msg!("InitializeGame: authority seated");
+    }
 
     // Write to accounts
     unsafe {
@@ -390,3 +487,163 @@ pub fn process_initialize_game(
     msg!("Game initialized");
     Ok(())
 }
+
+/// Create the game and seat the authority in one atomic call
+///
+/// Same data and accounts as `InitializeGame`, except the pre-seat section
+/// is required, so the table never exists without its first player. The
+/// PDAs and vault are created first; the authority's PlayerState and deposit
+/// transfer follow once the vault can receive it.
+pub fn process_initialize_and_join(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    require_pre_seat(data)?;
+    process_initialize_game(program_id, accounts, data)
+}
+
+/// Reject `InitializeAndJoin` data without the authority's seat
+fn require_pre_seat(data: &[u8]) -> ProgramResult {
+    if parse_pre_seat(data)?.is_none() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+/// Parse the optional authority pre-seat section at the end of the data
+///
+/// Returns the authority's commitment and deposit when the flag at byte 61 is set.
+fn parse_pre_seat(data: &[u8]) -> Result<Option<([u8; 32], u64)>, ProgramError> {
+    match data.get(61).copied().unwrap_or(0) {
+        0 => Ok(None),
+        1 => {
+            let section = data.get(62..102).ok_or(ProgramError::InvalidInstructionData)?;
+            let commitment: [u8; 32] = section[..32].try_into().unwrap();
+            let deposit_amount = u64::from_le_bytes(section[32..].try_into().unwrap());
+            Ok(Some((commitment, deposit_amount)))
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Reject tables that could never seat a heads-up game or exceed the seat limit
+fn validate_max_players(max_players: u8) -> ProgramResult {
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&max_players) {
+        return Err(PokerError::InvalidNumPlayers.into());
+    }
+    Ok(())
+}
+
+/// Validate the timeout and slash penalty settings
+fn validate_slash_params(timeout_seconds: u32, slash_percentage: u8) -> ProgramResult {
+    if timeout_seconds < 1 || slash_percentage > 100 {
+        return Err(PokerError::InvalidState.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::join_game::take_seat;
+
+    #[test]
+    fn test_validate_max_players_boundaries() {
+        assert_eq!(validate_max_players(0), Err(PokerError::InvalidNumPlayers.into()));
+        assert_eq!(validate_max_players(1), Err(PokerError::InvalidNumPlayers.into()));
+        assert!(validate_max_players(2).is_ok());
+        assert!(validate_max_players(MAX_PLAYERS).is_ok());
+        assert_eq!(
+            validate_max_players(MAX_PLAYERS + 1),
+            Err(PokerError::InvalidNumPlayers.into())
+        );
+    }
+
+    #[test]
+    fn test_authority_pre_seat() {
+        let mut data = vec![0u8; 61];
+        assert_eq!(parse_pre_seat(&data), Ok(None));
+
+        data.push(1);
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(&500u64.to_le_bytes());
+        let (commitment, deposit_amount) = parse_pre_seat(&data).unwrap().unwrap();
+        assert_eq!(commitment, [7u8; 32]);
+        assert_eq!(deposit_amount, 500);
+
+        // Flag set without the commitment and deposit
+        assert_eq!(parse_pre_seat(&data[..71]), Err(ProgramError::InvalidInstructionData));
+
+        // The authority takes the first seat of the new table
+        let authority = [9u8; 32];
+        let mut game_config =
+            GameConfig::new(0, [0u8; 32], authority, [0u8; 32], 4, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        assert_eq!(take_seat(&mut game_config, &mut player_list, &authority), Ok(0));
+        assert_eq!(game_config.current_players, 1);
+        assert_eq!(player_list.get_player(0), Some(&authority));
+    }
+
+    #[test]
+    fn test_initialize_and_join_seats_authority() {
+        // Plain InitializeGame data isn't enough
+        let mut data = vec![0u8; 61];
+        assert_eq!(require_pre_seat(&data), Err(ProgramError::InvalidInstructionData));
+
+        data.push(1);
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(&500u64.to_le_bytes());
+        assert_eq!(require_pre_seat(&data), Ok(()));
+
+        // One call leaves a fresh table with exactly the authority seated
+        let authority = [9u8; 32];
+        let mut game_config =
+            GameConfig::new(0, [0u8; 32], authority, [0u8; 32], 6, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        take_seat(&mut game_config, &mut player_list, &authority).unwrap();
+        assert_eq!(game_config.current_players, 1);
+        assert_eq!(player_list.find_player_index(&authority), Some(0));
+        assert!(game_config.is_accepting_players());
+    }
+
+    #[test]
+    fn test_verify_vault_data() {
+        let mint = [1u8; 32];
+        let game_config = [2u8; 32];
+        let mut vault = [0u8; TOKEN_ACCOUNT_SIZE];
+        vault[0..32].copy_from_slice(&mint);
+        vault[32..64].copy_from_slice(&game_config);
+        assert!(verify_vault_data(&vault, &mint, &game_config).is_ok());
+
+        // Initialized with the wrong authority
+        let mut wrong_authority = vault;
+        wrong_authority[32..64].copy_from_slice(&[3u8; 32]);
+        assert_eq!(
+            verify_vault_data(&wrong_authority, &mint, &game_config),
+            Err(PokerError::InvalidAccountData.into())
+        );
+
+        // Wrong mint, or an account that isn't a token account at all
+        assert_eq!(
+            verify_vault_data(&vault, &[4u8; 32], &game_config),
+            Err(PokerError::InvalidAccountData.into())
+        );
+        assert_eq!(
+            verify_vault_data(&vault[..64], &mint, &game_config),
+            Err(PokerError::InvalidAccountData.into())
+        );
+    }
+
+    #[test]
+    fn test_validate_slash_params_boundaries() {
+        assert!(validate_slash_params(1, 0).is_ok());
+        assert!(validate_slash_params(1, 100).is_ok());
+        assert!(validate_slash_params(u32::MAX, 100).is_ok());
+        assert!(validate_slash_params(DEFAULT_TIMEOUT_SECONDS, DEFAULT_SLASH_PERCENTAGE).is_ok());
+
+        assert_eq!(validate_slash_params(0, 10), Err(PokerError::InvalidState.into()));
+        assert_eq!(validate_slash_params(120, 101), Err(PokerError::InvalidState.into()));
+        assert_eq!(validate_slash_params(120, u8::MAX), Err(PokerError::InvalidState.into()));
+    }
+}
@@ -14,7 +14,7 @@ use pinocchio::{
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{constants::*, error::PokerError, state::*, utils::first_occupied_from};
 
 /// System program ID
 const SYSTEM_PROGRAM_ID: Pubkey = [0u8; 32];
@@ -31,13 +31,13 @@ fn create_player_state_account<'a>(
     bump: u8,
     rent: &Rent,
 ) -> ProgramResult {
-    let lamports = rent.minimum_balance(PLAYER_STATE_SIZE);
+    let lamports = rent.minimum_balance(PLAYER_STATE_ACCOUNT_SIZE);
 
     // Build create_account instruction data
     let mut ix_data = [0u8; 4 + 8 + 8 + 32];
     ix_data[0..4].copy_from_slice(&0u32.to_le_bytes()); // create_account = 0
     ix_data[4..12].copy_from_slice(&lamports.to_le_bytes());
-    ix_data[12..20].copy_from_slice(&(PLAYER_STATE_SIZE as u64).to_le_bytes());
+    ix_data[12..20].copy_from_slice(&(PLAYER_STATE_ACCOUNT_SIZE as u64).to_le_bytes());
     ix_data[20..52].copy_from_slice(program_id);
 
     let account_metas = [
@@ -81,6 +81,7 @@ pub fn process_join_game(
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    // Shuffle seed commitment: keccak256(seed || player), checked by Generate
     let mut commitment = [0u8; 32];
     commitment.copy_from_slice(&data[0..32]);
     let deposit_amount = u64::from_le_bytes(data[32..40].try_into().unwrap());
@@ -128,10 +129,63 @@ pub fn process_join_game(
         return Err(PokerError::InvalidShufflingState.into());
     }
 
-    if deposit_amount < game_config.min_buy_in {
-        return Err(PokerError::InsufficientChips.into());
+    seat_player(
+        program_id,
+        player,
+        player_state_acc,
+        player_token_acc,
+        vault,
+        system_program,
+        &mut game_config,
+        &mut player_list,
+        &commitment,
+        deposit_amount,
+    )?;
+
+    let clock = Clock::get()?;
+
+    if table_ready(player_list.count, game_config.max_players) {
+        game_state.shuffling_state = ShufflingState::Generating as u8;
+        game_state.current_turn =
+            first_occupied_from(&player_list, game_config.max_players, game_config.dealer_index + 3);
+        msg!("ShufflingStateChanged: Generating");
+    }
+
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    unsafe {
+        game_config_acc.borrow_mut_data_unchecked()[..GAME_CONFIG_SIZE]
+            .copy_from_slice(&game_config.to_bytes());
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
+    msg!("PlayerJoined");
+    Ok(())
+}
+
+/// Seat a player: create their PlayerState, take the next seat and move the
+/// deposit into the vault
+///
+/// Shared by `JoinGame` and `InitializeGame`'s authority pre-seat. The caller
+/// writes `game_config` and `player_list` back.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn seat_player(
+    program_id: &Pubkey,
+    player: &AccountInfo,
+    player_state_acc: &AccountInfo,
+    player_token_acc: &AccountInfo,
+    vault: &AccountInfo,
+    system_program: &AccountInfo,
+    game_config: &mut GameConfig,
+    player_list: &mut PlayerList,
+    commitment: &[u8; 32],
+    deposit_amount: u64,
+) -> ProgramResult {
+    validate_deposit(game_config, deposit_amount)?;
+
     let (_, player_bump) = find_program_address(
         &[PLAYER_STATE_SEED, &game_config.game_id, player.key()],
         program_id,
@@ -150,7 +204,7 @@ pub fn process_join_game(
         &rent,
     )?;
 
-    let seat_index = player_list.add_player(*player.key()).ok_or(PokerError::GameFull)?;
+    let seat_index = take_seat(game_config, player_list, player.key())?;
 
     // Transfer tokens from player's token account to vault
     Transfer {
@@ -163,35 +217,72 @@ pub fn process_join_game(
 
     msg!("DepositTransferred");
 
-    // Update current_players count
-    game_config.current_players = game_config.current_players.saturating_add(1);
-
     let player_state = PlayerState::new(
         player_bump, game_config.game_id, *player.key(),
-        seat_index, deposit_amount, commitment,
+        seat_index, deposit_amount, *commitment,
     );
 
-    let clock = Clock::get()?;
-
-    if player_list.count >= game_config.max_players {
-        game_state.shuffling_state = ShufflingState::Generating as u8;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-        msg!("ShufflingStateChanged: Generating");
-    }
-
-    game_state.last_action_timestamp = clock.unix_timestamp;
-
     unsafe {
-        game_config_acc.borrow_mut_data_unchecked()[..GAME_CONFIG_SIZE]
-            .copy_from_slice(&game_config.to_bytes());
-        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
-            .copy_from_slice(&game_state.to_bytes());
         player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
             .copy_from_slice(&player_state.to_bytes());
-        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
-            .copy_from_slice(&player_list.to_bytes());
     }
 
-    msg!("PlayerJoined");
     Ok(())
 }
+
+/// Check the deposit covers the buy-in
+///
+/// A zero deposit is rejected outright, even if the configured minimum is zero.
+fn validate_deposit(game_config: &GameConfig, deposit_amount: u64) -> Result<(), PokerError> {
+    if deposit_amount == 0 || deposit_amount < game_config.required_buy_in() {
+        return Err(PokerError::InsufficientChips);
+    }
+    Ok(())
+}
+
+/// Add a player to the list and count them in `current_players`
+pub(crate) fn take_seat(
+    game_config: &mut GameConfig,
+    player_list: &mut PlayerList,
+    player: &Pubkey,
+) -> Result<u8, PokerError> {
+    let seat_index = player_list.add_player(*player).ok_or(PokerError::GameFull)?;
+    game_config.current_players = game_config.current_players.saturating_add(1);
+    Ok(seat_index)
+}
+
+/// Whether enough players are seated to start shuffling.
+///
+/// The table must be full, and never with fewer than `MIN_PLAYERS` seated,
+/// since blind positions are meaningless for a single player.
+fn table_ready(seated: u8, max_players: u8) -> bool {
+    seated >= max_players && seated >= MIN_PLAYERS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_ready_minimum_players() {
+        assert!(!table_ready(0, 2));
+        assert!(!table_ready(1, 2));
+        assert!(table_ready(2, 2));
+        assert!(!table_ready(5, 6));
+        assert!(table_ready(6, 6));
+        // A misconfigured single-seat table never starts shuffling
+        assert!(!table_ready(1, 1));
+    }
+
+    #[test]
+    fn test_zero_deposit_rejected() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 2, 10, 100, 0);
+        assert_eq!(validate_deposit(&game_config, 100), Ok(()));
+        assert_eq!(validate_deposit(&game_config, 99), Err(PokerError::InsufficientChips));
+
+        // Even with no minimum configured, a broke player can't sit down
+        game_config.min_buy_in = 0;
+        assert_eq!(validate_deposit(&game_config, 0), Err(PokerError::InsufficientChips));
+        assert_eq!(validate_deposit(&game_config, 1), Ok(()));
+    }
+}
@@ -1,15 +1,23 @@
 //! Leave game instruction - allows player to withdraw from game
 
 use pinocchio::{
-    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    msg, program_error::ProgramError, pubkey::Pubkey,
     ProgramResult,
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::{constants::{MAX_PLAYERS, HOLE_CARDS_PER_PLAYER}, error::PokerError, state::*};
+use crate::{
+    constants::{GAME_CONFIG_SEED, HOLE_CARDS_PER_PLAYER, MIN_PLAYERS},
+    crypto::bn254::COMPRESSED_G1_SIZE,
+    error::PokerError,
+    state::*,
+    utils::{assert_own_player_state, load_player_stats_for, validate_owner},
+};
 
 pub fn process_leave(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
@@ -22,17 +30,19 @@ pub fn process_leave(
     let chip_vault_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_token_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let _token_program = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // On a stats table, leaving before the hand is settled: the player's PlayerStats
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
     }
+    validate_owner(player_state_acc, program_id)?;
 
     let mut game_config = unsafe {
         GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let game_state = unsafe {
+    let mut game_state = unsafe {
         GameState::from_bytes(game_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -51,10 +61,9 @@ pub fn process_leave(
     let player_idx = player_list.find_player(player.key())
         .ok_or(PokerError::NotAPlayer)?;
 
-    // Validate player state matches
-    if player_state.player != *player.key() {
-        return Err(PokerError::InvalidSigner.into());
-    }
+    // The refund comes out of this game's vault, so it must be worked out
+    // from this game's accounts
+    assert_own_table(&game_config, &game_state, &player_list, &player_state, player.key())?;
 
     // Can only leave during certain states
     let can_leave = match game_state.game_phase() {
@@ -63,11 +72,13 @@ pub fn process_leave(
         _ => {
             // During game, can only leave if:
             // 1. Player has folded, OR
-            // 2. Game is in ClaimPot state and pot has been claimed
+            // 2. Game is in ClaimPot state and pot has been claimed, OR
+            // 3. Leaving ends a heads-up hand, forfeiting it to the opponent
             player_state.is_folded()
                 || (game_state.texas_state() == TexasHoldEmState::ClaimPot
                     && game_state.pot_claimed != 0)
                 || game_state.texas_state() == TexasHoldEmState::Finished
+                || (game_config.current_players <= MIN_PLAYERS && is_hand_in_play(&game_state))
         }
     };
 
@@ -78,29 +89,49 @@ pub fn process_leave(
     // Calculate chips to return (player's remaining chips minus any committed bets)
     let chips_to_return = player_state.chips;
 
-    // Transfer chips back to player
+    // Transfer chips back to player (the vault is owned by the game_config PDA)
     if chips_to_return > 0 {
+        let bump_slice = [game_config.bump];
+        let seeds: [Seed; 3] = [
+            Seed::from(GAME_CONFIG_SEED),
+            Seed::from(&game_config.game_id[..]),
+            Seed::from(bump_slice.as_slice()),
+        ];
+        let signer = Signer::from(&seeds);
+
         Transfer {
             from: chip_vault_acc,
             to: player_token_acc,
             authority: game_config_acc,
             amount: chips_to_return,
-        }.invoke()?;
+        }.invoke_signed(&[signer])?;
     }
 
-    // Remove player from list
-    player_list.remove_player(player_idx);
+    // Settlement only counts the seats still at the table, so a player
+    // leaving a hand that hasn't been paid out is counted now
+    if game_config.stats_enabled() && leaves_unsettled_hand(&game_state) {
+        let stats_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let mut stats =
+            load_player_stats_for(stats_acc, program_id, &game_config.game_id, player.key())?;
+        stats.record_hand(player_state.current_bet, 0, false);
+        unsafe {
+            stats_acc.borrow_mut_data_unchecked()[..PLAYER_STATS_SIZE]
+                .copy_from_slice(&stats.to_bytes());
+        }
+    }
 
-    // Update game config
-    game_config.current_players = game_config.current_players.saturating_sub(1);
+    vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut player_state, player_idx);
 
-    // Clear player state
-    player_state.clear();
+    if abort_short_handed(&game_config, &mut game_state) {
+        msg!("NotEnoughPlayers: TexasHoldEmStateChanged: ClaimPot");
+    }
 
     // Write updates
     unsafe {
         game_config_acc.borrow_mut_data_unchecked()[..GAME_CONFIG_SIZE]
             .copy_from_slice(&game_config.to_bytes());
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
         player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
             .copy_from_slice(&player_state.to_bytes());
         player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
@@ -111,11 +142,92 @@ pub fn process_leave(
     Ok(())
 }
 
+/// Check the leaving player's PlayerState and the table accounts are this game's
+fn assert_own_table(
+    game_config: &GameConfig,
+    game_state: &GameState,
+    player_list: &PlayerList,
+    player_state: &PlayerState,
+    player: &Pubkey,
+) -> Result<(), PokerError> {
+    assert_own_player_state(player_state, player, &game_config.game_id)?;
+    if game_state.game_id != game_config.game_id || player_list.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Whether a hand is being played (between the first shuffle and its end)
+fn is_mid_hand(game_state: &GameState) -> bool {
+    !matches!(
+        game_state.game_phase(),
+        GamePhase::WaitingForPlayers | GamePhase::Finished
+    )
+}
+
+/// End a hand the table no longer has enough players for
+///
+/// A player still in a heads-up hand may leave it; the hand then goes
+/// straight to ClaimPot, where the player left takes the pot uncontested.
+/// A hand already at ClaimPot (the leaver had folded) is left as it is.
+/// Returns true if the hand was aborted.
+pub(crate) fn abort_short_handed(game_config: &GameConfig, game_state: &mut GameState) -> bool {
+    if game_config.current_players >= MIN_PLAYERS || !is_hand_in_play(game_state) {
+        return false;
+    }
+    game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+    true
+}
+
+/// Whether a hand is under way and hasn't reached ClaimPot yet
+fn is_hand_in_play(game_state: &GameState) -> bool {
+    is_mid_hand(game_state)
+        && !matches!(
+            game_state.texas_state(),
+            TexasHoldEmState::ClaimPot | TexasHoldEmState::Finished
+        )
+}
+
+/// Whether leaving now walks away from a pot that hasn't been paid out yet
+pub(crate) fn leaves_unsettled_hand(game_state: &GameState) -> bool {
+    is_mid_hand(game_state)
+        && game_state.pot_claimed == 0
+        && game_state.texas_state() != TexasHoldEmState::Finished
+}
+
+/// Take a leaving player off the table
+///
+/// A folded player leaving mid-hand also comes off the fold count, so the
+/// number of players still in the hand stays the same. Once the last player
+/// is gone the table is idle again, which is what lets `CloseGame` close it.
+pub(crate) fn vacate_seat(
+    game_config: &mut GameConfig,
+    game_state: &mut GameState,
+    player_list: &mut PlayerList,
+    player_state: &mut PlayerState,
+    seat: u8,
+) {
+    if is_mid_hand(game_state) && player_state.is_folded() {
+        game_state.num_folded_players = game_state.num_folded_players.saturating_sub(1);
+    }
+
+    player_list.remove_player(seat);
+    game_config.current_players = game_config.current_players.saturating_sub(1);
+    player_state.clear();
+
+    if game_config.current_players == 0 {
+        game_state.game_phase = GamePhase::WaitingForPlayers as u8;
+    }
+}
+
 impl PlayerList {
     pub fn remove_player(&mut self, index: u8) {
-        if (index as usize) < (MAX_PLAYERS as usize) {
+        if self.is_seat_occupied(index) {
             self.players[index as usize] = [0u8; 32];
-            self.player_count = self.player_count.saturating_sub(1);
+            self.folded_bitmap &= !(1 << index);
+            self.all_in_bitmap &= !(1 << index);
+            self.count = self.count.saturating_sub(1);
+            self.player_count = self.count;
         }
     }
 }
@@ -128,9 +240,124 @@ impl PlayerState {
         self.is_folded = 0;
         self.current_bet = 0;
         self.revealed_cards_count = 0;
-        self.revealed_cards = [([0u8; 32], [0u8; 32]); HOLE_CARDS_PER_PLAYER as usize];
+        self.revealed_cards = [[0u8; COMPRESSED_G1_SIZE]; HOLE_CARDS_PER_PLAYER as usize];
         self.submitted_hand = 0;
         self.hand_cards = [-1i8; 5];
         self.hand_rank = 0;
+        self.generate_progress = 0;
+        self.has_generated = 0;
+        self.has_submitted = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_game_config, make_game_state, make_player_state, seated_player_list};
+    use crate::utils::players_in_hand;
+
+    #[test]
+    fn test_folded_leaver_keeps_players_in_hand() {
+        let mut game_config = GameConfig::from_bytes(&make_game_config(3, 10)).unwrap();
+        game_config.current_players = 3;
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 0, 0)).unwrap();
+        game_state.num_folded_players = 1;
+        let mut player_list = seated_player_list(3);
+        let mut player_state = PlayerState::from_bytes(&make_player_state([2; 32], 1, 500)).unwrap();
+        player_state.is_folded = 1;
+
+        assert_eq!(players_in_hand(&player_list, game_state.num_folded_players), 2);
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut player_state, 1);
+
+        assert_eq!(game_state.num_folded_players, 0);
+        assert_eq!(players_in_hand(&player_list, game_state.num_folded_players), 2);
+        assert_eq!(player_list.count, 2);
+        assert!(!player_list.is_seat_occupied(1));
+        assert_eq!(game_config.current_players, 2);
+    }
+
+    #[test]
+    fn test_leave_rejects_accounts_of_another_game() {
+        let game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::ClaimPot, 0, 0)).unwrap();
+        let mut player_list = seated_player_list(2);
+        let mut player_state = PlayerState::from_bytes(&make_player_state([2; 32], 1, 500)).unwrap();
+        assert_eq!(
+            assert_own_table(&game_config, &game_state, &player_list, &player_state, &[2; 32]),
+            Ok(())
+        );
+
+        // Another seat's PlayerState
+        assert_eq!(
+            assert_own_table(&game_config, &game_state, &player_list, &player_state, &[1; 32]),
+            Err(PokerError::InvalidAccountData)
+        );
+
+        // The player's PlayerState at another table, paid from this vault
+        player_state.game_id = [9; 32];
+        assert_eq!(
+            assert_own_table(&game_config, &game_state, &player_list, &player_state, &[2; 32]),
+            Err(PokerError::InvalidAccountData)
+        );
+        player_state.game_id = game_config.game_id;
+
+        // Game state or player list of another table
+        game_state.game_id = [9; 32];
+        assert_eq!(
+            assert_own_table(&game_config, &game_state, &player_list, &player_state, &[2; 32]),
+            Err(PokerError::InvalidAccountData)
+        );
+        game_state.game_id = game_config.game_id;
+        player_list.game_id = [9; 32];
+        assert_eq!(
+            assert_own_table(&game_config, &game_state, &player_list, &player_state, &[2; 32]),
+            Err(PokerError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_leaving_before_settlement_is_counted_in_stats() {
+        // A folded player leaving mid-hand walks away from an unpaid pot
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 0, 40)).unwrap();
+        assert!(leaves_unsettled_hand(&game_state));
+
+        // Once the pot is paid, settlement has already counted the hand
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+        game_state.pot_claimed = 1;
+        assert!(!leaves_unsettled_hand(&game_state));
+        game_state.texas_state = TexasHoldEmState::Finished as u8;
+        assert!(!leaves_unsettled_hand(&game_state));
+
+        // Between hands there is nothing to count
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 0, 0)).unwrap();
+        game_state.game_phase = GamePhase::WaitingForPlayers as u8;
+        assert!(!leaves_unsettled_hand(&game_state));
+    }
+
+    #[test]
+    fn test_table_closes_once_everyone_has_left_with_their_chips() {
+        use crate::instructions::close_game::assert_closable;
+
+        // The last hand was settled; both players still hold chips in the vault
+        let mut game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::ClaimPot, 0, 0)).unwrap();
+        game_state.pot_claimed = 1;
+        let mut player_list = seated_player_list(2);
+        assert_eq!(assert_closable(&game_state, &game_config), Err(PokerError::InvalidGamePhase));
+
+        let mut player_state = PlayerState::from_bytes(&make_player_state([1; 32], 0, 1300)).unwrap();
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut player_state, 0);
+        assert_eq!(assert_closable(&game_state, &game_config), Err(PokerError::InvalidGamePhase));
+
+        let mut player_state = PlayerState::from_bytes(&make_player_state([2; 32], 1, 700)).unwrap();
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut player_state, 1);
+        assert_eq!(player_state.chips, 0);
+        assert_eq!(game_state.game_phase(), GamePhase::WaitingForPlayers);
+        assert_eq!(assert_closable(&game_state, &game_config), Ok(()));
     }
 }
@@ -1,11 +1,20 @@
 //! Lock deck instruction - final shuffle round
+//!
+//! Single-transaction variant of LockPart1/Part2. Like Part 1, it fixes the
+//! player's committed lock public keys before any card is locked.
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    error::PokerError,
+    instructions::lock_part1::fix_lock_keys,
+    state::*,
+    utils::{assert_shuffling_step, finish_seat_step},
+};
 
 pub fn process_lock(
     _program_id: &Pubkey,
@@ -23,6 +32,7 @@ pub fn process_lock(
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let deck_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -43,14 +53,17 @@ pub fn process_lock(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Validate state
-    if game_state.game_phase() != GamePhase::Shuffling {
-        return Err(PokerError::InvalidState.into());
-    }
-    if game_state.shuffling_state() != ShufflingState::Locking {
-        return Err(PokerError::InvalidShufflingState.into());
+    let mut player_state = unsafe {
+        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if player_state.player != *player.key() || player_state.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
     }
 
+    // Validate state
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Locking)?;
+
     // Validate turn
     let current_player = player_list.get_player(game_state.current_turn)
         .ok_or(PokerError::NotAPlayer)?;
@@ -58,6 +71,13 @@ pub fn process_lock(
         return Err(PokerError::NotYourTurn.into());
     }
 
+    // Fix the committed lock public keys before any card is locked
+    fix_lock_keys(
+        &mut player_state,
+        unsafe { player_state_acc.borrow_data_unchecked() },
+        game_config.deck_size,
+    )?;
+
     // Use zero-copy mutable reference instead of deserializing onto stack
     let mut deck_state = unsafe {
         DeckStateMut::from_bytes(deck_state_acc.borrow_mut_data_unchecked())
@@ -73,26 +93,23 @@ pub fn process_lock(
         deck_state.set_card_point(i, qx, qy);
     }
 
-    game_state.active_player_count += 1;
-
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all players have locked
-    if game_state.active_player_count >= game_config.max_players {
+    // Check if all players have locked; the first blind is posted next
+    let first_seat = game_config.first_blind_seat(&player_list);
+    if finish_seat_step(&mut game_state, &player_list, game_config.max_players, first_seat) {
         game_state.game_phase = GamePhase::Drawing as u8;
         game_state.drawing_state = DrawingState::Picking as u8;
-        game_state.active_player_count = 0;
-        game_state.current_turn = (game_config.dealer_index + 1) % game_config.max_players;
         msg!("GameStateChanged: Drawing");
-    } else {
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
     }
 
-    // Write back game_state only (deck_state writes go directly to account)
+    // Write back game_state and player_state (deck_state writes go directly to account)
     unsafe {
         game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
             .copy_from_slice(&game_state.to_bytes());
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
     }
 
     msg!("WorkDeckUpdate");
@@ -2,6 +2,11 @@
 //!
 //! Accepts 26 compressed EC points, decompresses them using the syscall,
 //! and stores them in the deck state. Part 2 must follow to complete lock.
+//!
+//! Part 1 also fixes the player's lock commitment: once the per-card lock
+//! public keys sent with `CommitLockKeys` cover the deck, they can't be
+//! changed for the rest of the hand. Reveals are later checked against those
+//! keys.
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
@@ -13,6 +18,7 @@ use crate::{
     crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
     error::PokerError,
     state::*,
+    utils::assert_shuffling_step,
 };
 
 pub fn process_lock_part1(
@@ -38,7 +44,7 @@ pub fn process_lock_part1(
         return Err(PokerError::InvalidSigner.into());
     }
 
-    let _game_config = unsafe {
+    let game_config = unsafe {
         GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -57,14 +63,12 @@ pub fn process_lock_part1(
         PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
+    if player_state.player != *player.key() || player_state.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
 
     // Validate state
-    if game_state.game_phase() != GamePhase::Shuffling {
-        return Err(PokerError::InvalidState.into());
-    }
-    if game_state.shuffling_state() != ShufflingState::Locking {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Locking)?;
 
     // Validate turn
     let current_player = player_list.get_player(game_state.current_turn)
@@ -78,6 +82,13 @@ pub fn process_lock_part1(
         return Err(PokerError::Part1AlreadySubmitted.into());
     }
 
+    // Fix the committed lock public keys before any card is locked
+    fix_lock_keys(
+        &mut player_state,
+        unsafe { player_state_acc.borrow_data_unchecked() },
+        game_config.deck_size,
+    )?;
+
     // Use zero-copy mutable reference for deck state
     let mut deck_state = unsafe {
         DeckStateMut::from_bytes(deck_state_acc.borrow_mut_data_unchecked())
@@ -122,3 +133,75 @@ pub fn process_lock_part1(
     msg!("LockPart1Complete");
     Ok(())
 }
+
+/// Mark the lock public keys committed in the player's account as fixed
+///
+/// Every card of the `deck_size`-card deck needs a valid key. Shared with
+/// the single-shot `Lock`. Once set, `CommitLockKeys` is rejected for the
+/// rest of the hand.
+pub(crate) fn fix_lock_keys(
+    player_state: &mut PlayerState,
+    account: &[u8],
+    deck_size: u8,
+) -> Result<(), PokerError> {
+    let committed = PlayerStateRef::from_bytes(account).ok_or(PokerError::InvalidAccountData)?;
+    if !committed.has_lock_public_keys(deck_size as usize) {
+        return Err(PokerError::LockKeysNotCommitted);
+    }
+    player_state.lock_committed = 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{DECK_SIZE, SHORT_DECK_SIZE};
+    use crate::crypto::bn254::{bn254_g1_compress, bn254_mul};
+    use crate::state::player_state::PLAYER_STATE_ACCOUNT_SIZE;
+
+    fn scalar(value: u16) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[30..].copy_from_slice(&value.to_be_bytes());
+        bytes
+    }
+
+    fn generator_times(value: u16) -> [u8; 64] {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        bn254_mul(&generator, &scalar(value)).unwrap()
+    }
+
+    #[test]
+    fn test_lock_keys_fixed_once_the_deck_is_covered() {
+        let mut player_state = PlayerState::new(0, [1u8; 32], [2u8; 32], 0, 100, [0u8; 32]);
+        let mut account = player_state.to_bytes().to_vec();
+        account.resize(PLAYER_STATE_ACCOUNT_SIZE, 0);
+
+        // Nothing committed yet
+        assert_eq!(
+            fix_lock_keys(&mut player_state, &account, DECK_SIZE as u8),
+            Err(PokerError::LockKeysNotCommitted)
+        );
+
+        // Card i is locked with key i + 1, for the 36 cards of a short deck
+        let keys: Vec<[u8; 32]> = (1..=SHORT_DECK_SIZE as u16)
+            .map(|key| bn254_g1_compress(&generator_times(key)).unwrap())
+            .collect();
+        PlayerStateMut::from_bytes(&mut account)
+            .unwrap()
+            .set_lock_public_keys(0, &keys)
+            .unwrap();
+
+        // Not enough for a full deck, but the whole of a short one
+        assert_eq!(
+            fix_lock_keys(&mut player_state, &account, DECK_SIZE as u8),
+            Err(PokerError::LockKeysNotCommitted)
+        );
+        fix_lock_keys(&mut player_state, &account, SHORT_DECK_SIZE as u8).unwrap();
+        assert_eq!(player_state.lock_committed, 1);
+
+        account[..PLAYER_STATE_SIZE].copy_from_slice(&player_state.to_bytes());
+        assert!(PlayerStateMut::from_bytes(&mut account).unwrap().is_lock_committed());
+    }
+}
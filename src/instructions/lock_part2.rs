@@ -13,6 +13,7 @@ use crate::{
     crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
     error::PokerError,
     state::*,
+    utils::{assert_shuffling_step, finish_seat_step},
 };
 
 pub fn process_lock_part2(
@@ -59,12 +60,7 @@ pub fn process_lock_part2(
     };
 
     // Validate state
-    if game_state.game_phase() != GamePhase::Shuffling {
-        return Err(PokerError::InvalidState.into());
-    }
-    if game_state.shuffling_state() != ShufflingState::Locking {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Locking)?;
 
     // Validate turn
     let current_player = player_list.get_player(game_state.current_turn)
@@ -108,21 +104,15 @@ pub fn process_lock_part2(
     // Reset Part1 flag for next round or next player
     player_state.lock_part1_done = 0;
 
-    // Increment player count
-    game_state.active_player_count += 1;
-
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all players have locked
-    if game_state.active_player_count >= game_config.max_players {
+    // Check if all players have locked; the first blind is posted next
+    let first_seat = game_config.first_blind_seat(&player_list);
+    if finish_seat_step(&mut game_state, &player_list, game_config.max_players, first_seat) {
         game_state.game_phase = GamePhase::Drawing as u8;
         game_state.drawing_state = DrawingState::Picking as u8;
-        game_state.active_player_count = 0;
-        game_state.current_turn = (game_config.dealer_index + 1) % game_config.max_players;
         msg!("GameStateChanged: Drawing");
-    } else {
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
     }
 
     // Write back states
@@ -1,24 +1,31 @@
 //! Map deck instruction - maps EC points to card IDs
+//!
+//! Single-transaction variant of MapDeckPart1/Part2. Points are copied one
+//! card at a time straight into the zero-copy accumulator, so no deck-sized
+//! buffer is ever placed on the stack. A short-deck table maps 36 points,
+//! one per card of its deck (see `deck_card_id`).
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
+    error::PokerError,
+    poker::deck_card_id,
+    state::*,
+};
 
 pub fn process_map_deck(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    // Data: 52 EC points (52 x 64 bytes = 3328 bytes)
-    if data.len() < DECK_SIZE * 64 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -27,6 +34,17 @@ pub fn process_map_deck(
         return Err(PokerError::InvalidSigner.into());
     }
 
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Data: one EC point per card (52 x 64 bytes = 3328 bytes, 36 for a short deck)
+    let deck_size = game_config.deck_size;
+    if data.len() < deck_size as usize * 64 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
     let mut game_state = unsafe {
         GameState::from_bytes(game_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
@@ -60,12 +78,15 @@ pub fn process_map_deck(
     };
 
     // Update accumulator with deck mapping (direct writes to account data)
-    for i in 0..DECK_SIZE {
-        let offset = i * 64;
-        // Read coordinates from instruction data
-        let qx = unsafe { &*(data[offset..].as_ptr() as *const [u8; 32]) };
-        let qy = unsafe { &*(data[offset + 32..].as_ptr() as *const [u8; 32]) };
-        accumulator.set_deck_mapping(i, qx, qy);
+    map_uncompressed_cards(&mut accumulator, data, deck_size);
+
+    // The completed mapping must be a permutation of distinct card points
+    let accumulator = unsafe {
+        AccumulatorStateRef::from_bytes(accumulator_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if !verify_deck_mapping(&accumulator, deck_size) {
+        return Err(PokerError::InvalidAccountData.into());
     }
 
     game_state.is_deck_submitted = 1;
@@ -82,3 +103,116 @@ pub fn process_map_deck(
     msg!("DeckSubmitted");
     Ok(())
 }
+
+/// Store the `deck_size` uncompressed (x || y) points in `data` as the deck mapping
+fn map_uncompressed_cards(accumulator: &mut AccumulatorStateMut, data: &[u8], deck_size: u8) {
+    for i in 0..deck_size as usize {
+        let offset = i * 64;
+        // Read coordinates from instruction data
+        let qx = unsafe { &*(data[offset..].as_ptr() as *const [u8; 32]) };
+        let qy = unsafe { &*(data[offset + 32..].as_ptr() as *const [u8; 32]) };
+        accumulator.set_deck_mapping(deck_card_id(deck_size, i) as usize, qx, qy);
+    }
+}
+
+/// Decompress `count` points from `data` and store them as deck positions
+/// `first_card..first_card + count` of the deck mapping
+pub(crate) fn map_compressed_cards(
+    accumulator: &mut AccumulatorStateMut,
+    data: &[u8],
+    first_card: usize,
+    count: usize,
+    deck_size: u8,
+) -> Result<(), PokerError> {
+    for i in 0..count {
+        let offset = i * COMPRESSED_POINT_SIZE;
+
+        // Read compressed point from instruction data
+        let compressed: &[u8; COMPRESSED_G1_SIZE] = unsafe {
+            &*(data[offset..].as_ptr() as *const [u8; COMPRESSED_G1_SIZE])
+        };
+
+        // Decompress using syscall
+        let decompressed = bn254_g1_decompress(compressed)
+            .map_err(|_| PokerError::DecompressionFailed)?;
+
+        // Store in accumulator deck mapping (split into x and y)
+        let qx = unsafe { &*(decompressed[..32].as_ptr() as *const [u8; 32]) };
+        let qy = unsafe { &*(decompressed[32..].as_ptr() as *const [u8; 32]) };
+        accumulator.set_deck_mapping(deck_card_id(deck_size, first_card + i) as usize, qx, qy);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::bn254::{bn254_g1_compress, bn254_mul};
+
+    /// Card points k * G for k = 1..=52
+    fn deck_points() -> Vec<[u8; 64]> {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        (1..=DECK_SIZE as u8)
+            .map(|k| {
+                let mut scalar = [0u8; 32];
+                scalar[31] = k;
+                bn254_mul(&generator, &scalar).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_single_shot_matches_two_part_mapping() {
+        let points = deck_points();
+
+        let uncompressed: Vec<u8> = points.iter().flatten().copied().collect();
+        let mut single = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut acc = AccumulatorStateMut::from_bytes(&mut single).unwrap();
+        map_uncompressed_cards(&mut acc, &uncompressed, DECK_SIZE as u8);
+
+        let compressed: Vec<u8> = points
+            .iter()
+            .flat_map(|point| bn254_g1_compress(point).unwrap())
+            .collect();
+        let split = CARDS_PER_PART * COMPRESSED_POINT_SIZE;
+        let mut two_part = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        {
+            let mut acc = AccumulatorStateMut::from_bytes(&mut two_part).unwrap();
+            let rest = DECK_SIZE - CARDS_PER_PART;
+            let full = DECK_SIZE as u8;
+            map_compressed_cards(&mut acc, &compressed[..split], 0, CARDS_PER_PART, full).unwrap();
+            map_compressed_cards(&mut acc, &compressed[split..], CARDS_PER_PART, rest, full).unwrap();
+        }
+
+        assert_eq!(single, two_part);
+        assert!(verify_deck_mapping(&AccumulatorStateRef::from_bytes(&single).unwrap(), DECK_SIZE as u8));
+    }
+
+    #[test]
+    fn test_short_deck_maps_only_six_and_up() {
+        use crate::poker::{get_card_name, CardValue};
+
+        let short = SHORT_DECK_SIZE as u8;
+        let points = deck_points();
+        let uncompressed: Vec<u8> = points[..SHORT_DECK_SIZE].iter().flatten().copied().collect();
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        map_uncompressed_cards(&mut acc, &uncompressed, short);
+
+        let acc = AccumulatorStateRef::from_bytes(&data).unwrap();
+        assert!(verify_deck_mapping(&acc, short));
+        // A 36-point mapping doesn't pass as a full deck
+        assert!(!verify_deck_mapping(&acc, DECK_SIZE as u8));
+
+        // Every mapped point identifies an Ace or a Six and up
+        for point in &points[..SHORT_DECK_SIZE] {
+            let qx: [u8; 32] = point[..32].try_into().unwrap();
+            let qy: [u8; 32] = point[32..].try_into().unwrap();
+            let id = acc.find_card_by_point(&qx, &qy).unwrap();
+            let (value, _) = get_card_name(id as i8);
+            assert!(value == CardValue::Ace || value >= CardValue::Six, "card {id}");
+        }
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Stores the original deck points in the accumulator for card identification.
 //! Accepts 26 compressed EC points, decompresses them, and stores in accumulator.
+//! On a short-deck table these are the first 26 of its 36 cards.
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
@@ -10,8 +11,8 @@ use pinocchio::{
 
 use crate::{
     constants::{CARDS_PER_PART, COMPRESSED_POINT_SIZE},
-    crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
     error::PokerError,
+    instructions::map_deck::map_compressed_cards,
     state::*,
 };
 
@@ -28,6 +29,7 @@ pub fn process_map_deck_part1(
 
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -37,6 +39,11 @@ pub fn process_map_deck_part1(
         return Err(PokerError::InvalidSigner.into());
     }
 
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
     let mut game_state = unsafe {
         GameState::from_bytes(game_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
@@ -82,23 +89,7 @@ pub fn process_map_deck_part1(
     };
 
     // Decompress and store cards 0-25 in accumulator deck mapping
-    for i in 0..CARDS_PER_PART {
-        let offset = i * COMPRESSED_POINT_SIZE;
-
-        // Read compressed point from instruction data
-        let compressed: &[u8; COMPRESSED_G1_SIZE] = unsafe {
-            &*(data[offset..].as_ptr() as *const [u8; COMPRESSED_G1_SIZE])
-        };
-
-        // Decompress using syscall
-        let decompressed = bn254_g1_decompress(compressed)
-            .map_err(|_| PokerError::DecompressionFailed)?;
-
-        // Store in accumulator deck mapping (split into x and y)
-        let qx = unsafe { &*(decompressed[..32].as_ptr() as *const [u8; 32]) };
-        let qy = unsafe { &*(decompressed[32..].as_ptr() as *const [u8; 32]) };
-        accumulator.set_deck_mapping(i, qx, qy);
-    }
+    map_compressed_cards(&mut accumulator, data, 0, CARDS_PER_PART, game_config.deck_size)?;
 
     // Mark that we're in the middle of deck mapping (reuse a flag)
     // For now, use shuffle_part1_done as indicator that MapDeckPart1 was done
@@ -1,6 +1,6 @@
-//! Map deck part 2 instruction (cards 26-51)
+//! Map deck part 2 instruction (cards 26-51, or 26-35 of a short deck)
 //!
-//! Completes the deck mapping started by Part1. Stores remaining 26 cards
+//! Completes the deck mapping started by Part1. Stores the remaining cards
 //! in the accumulator and sets deck_submitted flag.
 
 use pinocchio::{
@@ -9,9 +9,9 @@ use pinocchio::{
 };
 
 use crate::{
-    constants::{CARDS_PER_PART, COMPRESSED_POINT_SIZE, DECK_SIZE},
-    crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
+    constants::{CARDS_PER_PART, COMPRESSED_POINT_SIZE},
     error::PokerError,
+    instructions::map_deck::map_compressed_cards,
     state::*,
 };
 
@@ -20,14 +20,9 @@ pub fn process_map_deck_part2(
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    // Data: 26 compressed EC points (26 x 33 bytes = 858 bytes)
-    let expected_size = (DECK_SIZE - CARDS_PER_PART) * COMPRESSED_POINT_SIZE;
-    if data.len() < expected_size {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -37,6 +32,18 @@ pub fn process_map_deck_part2(
         return Err(PokerError::InvalidSigner.into());
     }
 
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Data: the rest of the deck compressed (26 x 33 bytes = 858 bytes, 10 for a short deck)
+    let deck_size = game_config.deck_size;
+    let remaining_cards = (deck_size as usize).saturating_sub(CARDS_PER_PART);
+    if data.len() < remaining_cards * COMPRESSED_POINT_SIZE {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
     let mut game_state = unsafe {
         GameState::from_bytes(game_state_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
@@ -80,25 +87,16 @@ pub fn process_map_deck_part2(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Decompress and store cards 26-51 in accumulator deck mapping
-    let remaining_cards = DECK_SIZE - CARDS_PER_PART;
-    for i in 0..remaining_cards {
-        let card_index = CARDS_PER_PART + i;
-        let offset = i * COMPRESSED_POINT_SIZE;
-
-        // Read compressed point from instruction data
-        let compressed: &[u8; COMPRESSED_G1_SIZE] = unsafe {
-            &*(data[offset..].as_ptr() as *const [u8; COMPRESSED_G1_SIZE])
-        };
-
-        // Decompress using syscall
-        let decompressed = bn254_g1_decompress(compressed)
-            .map_err(|_| PokerError::DecompressionFailed)?;
-
-        // Store in accumulator deck mapping (split into x and y)
-        let qx = unsafe { &*(decompressed[..32].as_ptr() as *const [u8; 32]) };
-        let qy = unsafe { &*(decompressed[32..].as_ptr() as *const [u8; 32]) };
-        accumulator.set_deck_mapping(card_index, qx, qy);
+    // Decompress and store the cards from position 26 on in accumulator deck mapping
+    map_compressed_cards(&mut accumulator, data, CARDS_PER_PART, remaining_cards, deck_size)?;
+
+    // The completed mapping must be a permutation of distinct card points
+    let accumulator = unsafe {
+        AccumulatorStateRef::from_bytes(accumulator_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if !verify_deck_mapping(&accumulator, deck_size) {
+        return Err(PokerError::InvalidAccountData.into());
     }
 
     // Reset Part1 flag
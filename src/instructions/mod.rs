@@ -3,6 +3,7 @@
 pub mod initialize_game;
 pub mod join_game;
 pub mod generate;
+pub mod generate_chunk;
 pub mod map_deck;
 pub mod map_deck_part1;
 pub mod map_deck_part2;
@@ -14,23 +15,34 @@ pub mod lock_part1;
 pub mod lock_part2;
 pub mod draw;
 pub mod reveal;
+pub mod reveal_combined;
 pub mod place_blind;
+pub mod straddle;
 pub mod bet;
 pub mod fold;
 pub mod deal_community;
 pub mod open_community_card;
 pub mod open;
 pub mod submit_best_hand;
+pub mod muck;
 pub mod claim_pot;
 pub mod start_next_game;
 pub mod leave;
 pub mod slash;
 pub mod close_game;
+pub mod cancel_game;
+pub mod commit_lock_keys;
+pub mod init_player_stats;
 pub mod test_compression;
+#[cfg(feature = "benchmark")]
+pub mod benchmark;
+#[cfg(feature = "test-deck")]
+pub mod set_deck;
 
 pub use initialize_game::*;
 pub use join_game::*;
 pub use generate::*;
+pub use generate_chunk::*;
 pub use map_deck::*;
 pub use map_deck_part1::*;
 pub use map_deck_part2::*;
@@ -42,19 +54,29 @@ pub use lock_part1::*;
 pub use lock_part2::*;
 pub use draw::*;
 pub use reveal::*;
+pub use reveal_combined::*;
 pub use place_blind::*;
+pub use straddle::*;
 pub use bet::*;
 pub use fold::*;
 pub use deal_community::*;
 pub use open_community_card::*;
 pub use open::*;
 pub use submit_best_hand::*;
+pub use muck::*;
 pub use claim_pot::*;
 pub use start_next_game::*;
 pub use leave::*;
 pub use slash::*;
 pub use close_game::*;
+pub use cancel_game::*;
+pub use commit_lock_keys::*;
+pub use init_player_stats::*;
 pub use test_compression::*;
+#[cfg(feature = "benchmark")]
+pub use benchmark::*;
+#[cfg(feature = "test-deck")]
+pub use set_deck::*;
 
 /// Helper to get next account from iterator
 pub fn next_account_info<'a>(
@@ -0,0 +1,251 @@
+//! Muck instruction - a beaten player gives up at showdown without showing
+//!
+//! At showdown a player who sees they are beaten may muck instead of
+//! showing. On their turn to open hole cards they skip opening, so the opens
+//! still required drop by their two cards and the cards stay hidden; on
+//! their turn in SubmitBest the submissions still required drop by one and
+//! their hand is never evaluated. Either way they are folded out of the pot.
+//! On an auto-pay table the muck that ends showdown also pays the pot out,
+//! reading the same trailing accounts as `SubmitBestHand`.
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar}, ProgramResult,
+};
+
+use crate::{
+    error::PokerError,
+    instructions::{
+        open::advance_opens,
+        submit_best_hand::{advance_submissions, assert_not_submitted, settle_if_auto_pay},
+    },
+    state::*,
+    utils::assert_is_turn,
+};
+
+pub fn process_muck(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let mut iter = accounts.iter();
+    let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !player.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_state = unsafe {
+        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Validate state: opening hole cards or submitting the best hand
+    if !matches!(
+        game_state.texas_state(),
+        TexasHoldEmState::Revealing | TexasHoldEmState::SubmitBest
+    ) {
+        return Err(PokerError::InvalidTexasState.into());
+    }
+
+    // Validate turn
+    assert_is_turn(&game_state, &player_list, player.key())?;
+    if player_state.player != *player.key() || player_state.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    assert_not_submitted(&player_state)?;
+
+    apply_muck(&mut game_state, &game_config, &mut player_list, &mut player_state);
+
+    let clock = Clock::get()?;
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    // Written before settling so the payout sees this seat as folded
+    unsafe {
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
+    }
+    msg!("PlayerMucked");
+
+    // The last muck can end showdown as well as the last submission
+    settle_if_auto_pay(
+        program_id,
+        game_config_acc,
+        &game_config,
+        &mut game_state,
+        &player_list,
+        &mut iter,
+    )?;
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+    }
+    Ok(())
+}
+
+/// Fold the player out of the showdown and move on to the next opener or submitter
+///
+/// A hole card the player already opened no longer counts toward the opens
+/// required.
+fn apply_muck(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &mut PlayerList,
+    player_state: &mut PlayerState,
+) {
+    player_state.is_folded = 1;
+    game_state.num_folded_players += 1;
+    player_list.mark_folded(game_state.current_turn);
+    if game_state.texas_state() == TexasHoldEmState::Revealing {
+        game_state.player_cards_opened =
+            game_state.player_cards_opened.saturating_sub(player_state.revealed_cards_count);
+        advance_opens(game_state, game_config, player_list, true);
+    } else {
+        advance_submissions(game_state, game_config, player_list);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seated_player_list;
+
+    #[test]
+    fn test_last_to_act_mucks_after_winning_hand_shown() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+
+        // Seats 0 and 1 have shown; seat 2 is beaten and mucks
+        game_state.num_submitted_hands = 2;
+        game_state.current_turn = 2;
+        let mut player_state = PlayerState::new(0, [0u8; 32], [3u8; 32], 2, 1000, [0u8; 32]);
+        assert_eq!(assert_not_submitted(&player_state), Ok(()));
+        apply_muck(&mut game_state, &game_config, &mut seated_player_list(3), &mut player_state);
+
+        // Nobody is left to submit, so the pot resolves between the two shown hands
+        assert!(player_state.is_folded());
+        assert!(!player_state.has_submitted());
+        assert_eq!(game_state.num_folded_players, 1);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::ClaimPot);
+        assert_eq!(game_state.current_turn, game_config.dealer_seat(&seated_player_list(3)));
+    }
+
+    #[test]
+    fn test_muck_passes_turn_to_next_submitter() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+        game_state.current_turn = 0;
+
+        let mut player_state = PlayerState::new(0, [0u8; 32], [1u8; 32], 0, 1000, [0u8; 32]);
+        apply_muck(&mut game_state, &game_config, &mut seated_player_list(3), &mut player_state);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::SubmitBest);
+        assert_eq!(game_state.current_turn, 1);
+    }
+
+    #[test]
+    fn test_muck_skips_seat_folded_earlier() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+        game_state.num_folded_players = 1;
+        game_state.current_turn = 0;
+
+        // Seat 1 folded on the turn, so seat 2 is the next to submit
+        let mut player_list = seated_player_list(4);
+        player_list.mark_folded(1);
+        let mut player_state = PlayerState::new(0, [0u8; 32], [1u8; 32], 0, 1000, [0u8; 32]);
+        apply_muck(&mut game_state, &game_config, &mut player_list, &mut player_state);
+        assert_eq!(game_state.current_turn, 2);
+        assert!(player_list.has_folded(0));
+    }
+
+    #[test]
+    fn test_muck_before_opening_drops_required_opens() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Revealing as u8;
+        game_state.betting_round_state = BettingRoundState::Showdown as u8;
+        let mut player_list = seated_player_list(3);
+
+        // Seat 0 has opened both cards; seat 1 is beaten and mucks instead of opening
+        game_state.player_cards_opened = 2;
+        game_state.current_turn = 1;
+        let mut player_state = PlayerState::new(0, [0u8; 32], [2u8; 32], 1, 1000, [0u8; 32]);
+        apply_muck(&mut game_state, &game_config, &mut player_list, &mut player_state);
+        assert!(player_list.has_folded(1));
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Revealing);
+        assert_eq!(game_state.current_turn, 2);
+
+        // Seat 2 opens both cards: four opens were enough, not six
+        game_state.player_cards_opened += 2;
+        advance_opens(&mut game_state, &game_config, &player_list, true);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::SubmitBest);
+        assert_eq!(game_state.current_turn, 0);
+    }
+
+    #[test]
+    fn test_last_opener_mucks_after_opening_one_card() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Revealing as u8;
+        game_state.betting_round_state = BettingRoundState::Showdown as u8;
+        let mut player_list = seated_player_list(3);
+
+        // Seats 0 and 1 opened both cards, seat 2 one before mucking
+        game_state.player_cards_opened = 5;
+        game_state.current_turn = 2;
+        let mut player_state = PlayerState::new(0, [0u8; 32], [3u8; 32], 2, 1000, [0u8; 32]);
+        player_state.revealed_cards_count = 1;
+        apply_muck(&mut game_state, &game_config, &mut player_list, &mut player_state);
+
+        // Its opened card doesn't count; the two seats left go on to submit
+        assert_eq!(game_state.player_cards_opened, 4);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::SubmitBest);
+        assert_eq!(game_state.current_turn, 0);
+    }
+
+    #[test]
+    fn test_last_muck_triggers_auto_pay() {
+        use crate::instructions::submit_best_hand::auto_pay_due;
+
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        game_config.auto_pay = 1;
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+        game_state.num_submitted_hands = 2;
+        game_state.current_turn = 2;
+
+        // The muck that leaves nobody to submit pays out like a last submission
+        let mut player_state = PlayerState::new(0, [0u8; 32], [3u8; 32], 2, 1000, [0u8; 32]);
+        apply_muck(&mut game_state, &game_config, &mut seated_player_list(3), &mut player_state);
+        assert!(auto_pay_due(&game_state, &game_config));
+
+        // Without the flag the pot waits for ClaimPot
+        game_config.auto_pay = 0;
+        assert!(!auto_pay_due(&game_state, &game_config));
+    }
+}
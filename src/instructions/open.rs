@@ -8,21 +8,17 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, crypto::bn254::bn254_mul, error::PokerError, state::*};
+use crate::{constants::*, crypto::bn254::{apply_inverse_key, bn254_g1_compress}, error::PokerError, state::*, utils::{assert_card_index, assert_is_turn, assert_not_finished, first_in_hand_from, get_next_turn, players_in_hand}};
 
 pub fn process_open(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    // Data: inv_key(32) + index(1) = 33 bytes
-    if data.len() < 33 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    let mut inv_key = [0u8; 32];
-    inv_key.copy_from_slice(&data[0..32]);
-    let index = data[32];
+    // Data: inv_key(32) + index(1) = 33 bytes, or two such pairs (66 bytes)
+    // to open both hole cards in one transaction
+    let (pairs, num_pairs) = parse_open_pairs(data)?;
+    let pairs = &pairs[..num_pairs];
 
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -69,75 +65,30 @@ pub fn process_open(
     };
 
     // Validate state
+    assert_not_finished(game_state.game_phase())?;
     if game_state.betting_round_state() != BettingRoundState::Showdown {
         return Err(PokerError::InvalidBettingState.into());
     }
 
     // Validate turn
-    let current_player = player_list.get_player(game_state.current_turn)
-        .ok_or(PokerError::NotAPlayer)?;
-    if current_player != player.key() {
-        return Err(PokerError::NotYourTurn.into());
-    }
+    assert_is_turn(&game_state, &player_list, player.key())?;
 
-    // Cannot open community cards here
-    if community_cards.is_community_card(index) {
-        return Err(PokerError::NotCommunityCard.into());
-    }
-
-    // Validate player hasn't opened 2 cards already
-    if player_state.revealed_cards_count >= HOLE_CARDS_PER_PLAYER {
-        return Err(PokerError::CannotDrawMoreCards.into());
+    // Only the signer's own hole cards can be opened
+    if player_state.player != *player.key() || player_state.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
     }
 
-    // Get current card point (zero-copy reference)
-    let (qx, qy) = deck_state.get_card_point(index as usize);
+    validate_open_indices(&player_state, &community_cards, pairs)?;
 
-    // Combine point coordinates for syscall
-    let mut point = [0u8; 64];
-    point[..32].copy_from_slice(qx);
-    point[32..].copy_from_slice(qy);
+    open_hole_cards(&mut deck_state, &mut player_state, pairs)?;
 
-    // Apply decryption using bn254 syscall: new_point = inv_key * point
-    let decrypted = bn254_mul(&point, &inv_key)
-        .map_err(|_| PokerError::ECOperationFailed)?;
-
-    // Split result back into coordinates
-    let mut decrypted_x = [0u8; 32];
-    let mut decrypted_y = [0u8; 32];
-    decrypted_x.copy_from_slice(&decrypted[..32]);
-    decrypted_y.copy_from_slice(&decrypted[32..]);
-
-    // Update deck (direct write to account data)
-    deck_state.set_card_point(index as usize, &decrypted_x, &decrypted_y);
-    deck_state.clear_card_owner(index as usize);
-
-    // Update player state
-    let revealed_idx = player_state.revealed_cards_count as usize;
-    player_state.revealed_cards[revealed_idx].0.copy_from_slice(&decrypted_x);
-    player_state.revealed_cards[revealed_idx].1.copy_from_slice(&decrypted_y);
-    player_state.revealed_cards_count += 1;
-
-    game_state.player_cards_opened += 1;
+    game_state.player_cards_opened += num_pairs as u8;
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all players have opened their cards
-    let players_in_play = game_config.max_players - game_state.num_folded_players;
-    let total_cards_needed = players_in_play * HOLE_CARDS_PER_PLAYER;
-
-    if game_state.player_cards_opened >= total_cards_needed {
-        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-        msg!("TexasHoldEmStateChanged: SubmitBest");
-    } else if player_state.revealed_cards_count >= HOLE_CARDS_PER_PLAYER {
-        // This player is done, move to next
-        game_state.current_turn = next_active_player(
-            game_state.current_turn,
-            game_config.max_players,
-        );
-    }
+    let seat_done = player_state.revealed_cards_count >= HOLE_CARDS_PER_PLAYER;
+    advance_opens(&mut game_state, &game_config, &player_list, seat_done);
 
     // Write back game_state and player_state
     // Note: deck_state is already using zero-copy so writes go directly to account
@@ -152,6 +103,256 @@ pub fn process_open(
     Ok(())
 }
 
-fn next_active_player(current: u8, max: u8) -> u8 {
-    (current + 1) % max
+/// Move to SubmitBest once every seat still in has opened both hole cards
+///
+/// Until then the turn passes on only when `seat_done`: the seat on turn has
+/// opened its second card or mucked.
+pub(crate) fn advance_opens(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    seat_done: bool,
+) {
+    let players_in_play = players_in_hand(player_list, game_state.num_folded_players);
+    let total_cards_needed = players_in_play * HOLE_CARDS_PER_PLAYER;
+
+    if game_state.player_cards_opened >= total_cards_needed {
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+        game_state.current_turn =
+            first_in_hand_from(player_list, game_config.max_players, game_config.dealer_index + 3);
+        msg!("TexasHoldEmStateChanged: SubmitBest");
+    } else if seat_done {
+        game_state.current_turn =
+            get_next_turn(game_state.current_turn, player_list, game_config.max_players);
+    }
+}
+
+/// An `(inv_key, card_index)` pair for one hole card
+type OpenPair = ([u8; 32], u8);
+
+/// Parse one or two `inv_key(32) + index(1)` pairs from instruction data
+fn parse_open_pairs(data: &[u8]) -> Result<([OpenPair; 2], usize), ProgramError> {
+    let num_pairs = match data.len() {
+        len if len >= 66 => 2,
+        len if len >= 33 => 1,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    let mut pairs = [([0u8; 32], 0u8); 2];
+    for (i, pair) in pairs.iter_mut().take(num_pairs).enumerate() {
+        let chunk = &data[i * 33..(i + 1) * 33];
+        pair.0.copy_from_slice(&chunk[..32]);
+        pair.1 = chunk[32];
+        assert_card_index(pair.1)?;
+    }
+    Ok((pairs, num_pairs))
+}
+
+/// Check that the board is complete and every index is one of the caller's
+/// unopened hole cards
+fn validate_open_indices(
+    player_state: &PlayerState,
+    community_cards: &CommunityCards,
+    pairs: &[OpenPair],
+) -> ProgramResult {
+    // Hands are evaluated 5-of-7, so the whole board must be open first
+    if !community_cards.is_board_complete() {
+        return Err(PokerError::InvalidCommunityCardsState.into());
+    }
+
+    // Validate player won't open more than 2 cards
+    if player_state.revealed_cards_count as usize + pairs.len() > HOLE_CARDS_PER_PLAYER as usize {
+        return Err(PokerError::CannotDrawMoreCards.into());
+    }
+
+    for (i, (_, index)) in pairs.iter().enumerate() {
+        // Cannot open community cards here
+        if community_cards.is_community_card(*index) {
+            return Err(PokerError::NotCommunityCard.into());
+        }
+        if !player_state.hole_cards.contains(index) {
+            return Err(PokerError::InvalidCardIndex.into());
+        }
+        if pairs[..i].iter().any(|(_, other)| other == index) {
+            return Err(PokerError::InvalidCardIndex.into());
+        }
+    }
+    Ok(())
+}
+
+/// Decrypt each card with its inverse key and record it as revealed
+fn open_hole_cards(
+    deck_state: &mut DeckStateMut,
+    player_state: &mut PlayerState,
+    pairs: &[OpenPair],
+) -> ProgramResult {
+    for (inv_key, index) in pairs {
+        let index = *index as usize;
+
+        // Get current card point (zero-copy reference)
+        let (qx, qy) = deck_state.get_card_point(index);
+
+        // Combine point coordinates for syscall
+        let mut point = [0u8; 64];
+        point[..32].copy_from_slice(qx);
+        point[32..].copy_from_slice(qy);
+
+        // Apply decryption using bn254 syscall: new_point = inv_key * point
+        let decrypted = apply_inverse_key(&point, inv_key)?;
+
+        // Split result back into coordinates
+        let mut decrypted_x = [0u8; 32];
+        let mut decrypted_y = [0u8; 32];
+        decrypted_x.copy_from_slice(&decrypted[..32]);
+        decrypted_y.copy_from_slice(&decrypted[32..]);
+
+        // Update deck (direct write to account data)
+        deck_state.set_card_point(index, &decrypted_x, &decrypted_y);
+        deck_state.clear_card_owner(index);
+
+        // Update player state (stored compressed, decompressed on demand)
+        let revealed_idx = player_state.revealed_cards_count as usize;
+        player_state.revealed_cards[revealed_idx] = bn254_g1_compress(&decrypted)
+            .map_err(|_| PokerError::ECOperationFailed)?;
+        player_state.revealed_cards_count += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_deck_state, make_player_state, TEST_GAME_ID};
+    use crate::crypto::bn254::bn254_mul;
+
+    fn scalar(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    fn two_pair_data(first: u8, second: u8) -> [u8; 66] {
+        let mut data = [0u8; 66];
+        data[..32].copy_from_slice(&scalar(2));
+        data[32] = first;
+        data[33..65].copy_from_slice(&scalar(2));
+        data[65] = second;
+        data
+    }
+
+    fn player_holding(cards: [u8; 2]) -> PlayerState {
+        let mut player_state =
+            PlayerState::from_bytes(&make_player_state([1u8; 32], 0, 1000)).unwrap();
+        player_state.hole_cards = cards;
+        player_state.hole_cards_count = 2;
+        player_state
+    }
+
+    #[test]
+    fn test_parse_open_pairs() {
+        assert_eq!(parse_open_pairs(&[0u8; 32]), Err(ProgramError::InvalidInstructionData));
+
+        let (_, num_pairs) = parse_open_pairs(&[0u8; 33]).unwrap();
+        assert_eq!(num_pairs, 1);
+
+        let (pairs, num_pairs) = parse_open_pairs(&two_pair_data(50, 51)).unwrap();
+        assert_eq!(num_pairs, 2);
+        assert_eq!(pairs[0], (scalar(2), 50));
+        assert_eq!(pairs[1], (scalar(2), 51));
+
+        // Index 52 is past the end of the deck
+        assert_eq!(
+            parse_open_pairs(&two_pair_data(51, 52)),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+    }
+
+    fn full_board() -> CommunityCards {
+        let mut community_cards = CommunityCards::new(0, TEST_GAME_ID);
+        for index in 0u8..5 {
+            community_cards.add_card(index);
+            community_cards.add_opened_card(&[index + 1; 32], &[0u8; 32], index as i8);
+        }
+        community_cards
+    }
+
+    #[test]
+    fn test_open_rejected_before_board_complete() {
+        let player_state = player_holding([50, 51]);
+        let (pairs, _) = parse_open_pairs(&two_pair_data(50, 51)).unwrap();
+
+        // Turn is open but the river is not
+        let mut community_cards = CommunityCards::new(0, TEST_GAME_ID);
+        for index in 0u8..4 {
+            community_cards.add_card(index);
+            community_cards.add_opened_card(&[index + 1; 32], &[0u8; 32], index as i8);
+        }
+        assert_eq!(
+            validate_open_indices(&player_state, &community_cards, &pairs[..1]),
+            Err(PokerError::InvalidCommunityCardsState.into())
+        );
+
+        community_cards.add_card(4);
+        community_cards.add_opened_card(&[5u8; 32], &[0u8; 32], 4);
+        assert!(validate_open_indices(&player_state, &community_cards, &pairs[..1]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_open_indices() {
+        let community_cards = full_board();
+        let player_state = player_holding([50, 51]);
+        let (pairs, _) = parse_open_pairs(&two_pair_data(50, 51)).unwrap();
+
+        assert!(validate_open_indices(&player_state, &community_cards, &pairs).is_ok());
+
+        // Someone else's card
+        let (other, _) = parse_open_pairs(&two_pair_data(50, 49)).unwrap();
+        assert_eq!(
+            validate_open_indices(&player_state, &community_cards, &other),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+
+        // Same card twice
+        let (twice, _) = parse_open_pairs(&two_pair_data(51, 51)).unwrap();
+        assert_eq!(
+            validate_open_indices(&player_state, &community_cards, &twice),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+
+        // One card already opened leaves room for only one more
+        let mut half_open = player_holding([50, 51]);
+        half_open.revealed_cards_count = 1;
+        assert_eq!(
+            validate_open_indices(&half_open, &community_cards, &pairs),
+            Err(PokerError::CannotDrawMoreCards.into())
+        );
+        assert!(validate_open_indices(&half_open, &community_cards, &pairs[1..]).is_ok());
+    }
+
+    #[test]
+    fn test_open_both_hole_cards_at_once() {
+        // Generator point (1, 2)
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let expected = bn254_mul(&generator, &scalar(2)).unwrap();
+
+        let mut deck_data = make_deck_state();
+        let mut deck_state = DeckStateMut::from_bytes(&mut deck_data).unwrap();
+        for index in [50usize, 51] {
+            deck_state.set_card_point_bytes(index, &generator);
+            deck_state.set_card_owner(index, &[1u8; 32]);
+        }
+
+        let mut player_state = player_holding([50, 51]);
+        let (pairs, num_pairs) = parse_open_pairs(&two_pair_data(50, 51)).unwrap();
+        open_hole_cards(&mut deck_state, &mut player_state, &pairs[..num_pairs]).unwrap();
+
+        assert_eq!(player_state.revealed_cards_count, 2);
+        for (i, index) in [50usize, 51].into_iter().enumerate() {
+            assert_eq!(deck_state.get_card_point_bytes(index), &expected);
+            assert!(!deck_state.card_has_owner(index));
+            assert_eq!(player_state.revealed_card_point(i), Some(expected));
+        }
+    }
 }
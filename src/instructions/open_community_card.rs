@@ -8,21 +8,24 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{crypto::bn254::bn254_mul, error::PokerError, state::*};
+use crate::{
+    crypto::bn254::apply_inverse_key,
+    error::PokerError,
+    instructions::{
+        fold::finish_betting_round,
+        reveal::{parse_reveal_batch, reveals_needed},
+    },
+    state::*,
+    utils::first_to_bet_from,
+};
 
 pub fn process_open_community_card(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    // Data: inv_key(32) + index(1) = 33 bytes
-    if data.len() < 33 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    let mut inv_key = [0u8; 32];
-    inv_key.copy_from_slice(&data[0..32]);
-    let index = data[32];
+    // Data: inv_key(32) + index(1) per card in the reveal batch
+    // (three pairs when the whole flop was dealt with DealFlop)
 
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -31,6 +34,7 @@ pub fn process_open_community_card(
     let deck_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let community_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -57,105 +61,308 @@ pub fn process_open_community_card(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    // Validate that the opener is the card owner (the dealer who dealt this card)
-    let card_owner = deck_state.get_card_owner(index as usize);
-    if card_owner != player.key() {
-        return Err(PokerError::NotCardOwner.into());
-    }
+    // Use zero-copy reference instead of deserializing onto stack
+    let accumulator = unsafe {
+        AccumulatorStateRef::from_bytes(accumulator_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
 
-    // Validate this is a community card
-    if !community_cards.is_community_card(index) {
-        return Err(PokerError::NotCommunityCard.into());
-    }
+    let (pairs, num_pairs) = parse_reveal_batch(data, &game_state)?;
+    let pairs = &pairs[..num_pairs];
+
+    // Only the cards currently being revealed may be opened, and only once
+    // every other player has applied their reveal
+    validate_open_ready(&game_state, &game_config, &player_list, pairs[0].1)?;
+
+    for (inv_key, index) in pairs {
+        let index = *index as usize;
+
+        // Validate that the opener is the card owner (the dealer who dealt this card)
+        if deck_state.get_card_owner(index) != player.key() {
+            return Err(PokerError::NotCardOwner.into());
+        }
+
+        // Validate this is a community card
+        if !community_cards.is_community_card(index as u8) {
+            return Err(PokerError::NotCommunityCard.into());
+        }
 
-    // Get current card point (zero-copy reference)
-    let (qx, qy) = deck_state.get_card_point(index as usize);
+        // Get current card point (zero-copy reference)
+        let (qx, qy) = deck_state.get_card_point(index);
 
-    // Combine point coordinates for syscall
-    let mut point = [0u8; 64];
-    point[..32].copy_from_slice(qx);
-    point[32..].copy_from_slice(qy);
+        // Combine point coordinates for syscall
+        let mut point = [0u8; 64];
+        point[..32].copy_from_slice(qx);
+        point[32..].copy_from_slice(qy);
 
-    // Apply decryption using bn254 syscall: new_point = inv_key * point
-    let decrypted = bn254_mul(&point, &inv_key)
-        .map_err(|_| PokerError::ECOperationFailed)?;
+        // Apply decryption using bn254 syscall: new_point = inv_key * point
+        let decrypted = apply_inverse_key(&point, inv_key)?;
 
-    // Split result back into coordinates
-    let mut decrypted_x = [0u8; 32];
-    let mut decrypted_y = [0u8; 32];
-    decrypted_x.copy_from_slice(&decrypted[..32]);
-    decrypted_y.copy_from_slice(&decrypted[32..]);
+        // Split result back into coordinates
+        let mut decrypted_x = [0u8; 32];
+        let mut decrypted_y = [0u8; 32];
+        decrypted_x.copy_from_slice(&decrypted[..32]);
+        decrypted_y.copy_from_slice(&decrypted[32..]);
 
-    // Update deck (direct write to account data)
-    deck_state.set_card_point(index as usize, &decrypted_x, &decrypted_y);
-    deck_state.clear_card_owner(index as usize);
+        // Update deck (direct write to account data)
+        deck_state.set_card_point(index, &decrypted_x, &decrypted_y);
+        deck_state.clear_card_owner(index);
+
+        // Add to opened cards, caching the card ID so SubmitBestHand can skip the lookup
+        open_card(&mut community_cards, &accumulator, &decrypted_x, &decrypted_y)?;
+    }
 
-    // Add to opened cards
-    community_cards.add_opened_card(&decrypted_x, &decrypted_y);
+    // This card's reveal round is over; the next deal starts a fresh one
+    player_list.reset_revealed();
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Update state based on opened count
-    let opened = community_cards.opened_count;
+    advance_after_open(&mut game_state, &game_config, &player_list, community_cards.opened_count);
+
+    // Write back game_state, community_cards and player_list
+    // Note: deck_state is already using zero-copy so writes go directly to account
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        community_acc.borrow_mut_data_unchecked()[..COMMUNITY_CARDS_SIZE]
+            .copy_from_slice(&community_cards.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
+    }
+
+    msg!("CardOpened");
+    Ok(())
+}
+
+/// Move the hand on once `opened` board cards are face up
+///
+/// With everyone all-in (`is_everybody_all_in`, set when a fold leaves no one
+/// able to bet) each betting round is skipped: the next card is dealt, and
+/// after the river the hand goes straight to showdown.
+pub(crate) fn advance_after_open(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    opened: u8,
+) {
     if opened < 3 {
         // Still opening flop - need more cards
         game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
         game_state.community_cards_state = CommunityCardsState::FlopAwaiting as u8;
         // Set turn back to dealer so they can deal the next card
-        game_state.current_turn = game_config.dealer_index;
-    } else if opened == 3 {
-        // Flop complete - start post-flop betting
-        game_state.texas_state = TexasHoldEmState::Betting as u8;
-        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
-        // Action starts at first player after dealer
-        let first_to_act = (game_config.dealer_index + 1) % game_config.max_players;
-        game_state.current_turn = first_to_act;
-        // Last to call is the dealer (button) - round ends when action returns to them
-        if let Some(dealer_player) = player_list.get_player(game_config.dealer_index) {
-            game_state.last_to_call = *dealer_player;
+        game_state.current_turn = game_config.dealer_seat(player_list);
+    } else {
+        // Flop, turn or river complete - start the betting round for it
+        let round = match opened {
+            3 => BettingRoundState::PostFlop,
+            4 => BettingRoundState::PostTurn,
+            _ => BettingRoundState::Showdown,
+        };
+        start_street(game_state, game_config, player_list, round);
+    }
+
+    if opened >= 3 && game_state.is_everybody_all_in() {
+        finish_betting_round(game_state, game_config, player_list);
+        msg!("AllIn: betting round skipped");
+    }
+}
+
+/// Open betting on a new street
+///
+/// Action starts with the first seat left of the dealer that can still bet,
+/// and the round ends when it gets back to the button. All-in seats neither
+/// act nor count towards closing the round; with at most one seat left able
+/// to bet there is nobody to bet against, so the board runs out. The minimum
+/// raise starts over: the first raise of the street must be at least a big
+/// blind. `current_call_amount` carries over, since each seat's
+/// `current_bet` counts the whole hand; everyone who matched it starts the
+/// street with nothing to call.
+fn start_street(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    round: BettingRoundState,
+) {
+    game_state.texas_state = TexasHoldEmState::Betting as u8;
+    game_state.betting_round_state = round as u8;
+    game_state.current_turn =
+        first_to_bet_from(player_list, game_config.max_players, game_config.dealer_index + 1);
+    if let Some(dealer_player) = player_list.get_player(game_config.dealer_index) {
+        game_state.last_to_call = *dealer_player;
+    }
+    game_state.start_betting_round(player_list.count_can_bet());
+    if player_list.count_can_bet() <= 1 {
+        game_state.is_everybody_all_in = 1;
+    }
+    game_state.last_raise = game_config.big_blind();
+    match round {
+        BettingRoundState::PostFlop => msg!("BettingRoundStateChanged: PostFlop"),
+        BettingRoundState::PostTurn => msg!("BettingRoundStateChanged: PostTurn"),
+        _ => msg!("BettingRoundStateChanged: Showdown"),
+    }
+}
+
+/// Record an opened board card together with its card ID from the deck mapping
+///
+/// A fully decrypted card must be one of the mapped deck points; anything
+/// else means the dealer opened with a bogus key.
+fn open_card(
+    community_cards: &mut CommunityCards,
+    accumulator: &AccumulatorStateRef,
+    qx: &[u8; 32],
+    qy: &[u8; 32],
+) -> Result<(), PokerError> {
+    let id = accumulator.find_card_by_point(qx, qy).ok_or(PokerError::IllegalCard)?;
+    community_cards.add_opened_card(qx, qy, id);
+    Ok(())
+}
+
+/// Check that a community card open follows a completed reveal round
+fn validate_open_ready(
+    game_state: &GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    index: u8,
+) -> Result<(), ProgramError> {
+    if game_state.texas_state() != TexasHoldEmState::CommunityCardsAwaiting {
+        return Err(PokerError::InvalidTexasState.into());
+    }
+    if index != game_state.card_to_reveal {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    // reveal.rs flips drawing_state back to Picking once every non-owner has revealed
+    if game_state.drawing_state() != DrawingState::Picking
+        || player_list.count_revealed() < reveals_needed(game_config, player_list)
+    {
+        return Err(PokerError::InvalidDrawingState.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (GameState, GameConfig, PlayerList) {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 20, 1000);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for i in 1..=3u8 {
+            player_list.add_player([i; 32]);
         }
-        // Reset current_call_amount for new betting round
-        game_state.current_call_amount = 0;
-        msg!("BettingRoundStateChanged: PostFlop");
-    } else if opened == 4 {
-        // Turn complete - start post-turn betting
-        game_state.texas_state = TexasHoldEmState::Betting as u8;
-        game_state.betting_round_state = BettingRoundState::PostTurn as u8;
-        let first_to_act = (game_config.dealer_index + 1) % game_config.max_players;
-        game_state.current_turn = first_to_act;
-        if let Some(dealer_player) = player_list.get_player(game_config.dealer_index) {
-            game_state.last_to_call = *dealer_player;
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
+        game_state.drawing_state = DrawingState::Revealing as u8;
+        game_state.card_to_reveal = 45;
+        (game_state, game_config, player_list)
+    }
+
+    #[test]
+    fn test_rejects_premature_open() {
+        let (mut game_state, game_config, mut player_list) = setup();
+
+        // No reveals yet
+        assert_eq!(
+            validate_open_ready(&game_state, &game_config, &player_list, 45),
+            Err(PokerError::InvalidDrawingState.into())
+        );
+
+        // One of two reveals done
+        player_list.mark_revealed(1);
+        assert_eq!(
+            validate_open_ready(&game_state, &game_config, &player_list, 45),
+            Err(PokerError::InvalidDrawingState.into())
+        );
+
+        // Reveal round complete
+        player_list.mark_revealed(2);
+        game_state.drawing_state = DrawingState::Picking as u8;
+        assert!(validate_open_ready(&game_state, &game_config, &player_list, 45).is_ok());
+    }
+
+    fn mapped_deck() -> Vec<u8> {
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut mapping = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        for i in 0..crate::constants::DECK_SIZE {
+            mapping.set_deck_mapping(i, &[i as u8 + 1; 32], &[0xAA; 32]);
         }
-        game_state.current_call_amount = 0;
-        msg!("BettingRoundStateChanged: PostTurn");
-    } else if opened == 5 {
-        // River complete - start final betting (showdown)
-        game_state.texas_state = TexasHoldEmState::Betting as u8;
-        game_state.betting_round_state = BettingRoundState::Showdown as u8;
-        let first_to_act = (game_config.dealer_index + 1) % game_config.max_players;
-        game_state.current_turn = first_to_act;
-        if let Some(dealer_player) = player_list.get_player(game_config.dealer_index) {
-            game_state.last_to_call = *dealer_player;
+        data
+    }
+
+    #[test]
+    fn test_opened_ids_cached() {
+        let data = mapped_deck();
+        let accumulator = AccumulatorStateRef::from_bytes(&data).unwrap();
+
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+        for qx in [[8u8; 32], [20u8; 32], [52u8; 32]] {
+            open_card(&mut community_cards, &accumulator, &qx, &[0xAA; 32]).unwrap();
         }
-        game_state.current_call_amount = 0;
-        msg!("BettingRoundStateChanged: Showdown");
+        assert_eq!(community_cards.opened_ids[..3], [7, 19, 51]);
+
+        // Cached IDs match a fresh scan of the deck mapping
+        for slot in 0..3 {
+            let (qx, qy) = community_cards.get_opened_card(slot).unwrap();
+            assert_eq!(community_cards.cached_id(&qx, &qy), accumulator.find_card_by_point(&qx, &qy));
+        }
+
+        // The cache survives the account round trip
+        let restored = CommunityCards::from_bytes(&community_cards.to_bytes()).unwrap();
+        assert_eq!(restored.opened_ids, community_cards.opened_ids);
     }
 
-    // Write back game_state and community_cards
-    // Note: deck_state is already using zero-copy so writes go directly to account
-    unsafe {
-        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
-            .copy_from_slice(&game_state.to_bytes());
-        community_acc.borrow_mut_data_unchecked()[..COMMUNITY_CARDS_SIZE]
-            .copy_from_slice(&community_cards.to_bytes());
+    #[test]
+    fn test_rejects_tampered_community_card() {
+        let data = mapped_deck();
+        let accumulator = AccumulatorStateRef::from_bytes(&data).unwrap();
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+        open_card(&mut community_cards, &accumulator, &[8u8; 32], &[0xAA; 32]).unwrap();
+
+        // Decrypting with a wrong key lands on a point outside the deck
+        assert_eq!(
+            open_card(&mut community_cards, &accumulator, &[8u8; 32], &[0xAB; 32]),
+            Err(PokerError::IllegalCard)
+        );
+        // Nor does a fully stripped (identity) point count
+        assert_eq!(
+            open_card(&mut community_cards, &accumulator, &[0u8; 32], &[0u8; 32]),
+            Err(PokerError::IllegalCard)
+        );
+        assert_eq!(community_cards.opened_count, 1);
     }
 
-    msg!("CardOpened");
-    Ok(())
+    #[test]
+    fn test_rejects_wrong_card() {
+        let (mut game_state, game_config, mut player_list) = setup();
+        player_list.mark_revealed(1);
+        player_list.mark_revealed(2);
+        game_state.drawing_state = DrawingState::Picking as u8;
+
+        assert_eq!(
+            validate_open_ready(&game_state, &game_config, &player_list, 46),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+    }
+
+    #[test]
+    fn test_first_post_flop_raise_is_at_least_big_blind() {
+        use crate::poker::legal_actions;
+
+        // A 300 raise pre-flop must not carry over to the flop
+        let (mut game_state, game_config, player_list) = setup();
+        game_state.last_raise = 300;
+        advance_after_open(&mut game_state, &game_config, &player_list, 3);
+
+        assert_eq!(game_state.betting_round_state(), BettingRoundState::PostFlop);
+        assert_eq!(game_state.last_raise, game_config.big_blind());
+
+        let player_state = PlayerState::new(0, [0u8; 32], [2u8; 32], game_state.current_turn, 1000, [0u8; 32]);
+        let actions = legal_actions(&game_state, &player_state, &game_config);
+        assert_eq!(actions.min_bet, game_config.big_blind());
+    }
 }
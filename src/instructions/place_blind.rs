@@ -44,7 +44,7 @@ pub fn process_place_blind(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -69,17 +69,21 @@ pub fn process_place_blind(
         return Err(PokerError::InsufficientChips.into());
     }
 
+    // The seat on turn must be the blind's seat; with a dead small blind the
+    // big blind posts first
+    let is_small_blind =
+        game_state.current_call_amount == 0 && game_config.has_small_blind(&player_list);
+    validate_blind_seat(&game_config, &player_list, game_state.current_turn, is_small_blind)?;
+
     // Check if small blind or big blind
-    if game_state.current_call_amount == 0 {
+    if is_small_blind {
         // Small blind
-        let expected = game_config.small_blind.min(player_state.chips);
-        if player_state.current_bet + amount != expected && amount != player_state.chips {
+        if !blind_amount_valid(amount, player_state.current_bet, player_state.chips, game_config.small_blind) {
             return Err(PokerError::InvalidSmallBlind.into());
         }
     } else {
         // Big blind
-        let expected = (game_config.small_blind * 2).min(player_state.chips);
-        if player_state.current_bet + amount != expected && amount != player_state.chips {
+        if !blind_amount_valid(amount, player_state.current_bet, player_state.chips, game_config.small_blind * 2) {
             return Err(PokerError::InvalidBigBlind.into());
         }
     }
@@ -88,29 +92,166 @@ pub fn process_place_blind(
     player_state.chips -= amount;
     player_state.current_bet += amount;
     game_state.pot += amount;
-    game_state.current_call_amount = player_state.current_bet;
+    // A big blind all-in for less than a short small blind doesn't lower the call
+    game_state.current_call_amount = game_state.current_call_amount.max(player_state.current_bet);
+    game_state.record_action(game_state.current_turn, BetActionType::Blind, amount);
+    if player_state.chips == 0 {
+        player_list.mark_all_in(game_state.current_turn);
+    }
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if blinds are complete
-    if game_state.current_call_amount == game_config.small_blind {
-        // Move to big blind
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
-    } else {
-        // Blinds complete, move to drawing
-        game_state.texas_state = TexasHoldEmState::Drawing as u8;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-        msg!("TexasHoldEmStateChanged: Drawing");
-    }
+    advance_blinds(&mut game_state, &game_config, &player_list, is_small_blind);
 
     unsafe {
         game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
             .copy_from_slice(&game_state.to_bytes());
         player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
             .copy_from_slice(&player_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
     msg!("BlindPlaced");
     Ok(())
 }
+
+/// Whether `amount` posts exactly the `expected` blind
+///
+/// Only a stack too short to cover the blind may post less, and then it
+/// must go all-in.
+fn blind_amount_valid(amount: u64, current_bet: u64, chips: u64, expected: u64) -> bool {
+    if chips < expected {
+        amount == chips
+    } else {
+        current_bet + amount == expected
+    }
+}
+
+/// Pass the turn on once a blind is posted
+///
+/// Goes by which blind was posted rather than the amount: a small blind
+/// all-in for less than `small_blind` still hands over to the big blind.
+pub(crate) fn advance_blinds(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    is_small_blind: bool,
+) {
+    if is_small_blind {
+        // Move to big blind
+        game_state.current_turn = game_config.bb_seat(player_list);
+    } else {
+        // Blinds complete, move to drawing
+        game_state.texas_state = TexasHoldEmState::Drawing as u8;
+        game_state.current_turn = game_config.utg_seat(player_list);
+        msg!("TexasHoldEmStateChanged: Drawing");
+    }
+}
+
+/// Reject a blind posted from any seat other than the small/big blind seat
+pub(crate) fn validate_blind_seat(
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+    seat: u8,
+    is_small_blind: bool,
+) -> Result<(), PokerError> {
+    let expected = if is_small_blind {
+        game_config.sb_seat()
+    } else {
+        game_config.bb_seat(player_list)
+    };
+    if seat != expected {
+        return Err(PokerError::NotYourTurn);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seated_player_list;
+
+    #[test]
+    fn test_small_blind_from_wrong_seat_rejected() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        game_config.dealer_index = 1;
+        game_config.current_players = 4;
+        let player_list = seated_player_list(4);
+
+        // SB is the seat after the dealer, BB the one after that
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, true), Ok(()));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 3, false), Ok(()));
+
+        // Dealer or big blind seat trying to post the small blind
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 1, true), Err(PokerError::NotYourTurn));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 3, true), Err(PokerError::NotYourTurn));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, false), Err(PokerError::NotYourTurn));
+
+        // Down to seats 0 and 2: heads-up, the dealer posts the small blind
+        let mut player_list = player_list;
+        player_list.remove_player(1);
+        player_list.remove_player(3);
+        game_config.current_players = 2;
+        game_config.dealer_index = 0;
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 0, true), Ok(()));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, false), Ok(()));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 1, false), Err(PokerError::NotYourTurn));
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, true), Err(PokerError::NotYourTurn));
+    }
+
+    #[test]
+    fn test_dead_small_blind_leaves_the_big_blind_to_post_first() {
+        // Button 0, the small blind seat 1 is empty
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        game_config.current_players = 3;
+        let mut player_list = seated_player_list(4);
+        player_list.remove_player(1);
+        assert!(!game_config.has_small_blind(&player_list));
+        assert_eq!(game_config.first_blind_seat(&player_list), 2);
+        assert_eq!(game_config.bb_seat(&player_list), 2);
+        assert_eq!(game_config.utg_seat(&player_list), 3);
+
+        // Only the big blind is posted, then UTG draws first
+        assert_eq!(validate_blind_seat(&game_config, &player_list, 2, false), Ok(()));
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        advance_blinds(&mut game_state, &game_config, &player_list, false);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Drawing);
+        assert_eq!(game_state.current_turn, 3);
+    }
+
+    #[test]
+    fn test_deep_stack_must_post_the_exact_blind() {
+        // Deep stack: only the blind itself
+        assert!(blind_amount_valid(10, 0, 1000, 10));
+        assert!(!blind_amount_valid(9, 0, 1000, 10));
+        assert!(!blind_amount_valid(50, 0, 1000, 10));
+        // Going all-in is not a way around the blind size
+        assert!(!blind_amount_valid(1000, 0, 1000, 10));
+
+        // Short stack: all-in for less, nothing else
+        assert!(blind_amount_valid(4, 0, 4, 10));
+        assert!(!blind_amount_valid(3, 0, 4, 10));
+    }
+
+    #[test]
+    fn test_short_all_in_small_blind_hands_over_to_big_blind() {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        game_config.current_players = 3;
+        let player_list = seated_player_list(3);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+
+        // SB all-in for 4 of a 10 small blind
+        game_state.current_call_amount = 4;
+        advance_blinds(&mut game_state, &game_config, &player_list, true);
+        assert_eq!(game_state.current_turn, game_config.bb_seat(&player_list));
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Betting);
+
+        // The big blind then completes the blinds
+        advance_blinds(&mut game_state, &game_config, &player_list, false);
+        assert_eq!(game_state.current_turn, game_config.utg_seat(&player_list));
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Drawing);
+    }
+}
@@ -2,29 +2,31 @@
 //!
 //! The client provides the INVERSE of the lock key directly. This avoids
 //! expensive on-chain modular inverse computation. Verification happens
-//! at card reveal time when the decrypted card must match the original deck.
+//! at card reveal time when the decrypted card must match the original deck,
+//! and up front against the lock public key the player committed for the card.
+//! The up-front check ties the key to that commitment, not to the lock the
+//! player applied to the deck (see `CommitLockKeys`).
 
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, crypto::bn254::bn254_mul, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    crypto::bn254::{apply_inverse_key, verify_inverse_key},
+    error::PokerError,
+    state::*,
+    utils::{assert_card_index, assert_not_finished, first_to_bet_from, get_next_turn},
+};
 
 pub fn process_reveal(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    // Data: inv_key(32) + index(1) = 33 bytes
+    // Data: reveal_round(2) + inv_key(32) + index(1) per card in the reveal batch
     // inv_key is the modular inverse of the lock key, computed off-chain
-    if data.len() < 33 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    let mut inv_key = [0u8; 32];
-    inv_key.copy_from_slice(&data[0..32]);
-    let index = data[32];
 
     let mut iter = accounts.iter();
     let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
@@ -32,6 +34,7 @@ pub fn process_reveal(
     let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let deck_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -59,19 +62,24 @@ pub fn process_reveal(
     };
 
     // Validate state
+    assert_not_finished(game_state.game_phase())?;
     if game_state.drawing_state() != DrawingState::Revealing {
         return Err(PokerError::InvalidDrawingState.into());
     }
 
-    // Validate card index
-    if index != game_state.card_to_reveal {
-        return Err(PokerError::InvalidCardIndex.into());
-    }
+    // One key per card in the batch (the whole flop after DealFlop)
+    let data = check_reveal_round(data, &game_state)?;
+    let (pairs, num_pairs) = parse_reveal_batch(data, &game_state)?;
+    let pairs = &pairs[..num_pairs];
 
-    // Owner cannot reveal their own card
-    let card_owner = deck_state.get_card_owner(index as usize);
-    if card_owner == player.key() {
-        return Err(PokerError::NotCardOwner.into());
+    for (_, index) in pairs {
+        // The card must belong to the phase being revealed for
+        validate_reveal_kind(&game_state, game_config.deck_size, *index)?;
+
+        // Owner cannot reveal their own card
+        if deck_state.get_card_owner(*index as usize) == player.key() {
+            return Err(PokerError::NotCardOwner.into());
+        }
     }
 
     // Check player hasn't already revealed
@@ -81,27 +89,39 @@ pub fn process_reveal(
         return Err(PokerError::PlayerAlreadyRevealed.into());
     }
 
-    // Get current card point (zero-copy reference)
-    let (qx, qy) = deck_state.get_card_point(index as usize);
+    // The revealer's own state, for key replay checks
+    let mut player_state = unsafe {
+        PlayerStateMut::from_bytes(player_state_acc.borrow_mut_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if player_state.player() != player.key() || player_state.game_id() != &game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+
+    for (inv_key, index) in pairs {
+        // Block a key already used by this player for another card
+        player_state.record_reveal_key(inv_key)?;
 
-    // Combine point coordinates for syscall
-    let mut point = [0u8; 64];
-    point[..32].copy_from_slice(qx);
-    point[32..].copy_from_slice(qy);
+        // The key must match the lock public key the player committed for this card
+        let lock_public_key = player_state.lock_public_key(*index as usize)
+            .ok_or(PokerError::InvalidDecryptProof)?;
+        verify_inverse_key(lock_public_key, inv_key)?;
 
-    // Apply decryption using bn254 syscall: new_point = inv_key * point
-    // The client provides the inverse key directly to avoid expensive on-chain computation
-    let decrypted = bn254_mul(&point, &inv_key)
-        .map_err(|_| PokerError::ECOperationFailed)?;
+        // Get current card point (zero-copy reference)
+        let (qx, qy) = deck_state.get_card_point(*index as usize);
 
-    // Split result back into coordinates
-    let mut decrypted_x = [0u8; 32];
-    let mut decrypted_y = [0u8; 32];
-    decrypted_x.copy_from_slice(&decrypted[..32]);
-    decrypted_y.copy_from_slice(&decrypted[32..]);
+        // Combine point coordinates for syscall
+        let mut point = [0u8; 64];
+        point[..32].copy_from_slice(qx);
+        point[32..].copy_from_slice(qy);
 
-    // Update deck with decrypted point (direct write to account data)
-    deck_state.set_card_point(index as usize, &decrypted_x, &decrypted_y);
+        // Apply decryption using bn254 syscall: new_point = inv_key * point
+        // The client provides the inverse key directly to avoid expensive on-chain computation
+        let decrypted = apply_inverse_key(&point, inv_key)?;
+
+        // Update deck with decrypted point (direct write to account data)
+        deck_state.set_card_point_bytes(*index as usize, &decrypted);
+    }
 
     // Mark player as having revealed
     player_list.mark_revealed(player_index);
@@ -109,41 +129,9 @@ pub fn process_reveal(
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all non-owners have revealed (max_players - 1)
-    if player_list.count_revealed() >= game_config.max_players - 1 {
-        // Check if this is a community card reveal (texas_state == CommunityCardsAwaiting)
-        // or a hole card reveal (texas_state == Drawing)
-        if game_state.texas_state() == TexasHoldEmState::CommunityCardsAwaiting {
-            // Community card reveal complete - ready for dealer to open the card
-            // Don't change any states, just mark drawing_state as Picking so dealer can open
-            game_state.drawing_state = DrawingState::Picking as u8;
-            msg!("CommunityCardRevealComplete");
-        } else {
-            // Hole card reveal
-            game_state.drawing_state = DrawingState::Picking as u8;
-
-            // Check if all cards drawn for this phase
-            let total_cards_needed = (game_config.max_players as u8) * HOLE_CARDS_PER_PLAYER;
-            if game_state.cards_drawn >= total_cards_needed {
-                game_state.texas_state = TexasHoldEmState::Betting as u8;
-                game_state.betting_round_state = BettingRoundState::PreFlop as u8;
-                game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-
-                // Set last_to_call to big blind player - if action returns to them, round ends
-                let bb_index = (game_config.dealer_index + 2) % game_config.max_players;
-                if let Some(bb_player) = player_list.get_player(bb_index) {
-                    game_state.last_to_call = *bb_player;
-                }
-
-                msg!("TexasHoldEmStateChanged: Betting");
-                msg!("BettingRoundStateChanged: PreFlop");
-            } else {
-                // Next player draws
-                game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
-            }
-
-            msg!("DrawingStateChanged: Picking");
-        }
+    // Check if every seat except the card's owner has revealed
+    if player_list.count_revealed() >= reveals_needed(&game_config, &player_list) {
+        complete_reveal(&mut game_state, &game_config, &mut player_list);
     }
 
     // Write back game_state and player_list
@@ -158,3 +146,319 @@ pub fn process_reveal(
     msg!("CardRevealed");
     Ok(())
 }
+
+/// Advance the drawing state once a card has been fully decrypted
+///
+/// Shared by the one-by-one `Reveal` and the single-shot `RevealCombined`.
+///
+/// `revealed_bitmap` only counts reveals for the card in flight. A hole card
+/// is finished here, so the bitmap is cleared straight away; a community card
+/// keeps it until `OpenCommunityCard` has checked it and clears it there.
+pub(crate) fn complete_reveal(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &mut PlayerList,
+) {
+    // Check if this is a community card reveal (texas_state == CommunityCardsAwaiting)
+    // or a hole card reveal (texas_state == Drawing)
+    if game_state.texas_state() == TexasHoldEmState::CommunityCardsAwaiting {
+        // Community card reveal complete - ready for dealer to open the card
+        // Don't change any states, just mark drawing_state as Picking so dealer can open
+        game_state.drawing_state = DrawingState::Picking as u8;
+        msg!("CommunityCardRevealComplete");
+    } else {
+        // Hole card reveal
+        game_state.drawing_state = DrawingState::Picking as u8;
+
+        // Check if all cards drawn for this phase
+        let total_cards_needed = player_list.occupied_count() * HOLE_CARDS_PER_PLAYER;
+        if game_state.cards_drawn >= total_cards_needed {
+            game_state.texas_state = TexasHoldEmState::Betting as u8;
+            game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+            let (first_to_act, last_to_act) =
+                game_config.preflop_seats(player_list, game_state.current_call_amount);
+            // A blind or straddle may have put the first seat all-in
+            game_state.current_turn =
+                first_to_bet_from(player_list, game_config.max_players, first_to_act);
+            game_state.start_betting_round(player_list.count_can_bet());
+
+            // Set last_to_call to the big blind (or straddler) - if action returns to them, round ends
+            if let Some(last_player) = player_list.get_player(last_to_act) {
+                game_state.last_to_call = *last_player;
+            }
+
+            msg!("TexasHoldEmStateChanged: Betting");
+            msg!("BettingRoundStateChanged: PreFlop");
+        } else {
+            // Next player draws
+            game_state.current_turn =
+                get_next_turn(game_state.current_turn, player_list, game_config.max_players);
+        }
+        player_list.reset_revealed();
+
+        msg!("DrawingStateChanged: Picking");
+    }
+}
+
+/// Strip the `reveal_round` prefix, rejecting a reveal meant for another card
+///
+/// Each card put up for reveal bumps `GameState::reveal_round`, so a reveal
+/// built for an earlier card - delayed or replayed - no longer matches.
+fn check_reveal_round<'a>(data: &'a [u8], game_state: &GameState) -> Result<&'a [u8], ProgramError> {
+    let (round, rest) = data.split_first_chunk::<2>().ok_or(ProgramError::InvalidInstructionData)?;
+    if u16::from_le_bytes(*round) != game_state.reveal_round {
+        return Err(PokerError::StaleRevealRound.into());
+    }
+    Ok(rest)
+}
+
+/// An `inv_key(32) + index(1)` pair from instruction data
+pub(crate) type RevealPair = ([u8; 32], u8);
+
+/// Parse one pair per card in the pending reveal batch, in batch order
+///
+/// The batch is a single card except right after `DealFlop`, when all three
+/// flop cards are revealed (and later opened) together.
+pub(crate) fn parse_reveal_batch(
+    data: &[u8],
+    game_state: &GameState,
+) -> Result<([RevealPair; FLOP_CARDS as usize], usize), ProgramError> {
+    let num_pairs = game_state.pending_reveal_count();
+    if num_pairs == 0 || num_pairs > FLOP_CARDS as usize || data.len() != num_pairs * 33 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut pairs = [([0u8; 32], 0u8); FLOP_CARDS as usize];
+    let chunks = data.chunks_exact(33).zip(game_state.pending_reveal_cards());
+    for (pair, (chunk, expected)) in pairs.iter_mut().zip(chunks) {
+        pair.0.copy_from_slice(&chunk[..32]);
+        pair.1 = chunk[32];
+        assert_card_index(pair.1)?;
+        if pair.1 != expected {
+            return Err(PokerError::InvalidCardIndex.into());
+        }
+    }
+    Ok((pairs, num_pairs))
+}
+
+/// Number of reveals that complete the card being revealed
+///
+/// A hole card is revealed by everyone but the player it was drawn for. A
+/// community card is owned by the dealer, who opens it last, so only the
+/// non-dealer seats reveal it - a single reveal heads-up. Either way the
+/// owner is the one seat that never reveals.
+pub(crate) fn reveals_needed(game_config: &GameConfig, player_list: &PlayerList) -> u8 {
+    game_config.reveals_needed(player_list)
+}
+
+/// Check that the card being revealed matches the current phase
+///
+/// Hole cards are drawn first from the top of the `deck_size`-card deck, so
+/// the last `cards_drawn` indices are hole cards and everything below them
+/// belongs to the board.
+pub(crate) fn validate_reveal_kind(
+    game_state: &GameState,
+    deck_size: u8,
+    index: u8,
+) -> Result<(), ProgramError> {
+    let first_hole_index = deck_size.saturating_sub(game_state.cards_drawn);
+    let is_hole_card = index >= first_hole_index;
+    let expects_community = game_state.texas_state() == TexasHoldEmState::CommunityCardsAwaiting;
+    if is_hole_card == expects_community {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heads_up_community_card_needs_one_reveal() {
+        let dealer = [1u8; 32];
+        let other = [2u8; 32];
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        player_list.add_player(dealer);
+        player_list.add_player(other);
+
+        // The dealer owns the community card, so only the other seat reveals
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 2, 10, 100, 0);
+        assert_eq!(reveals_needed(&game_config, &player_list), 1);
+        assert!(player_list.count_revealed() < reveals_needed(&game_config, &player_list));
+        player_list.mark_revealed(player_list.find_player_index(&other).unwrap());
+        assert!(player_list.count_revealed() >= reveals_needed(&game_config, &player_list));
+    }
+
+    #[test]
+    fn test_community_card_waits_for_every_non_dealer() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..4u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+
+        // Dealer at seat 0: seats 1-3 must all reveal
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        for seat in 1..4u8 {
+            assert!(player_list.count_revealed() < reveals_needed(&game_config, &player_list));
+            player_list.mark_revealed(seat);
+        }
+        assert!(player_list.count_revealed() >= reveals_needed(&game_config, &player_list));
+    }
+
+    #[test]
+    fn test_every_lock_holder_must_reveal() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 5, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..5u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        assert_eq!(reveals_needed(&game_config, &player_list), 4);
+
+        // Every non-owner holds a lock on the card, so all four must reveal
+        for seat in 1..4u8 {
+            player_list.mark_revealed(seat);
+            assert!(player_list.count_revealed() < reveals_needed(&game_config, &player_list));
+        }
+        player_list.mark_revealed(4);
+        assert!(player_list.count_revealed() >= reveals_needed(&game_config, &player_list));
+    }
+
+    #[test]
+    fn test_hole_reveals_do_not_leak_into_community_reveal() {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..3u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Drawing as u8;
+
+        // Last hole card: seats 1 and 2 reveal for seat 0's card
+        game_state.cards_drawn = 6;
+        player_list.mark_revealed(1);
+        player_list.mark_revealed(2);
+        complete_reveal(&mut game_state, &game_config, &mut player_list);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Betting);
+        assert_eq!(player_list.count_revealed(), 0);
+
+        // First community card: one reveal in must not look like a full round
+        game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
+        player_list.mark_revealed(1);
+        assert!(player_list.count_revealed() < reveals_needed(&game_config, &player_list));
+        player_list.mark_revealed(2);
+        complete_reveal(&mut game_state, &game_config, &mut player_list);
+
+        // Kept for OpenCommunityCard to check
+        assert_eq!(player_list.count_revealed(), reveals_needed(&game_config, &player_list));
+    }
+
+    #[test]
+    fn test_flop_batch_reveals_three_cards() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.cards_left_in_deck = 45;
+        game_state.card_to_reveal = 47;
+
+        let mut data = Vec::new();
+        for (key, index) in [(1u8, 47u8), (2, 46), (3, 45)] {
+            data.extend_from_slice(&[key; 32]);
+            data.push(index);
+        }
+        let (pairs, num_pairs) = parse_reveal_batch(&data, &game_state).unwrap();
+        assert_eq!(num_pairs, 3);
+        assert_eq!(pairs.map(|(key, index)| (key[0], index)), [(1, 47), (2, 46), (3, 45)]);
+
+        // Every flop card must be covered, in dealing order
+        assert_eq!(
+            parse_reveal_batch(&data[..33], &game_state),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        data[32] = 46;
+        assert_eq!(
+            parse_reveal_batch(&data, &game_state),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+
+        // A single drawn card is a batch of one
+        game_state.card_to_reveal = 45;
+        let (pairs, num_pairs) = parse_reveal_batch(&data[66..], &game_state).unwrap();
+        assert_eq!((num_pairs, pairs[0].1), (1, 45));
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_card_index() {
+        // Even if card_to_reveal pointed past the deck, the index is refused
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.card_to_reveal = DECK_SIZE as u8;
+        game_state.cards_left_in_deck = DECK_SIZE as u8;
+
+        let mut data = [7u8; 33];
+        data[32] = DECK_SIZE as u8;
+        assert_eq!(
+            parse_reveal_batch(&data, &game_state),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+    }
+
+    #[test]
+    fn test_rejects_stale_reveal_round() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.card_to_reveal = 51;
+        game_state.cards_left_in_deck = 51;
+        game_state.begin_reveal();
+
+        let mut data = game_state.reveal_round.to_le_bytes().to_vec();
+        data.extend_from_slice(&[7u8; 32]);
+        data.push(51);
+        assert_eq!(check_reveal_round(&data, &game_state), Ok(&data[2..]));
+
+        // The next card goes up for reveal: the old round no longer applies
+        game_state.card_to_reveal = 50;
+        game_state.cards_left_in_deck = 50;
+        game_state.begin_reveal();
+        assert_eq!(
+            check_reveal_round(&data, &game_state),
+            Err(PokerError::StaleRevealRound.into())
+        );
+        assert_eq!(check_reveal_round(&data[..1], &game_state), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_rejects_hole_card_during_community_reveal() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        // Two players' hole cards dealt from indices 48..52
+        game_state.cards_drawn = 4;
+        game_state.cards_left_in_deck = DECK_SIZE as u8 - 5;
+        game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
+
+        // Reusing a hole-card index is rejected
+        assert_eq!(
+            validate_reveal_kind(&game_state, DECK_SIZE as u8, 49),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+
+        // The freshly dealt community card is accepted
+        assert!(validate_reveal_kind(&game_state, DECK_SIZE as u8, 47).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_community_card_during_hole_reveal() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.cards_drawn = 3;
+        game_state.texas_state = TexasHoldEmState::Drawing as u8;
+
+        assert!(validate_reveal_kind(&game_state, DECK_SIZE as u8, 49).is_ok());
+        assert_eq!(
+            validate_reveal_kind(&game_state, DECK_SIZE as u8, 48),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+
+        // A short deck is drawn from index 35 down
+        let short = crate::constants::SHORT_DECK_SIZE as u8;
+        assert!(validate_reveal_kind(&game_state, short, 33).is_ok());
+        assert_eq!(
+            validate_reveal_kind(&game_state, short, 32),
+            Err(PokerError::InvalidCardIndex.into())
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Reveal combined instruction - every non-owner decrypts a drawn card at once
+//!
+//! Decryption is `inv_key * point` and the players' inverse keys compose
+//! multiplicatively, so the product of all non-owners' inverse keys removes
+//! every other lock with a single `bn254_mul`, instead of one `Reveal` per
+//! player. Only the individual inverse keys are passed and the program takes
+//! their product itself; each key is recorded against its player for replay
+//! protection and checked against their committed lock public key, just like
+//! `Reveal`.
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar}, ProgramResult,
+};
+
+use crate::{
+    constants::MAX_PLAYERS,
+    crypto::bn254::{apply_inverse_key, mul_mod_bn254, verify_inverse_key},
+    error::PokerError,
+    instructions::reveal::{complete_reveal, validate_reveal_kind},
+    state::*,
+    utils::{active_seats, assert_card_index, ActiveSeats},
+};
+
+/// Data: index(1) + inv_key(32) per revealing seat
+const HEADER_SIZE: usize = 1;
+
+/// Parsed `RevealCombined` instruction data
+#[derive(Debug, PartialEq, Eq)]
+struct CombinedReveal<'a> {
+    index: u8,
+    inv_keys: &'a [[u8; 32]],
+}
+
+pub fn process_reveal_combined(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let CombinedReveal { index, inv_keys } = parse_combined_reveal(data)?;
+
+    let mut iter = accounts.iter();
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let deck_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // Remaining accounts: (player signer, player_state) for every seat except
+    // the card's owner, in seat order, matching the order of `inv_keys`
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut deck_state = unsafe {
+        DeckStateMut::from_bytes(deck_state_acc.borrow_mut_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Validate state
+    if game_state.drawing_state() != DrawingState::Revealing {
+        return Err(PokerError::InvalidDrawingState.into());
+    }
+    // A single card only: a dealt flop is revealed through `Reveal` as a batch
+    if index != game_state.card_to_reveal || game_state.pending_reveal_count() != 1 {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    validate_reveal_kind(&game_state, game_config.deck_size, index)?;
+
+    // All-or-nothing: no one may have revealed this card individually
+    if player_list.count_revealed() != 0 {
+        return Err(PokerError::PlayerAlreadyRevealed.into());
+    }
+
+    // One key per non-owner seat
+    let seats = revealing_seats(&player_list, game_config.max_players);
+    let non_owners = seats.len().saturating_sub(1) as u8;
+    if inv_keys.len() != non_owners as usize {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Every non-owner seat signs and records its own key
+    let card_owner = *deck_state.get_card_owner(index as usize);
+    let mut keys = inv_keys.iter();
+    for &seat in seats.as_slice() {
+        let seated = player_list.get_player(seat).ok_or(PokerError::NotAPlayer)?;
+        if seated == &card_owner {
+            continue;
+        }
+        let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if !player.is_signer() {
+            return Err(PokerError::InvalidSigner.into());
+        }
+        if player.key() != seated {
+            return Err(PokerError::NotAPlayer.into());
+        }
+
+        let mut player_state = unsafe {
+            PlayerStateMut::from_bytes(player_state_acc.borrow_mut_data_unchecked())
+                .ok_or(PokerError::InvalidAccountData)?
+        };
+        if player_state.player() != player.key() || player_state.game_id() != &game_config.game_id {
+            return Err(PokerError::InvalidAccountData.into());
+        }
+        let inv_key: &[u8; 32] = keys.next().ok_or(ProgramError::InvalidInstructionData)?;
+        player_state.record_reveal_key(inv_key)?;
+        let lock_public_key = player_state.lock_public_key(index as usize)
+            .ok_or(PokerError::InvalidDecryptProof)?;
+        verify_inverse_key(lock_public_key, inv_key)?;
+        player_list.mark_revealed(seat);
+    }
+
+    // The owner's seat was skipped, so exactly the non-owners were covered
+    if player_list.count_revealed() < non_owners {
+        return Err(PokerError::NotCardOwner.into());
+    }
+
+    // Single decryption with the product of the verified keys
+    let combined_key = combine_inverse_keys(inv_keys);
+    let (qx, qy) = deck_state.get_card_point(index as usize);
+    let mut point = [0u8; 64];
+    point[..32].copy_from_slice(qx);
+    point[32..].copy_from_slice(qy);
+    let decrypted = apply_inverse_key(&point, &combined_key)?;
+    deck_state.set_card_point_bytes(index as usize, &decrypted);
+
+    let clock = Clock::get()?;
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    complete_reveal(&mut game_state, &game_config, &mut player_list);
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
+    }
+
+    msg!("CardRevealed");
+    Ok(())
+}
+
+/// Split instruction data into the card index and individual keys
+fn parse_combined_reveal(data: &[u8]) -> Result<CombinedReveal<'_>, ProgramError> {
+    if data.len() < HEADER_SIZE {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (inv_keys, rest) = data[HEADER_SIZE..].as_chunks::<32>();
+    if !rest.is_empty() || inv_keys.len() >= MAX_PLAYERS as usize {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    assert_card_index(data[0])?;
+    Ok(CombinedReveal { index: data[0], inv_keys })
+}
+
+/// Occupied seats, each of which locked every card during the shuffle
+///
+/// Empty seats hold no lock, so the card owner plus these non-owners is
+/// the whole table whether or not it is full.
+fn revealing_seats(player_list: &PlayerList, max_players: u8) -> ActiveSeats {
+    active_seats(player_list, max_players, |_| false)
+}
+
+/// Product of the individual inverse keys in the BN254 scalar field
+fn combine_inverse_keys(inv_keys: &[[u8; 32]]) -> [u8; 32] {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    inv_keys.iter().fold(one, |acc, key| mul_mod_bn254(&acc, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::bn254::{bn254_mul, mod_inverse_bn254};
+
+    fn scalar(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    fn generator() -> [u8; 64] {
+        let mut point = [0u8; 64];
+        point[31] = 1;
+        point[63] = 2;
+        point
+    }
+
+    #[test]
+    fn test_combined_key_decrypts_doubly_locked_card() {
+        let card = bn254_mul(&generator(), &scalar(5)).unwrap();
+        let (k1, k2) = (scalar(3), scalar(7));
+        let locked = bn254_mul(&bn254_mul(&card, &k1).unwrap(), &k2).unwrap();
+
+        let inv_keys = [mod_inverse_bn254(&k1).unwrap(), mod_inverse_bn254(&k2).unwrap()];
+        let combined = combine_inverse_keys(&inv_keys);
+
+        // One multiplication undoes both locks
+        assert_eq!(bn254_mul(&locked, &combined).unwrap(), card);
+        // Same result as revealing one key at a time
+        let sequential = bn254_mul(&bn254_mul(&locked, &inv_keys[0]).unwrap(), &inv_keys[1]).unwrap();
+        assert_eq!(sequential, card);
+    }
+
+    #[test]
+    fn test_short_table_reveals_from_occupied_seats_only() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..3 {
+            player_list.add_player([seat + 1; 32]);
+        }
+
+        // Three of six seats taken: the owner plus two non-owners
+        let seats = revealing_seats(&player_list, 6);
+        assert_eq!(seats.as_slice(), &[0, 1, 2]);
+        assert_eq!(seats.len() - 1, 2);
+    }
+
+    #[test]
+    fn test_parse_combined_reveal() {
+        let inv_keys = [mod_inverse_bn254(&scalar(3)).unwrap(), mod_inverse_bn254(&scalar(7)).unwrap()];
+        let mut data = vec![47];
+        data.extend_from_slice(&inv_keys[0]);
+        data.extend_from_slice(&inv_keys[1]);
+
+        let parsed = parse_combined_reveal(&data).unwrap();
+        assert_eq!(parsed, CombinedReveal { index: 47, inv_keys: &inv_keys });
+
+        // Truncated key, and no card index at all
+        assert_eq!(
+            parse_combined_reveal(&data[..data.len() - 1]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(parse_combined_reveal(&[]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_combined_reveal(&[200]), Err(PokerError::InvalidCardIndex.into()));
+    }
+}
@@ -0,0 +1,193 @@
+//! Set deck instruction - force a known deck ordering for integration tests
+//!
+//! Only built with the `test-deck` feature; never part of production builds.
+//! Stands in for the players' shuffles: once the deck is mapped, the
+//! authority writes the mapped card points in a chosen order and the game
+//! moves straight on to locking. Locks, reveals and opens then run as usual,
+//! so a test can play a known hand to showdown.
+//!
+//! Data: one card ID per deck card, in dealing order (the first ID is the
+//! first card drawn)
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::{
+    constants::DECK_SIZE,
+    error::PokerError,
+    poker::is_short_deck_card,
+    state::*,
+    utils::{assert_shuffling_step, first_occupied_from},
+};
+
+pub fn process_set_deck(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let mut iter = accounts.iter();
+    let authority = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let deck_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !authority.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    if game_config.authority != *authority.key() {
+        return Err(PokerError::InvalidAuthority.into());
+    }
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // In place of the first shuffle, after the deck mapping is known
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Shuffling)?;
+    if !game_state.is_deck_submitted() || game_state.active_player_count != 0 {
+        return Err(PokerError::InvalidShufflingState.into());
+    }
+
+    let order = parse_deck_order(data, &game_config)?;
+
+    let accumulator = unsafe {
+        AccumulatorStateRef::from_bytes(accumulator_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    let mut deck_state = unsafe {
+        DeckStateMut::from_bytes(deck_state_acc.borrow_mut_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    write_deck_order(&mut deck_state, &accumulator, order);
+
+    // Every shuffle is skipped
+    game_state.shuffling_state = ShufflingState::Locking as u8;
+    let player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+    game_state.current_turn =
+        first_occupied_from(&player_list, game_config.max_players, game_config.dealer_index + 3);
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+    }
+
+    msg!("DeckSet");
+    msg!("ShufflingStateChanged: Locking");
+    Ok(())
+}
+
+/// Check the order names every card of the game's deck exactly once
+fn parse_deck_order<'a>(data: &'a [u8], game_config: &GameConfig) -> Result<&'a [u8], ProgramError> {
+    if data.len() != game_config.deck_size as usize {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mut seen = [false; DECK_SIZE];
+    for &id in data {
+        let valid = (id as usize) < DECK_SIZE
+            && (!game_config.is_short_deck() || is_short_deck_card(id as i8));
+        if !valid || seen[id as usize] {
+            return Err(PokerError::IllegalCard.into());
+        }
+        seen[id as usize] = true;
+    }
+    Ok(data)
+}
+
+/// Lay out the mapped card points so `order[0]` is drawn first
+///
+/// Cards come off the top of the deck, so the first card dealt sits at the
+/// highest index.
+fn write_deck_order(deck_state: &mut DeckStateMut, accumulator: &AccumulatorStateRef, order: &[u8]) {
+    for (dealt, &id) in order.iter().enumerate() {
+        let (qx, qy) = accumulator.get_deck_mapping(id as usize);
+        deck_state.set_card_point(order.len() - 1 - dealt, qx, qy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poker::{compare_hands_for_deck, evaluate_hand_for_deck, CardId};
+    use crate::test_utils::{make_deck_state, make_game_config};
+
+    fn mapped_deck() -> Vec<u8> {
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut mapping = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        for i in 0..DECK_SIZE {
+            mapping.set_deck_mapping(i, &[i as u8 + 1; 32], &[0xAA; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_set_deck_plays_known_hand() {
+        use CardId::*;
+
+        // Heads-up: hole cards alternate between the seats, then the board
+        let dealt = [
+            AceSpades, KingDiamonds, AceHearts, KingClubs,
+            TwoClubs, SevenDiamonds, NineHearts, JackSpades, ThreeClubs,
+        ];
+        let mut order: Vec<u8> = dealt.iter().map(|&card| card as u8).collect();
+        let rest: Vec<u8> = (0..DECK_SIZE as u8).filter(|id| !order.contains(id)).collect();
+        order.extend(rest);
+
+        let game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        assert_eq!(parse_deck_order(&order, &game_config), Ok(&order[..]));
+
+        let accumulator_data = mapped_deck();
+        let accumulator = AccumulatorStateRef::from_bytes(&accumulator_data).unwrap();
+        let mut deck_data = make_deck_state();
+        let mut deck_state = DeckStateMut::from_bytes(&mut deck_data).unwrap();
+        write_deck_order(&mut deck_state, &accumulator, &order);
+
+        // Draw from the top: every card decodes back to the requested one
+        let drawn: Vec<i8> = (0..dealt.len())
+            .map(|n| {
+                let (qx, qy) = deck_state.get_card_point(DECK_SIZE - 1 - n);
+                accumulator.find_card_by_point(qx, qy).unwrap()
+            })
+            .collect();
+        assert_eq!(drawn, dealt.map(|card| card as i8));
+
+        // Showdown: aces beat kings on a dry board
+        let board = &drawn[4..];
+        let best = |hole: [i8; 2]| {
+            evaluate_hand_for_deck([hole[0], hole[1], board[1], board[2], board[3]], DECK_SIZE as u8)
+        };
+        let (aces, aces_cards) = best([drawn[0], drawn[2]]);
+        let (kings, kings_cards) = best([drawn[1], drawn[3]]);
+        assert_eq!(compare_hands_for_deck(aces, &aces_cards, kings, &kings_cards, DECK_SIZE as u8), 1);
+    }
+
+    #[test]
+    fn test_rejects_incomplete_deck_order() {
+        let game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        let mut order: Vec<u8> = (0..DECK_SIZE as u8).collect();
+
+        // A repeated card leaves another out
+        order[1] = 0;
+        assert_eq!(parse_deck_order(&order, &game_config), Err(PokerError::IllegalCard.into()));
+
+        order[1] = DECK_SIZE as u8;
+        assert_eq!(parse_deck_order(&order, &game_config), Err(PokerError::IllegalCard.into()));
+
+        assert_eq!(
+            parse_deck_order(&order[..DECK_SIZE - 1], &game_config),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
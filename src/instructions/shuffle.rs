@@ -5,7 +5,12 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{
+    constants::*,
+    error::PokerError,
+    state::*,
+    utils::{assert_shuffling_step, finish_seat_step},
+};
 
 pub fn process_shuffle(
     _program_id: &Pubkey,
@@ -44,9 +49,7 @@ pub fn process_shuffle(
     };
 
     // Validate state
-    if game_state.shuffling_state() != ShufflingState::Shuffling {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Shuffling)?;
 
     if !game_state.is_deck_submitted() {
         return Err(PokerError::DeckNotSubmitted.into());
@@ -74,19 +77,14 @@ pub fn process_shuffle(
         deck_state.set_card_point(i, qx, qy);
     }
 
-    game_state.active_player_count += 1;
-
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
     // Check if all players have shuffled
-    if game_state.active_player_count >= game_config.max_players {
+    let first_seat = game_config.dealer_index + 3;
+    if finish_seat_step(&mut game_state, &player_list, game_config.max_players, first_seat) {
         game_state.shuffling_state = ShufflingState::Locking as u8;
-        game_state.active_player_count = 0;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
         msg!("ShufflingStateChanged: Locking");
-    } else {
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
     }
 
     // Write back game_state only (deck_state writes go directly to account)
@@ -13,6 +13,7 @@ use crate::{
     crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
     error::PokerError,
     state::*,
+    utils::assert_shuffling_step,
 };
 
 pub fn process_shuffle_part1(
@@ -59,9 +60,7 @@ pub fn process_shuffle_part1(
     };
 
     // Validate state
-    if game_state.shuffling_state() != ShufflingState::Shuffling {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Shuffling)?;
 
     // Note: No deck_submitted check - first shuffle establishes the deck
 
@@ -13,6 +13,7 @@ use crate::{
     crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE},
     error::PokerError,
     state::*,
+    utils::{assert_shuffling_step, finish_seat_step},
 };
 
 pub fn process_shuffle_part2(
@@ -59,9 +60,7 @@ pub fn process_shuffle_part2(
     };
 
     // Validate state
-    if game_state.shuffling_state() != ShufflingState::Shuffling {
-        return Err(PokerError::InvalidShufflingState.into());
-    }
+    assert_shuffling_step(game_state.game_phase(), game_state.shuffling_state(), ShufflingState::Shuffling)?;
 
     // Note: No deck_submitted check - first shuffle establishes the deck
 
@@ -112,20 +111,14 @@ pub fn process_shuffle_part2(
         game_state.is_deck_submitted = 1;
     }
 
-    // Increment player count
-    game_state.active_player_count += 1;
-
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
     // Check if all players have shuffled
-    if game_state.active_player_count >= game_config.max_players {
+    let first_seat = game_config.dealer_index + 3;
+    if finish_seat_step(&mut game_state, &player_list, game_config.max_players, first_seat) {
         game_state.shuffling_state = ShufflingState::Locking as u8;
-        game_state.active_player_count = 0;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
         msg!("ShufflingStateChanged: Locking");
-    } else {
-        game_state.current_turn = (game_state.current_turn + 1) % game_config.max_players;
     }
 
     // Write back states
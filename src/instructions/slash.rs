@@ -6,7 +6,11 @@ use pinocchio::{
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::{error::PokerError, state::*};
+use crate::{
+    error::PokerError,
+    state::*,
+    utils::{get_next_turn, next_to_bet, players_in_hand},
+};
 
 pub fn process_slash(
     _program_id: &Pubkey,
@@ -42,7 +46,7 @@ pub fn process_slash(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -61,9 +65,7 @@ pub fn process_slash(
     // Check timeout
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
-    let time_since_last_action = current_time - game_state.last_action_timestamp;
-
-    if time_since_last_action < game_config.timeout_seconds as i64 {
+    if !timeout_elapsed(current_time, game_state.last_action_timestamp, game_config.timeout_seconds) {
         return Err(PokerError::TimeoutNotReached.into());
     }
 
@@ -99,24 +101,25 @@ pub fn process_slash(
     if !offender_state.is_folded() {
         offender_state.is_folded = 1;
         game_state.num_folded_players += 1;
+        player_list.mark_folded(game_state.current_turn);
     }
 
     // Update last action timestamp
     game_state.last_action_timestamp = current_time;
 
     // Check if only one player remaining
-    let players_remaining = game_config.max_players - game_state.num_folded_players;
+    let players_remaining = players_in_hand(&player_list, game_state.num_folded_players);
     if players_remaining == 1 {
         game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
         msg!("EarlyEnd: Only one player remaining after slash");
     } else {
-        // Move to next player
-        game_state.current_turn = next_active_player(
-            game_state.current_turn,
-            game_config.max_players,
-            &player_list,
-            &offender_state,
-        );
+        // Move to the next seat still in after the offender (still able to
+        // bet, when slashed mid-round)
+        game_state.current_turn = if game_state.texas_state() == TexasHoldEmState::Betting {
+            next_to_bet(game_state.current_turn, &player_list, game_config.max_players)
+        } else {
+            get_next_turn(game_state.current_turn, &player_list, game_config.max_players)
+        };
     }
 
     // Write updates
@@ -125,24 +128,46 @@ pub fn process_slash(
             .copy_from_slice(&game_state.to_bytes());
         offender_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
             .copy_from_slice(&offender_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
     msg!("PlayerSlashed");
     Ok(())
 }
 
+/// Whether at least `timeout_seconds` have passed since the last action
+///
+/// A `last_action_timestamp` in the future never counts as timed out, so a
+/// bad stored timestamp cannot be used to slash early.
+fn timeout_elapsed(current_time: i64, last_action_timestamp: i64, timeout_seconds: u32) -> bool {
+    let elapsed = current_time.saturating_sub(last_action_timestamp);
+    elapsed > 0 && elapsed >= timeout_seconds as i64
+}
+
 fn calculate_slash_amount(chips: u64, slash_percentage: u8) -> u64 {
     // slash_percentage is 0-100
     let percentage = slash_percentage.min(100) as u64;
     (chips * percentage) / 100
 }
 
-fn next_active_player(
-    current: u8,
-    max: u8,
-    _player_list: &PlayerList,
-    _offender_state: &PlayerState,
-) -> u8 {
-    // Simplified - in production would skip folded players
-    (current + 1) % max
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_elapsed() {
+        assert!(!timeout_elapsed(1_000, 950, 120));
+        assert!(timeout_elapsed(1_000, 880, 120));
+        assert!(timeout_elapsed(1_000, 0, 120));
+    }
+
+    #[test]
+    fn test_future_timestamp_never_times_out() {
+        assert!(!timeout_elapsed(1_000, 2_000, 120));
+        assert!(!timeout_elapsed(1_000, 1_000, 0));
+        assert!(!timeout_elapsed(i64::MIN, i64::MAX, 1));
+        // Extreme gaps saturate instead of overflowing
+        assert!(timeout_elapsed(i64::MAX, i64::MIN, 120));
+    }
 }
@@ -5,7 +5,7 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{constants::*, error::PokerError, state::*};
+use crate::{constants::*, error::PokerError, state::*, utils::first_occupied_from};
 
 pub fn process_start_next_game(
     _program_id: &Pubkey,
@@ -42,11 +42,16 @@ pub fn process_start_next_game(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let player_list = unsafe {
+    let mut player_list = unsafe {
         PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
 
+    // A finished sit-and-go never deals again
+    if game_state.game_phase() == GamePhase::Finished {
+        return Err(PokerError::InvalidGamePhase.into());
+    }
+
     // Validate state - must be finished or pot claimed
     if game_state.texas_state() != TexasHoldEmState::Finished
         && game_state.texas_state() != TexasHoldEmState::ClaimPot
@@ -65,24 +70,24 @@ pub fn process_start_next_game(
         return Err(PokerError::PotNotClaimed.into());
     }
 
-    // Rotate dealer position
-    game_config.dealer_index = (game_config.dealer_index + 1) % game_config.max_players;
-
-    // Increment game number
-    game_config.game_number += 1;
+    // A sit-and-go ends once a single player holds every chip
+    if game_config.game_mode() == GameMode::SitAndGo {
+        let stacks = seat_stacks(&player_states_accounts, &player_list, &game_config)?;
+        if let Some(winner) = sit_and_go_winner(&stacks[..game_config.max_players as usize]) {
+            finish_sit_and_go(&mut game_state, &mut game_config, winner);
+            unsafe {
+                game_config_acc.borrow_mut_data_unchecked()[..GAME_CONFIG_SIZE]
+                    .copy_from_slice(&game_config.to_bytes());
+                game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+                    .copy_from_slice(&game_state.to_bytes());
+            }
+            msg!("SitAndGoFinished");
+            return Ok(());
+        }
+    }
 
-    // Reset game state
     let clock = Clock::get()?;
-    game_state.reset();
-    game_state.last_action_timestamp = clock.unix_timestamp;
-    game_state.cards_left_in_deck = DECK_SIZE as u8;
-
-    // Since players are already in the game, advance to Shuffling phase
-    if game_config.current_players >= MIN_PLAYERS {
-        game_state.game_phase = GamePhase::Shuffling as u8;
-        game_state.shuffling_state = ShufflingState::Generating as u8;
-        game_state.current_turn = (game_config.dealer_index + 3) % game_config.max_players;
-    }
+    prepare_next_hand(&mut game_config, &mut game_state, &mut player_list, clock.unix_timestamp);
 
     // Reset deck state (use zero-copy to avoid 3361-byte stack allocation)
     let mut deck_state = unsafe {
@@ -108,18 +113,7 @@ pub fn process_start_next_game(
     // Reset all player states
     for i in 0..game_config.max_players {
         if let Some(state_acc) = player_states_accounts[i as usize] {
-            let mut player_state = unsafe {
-                PlayerState::from_bytes(state_acc.borrow_data_unchecked())
-                    .ok_or(PokerError::InvalidAccountData)?
-            };
-
-            // Keep player key and chip count, reset everything else
-            player_state.reset_for_new_game();
-
-            unsafe {
-                state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
-                    .copy_from_slice(&player_state.to_bytes());
-            }
+            reset_player_account(unsafe { state_acc.borrow_mut_data_unchecked() })?;
         }
     }
 
@@ -132,22 +126,116 @@ pub fn process_start_next_game(
             .copy_from_slice(&game_state.to_bytes());
         community_acc.borrow_mut_data_unchecked()[..COMMUNITY_CARDS_SIZE]
             .copy_from_slice(&community_cards.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
     }
 
     msg!("NextGameStarted");
     Ok(())
 }
 
+/// Move the button and reset the table-wide state for the next hand
+///
+/// Deck, accumulator, community cards and PlayerStates are reset separately
+/// through their own accounts.
+pub(crate) fn prepare_next_hand(
+    game_config: &mut GameConfig,
+    game_state: &mut GameState,
+    player_list: &mut PlayerList,
+    timestamp: i64,
+) {
+    // Move the button so the big blind advances exactly one occupied seat
+    game_config.advance_dealer(player_list);
+
+    // Increment game number
+    game_config.game_number += 1;
+
+    // Reset game state
+    game_state.reset(game_config.deck_size);
+    game_state.last_action_timestamp = timestamp;
+
+    // Since players are already in the game, advance to Shuffling phase
+    if game_config.current_players >= MIN_PLAYERS {
+        game_state.game_phase = GamePhase::Shuffling as u8;
+        game_state.shuffling_state = ShufflingState::Generating as u8;
+        game_state.current_turn =
+            first_occupied_from(player_list, game_config.max_players, game_config.dealer_index + 3);
+    }
+
+    // Clear stale reveal bits from the previous hand
+    player_list.reset_for_next_game();
+}
+
+/// Chip stack of every seat (0 for empty seats)
+///
+/// Deciding a sit-and-go needs every seated player's state, so a missing or
+/// mismatched account is rejected rather than read as an empty stack.
+fn seat_stacks(
+    player_states_accounts: &[Option<&AccountInfo>],
+    player_list: &PlayerList,
+    game_config: &GameConfig,
+) -> Result<[u64; MAX_PLAYERS as usize], ProgramError> {
+    let mut stacks = [0u64; MAX_PLAYERS as usize];
+    for seat in 0..game_config.max_players {
+        let Some(seated) = player_list.get_player(seat) else {
+            continue;
+        };
+        let state_acc = player_states_accounts[seat as usize].ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let player_state = unsafe {
+            PlayerStateRef::from_bytes(state_acc.borrow_data_unchecked())
+                .ok_or(PokerError::InvalidAccountData)?
+        };
+        if player_state.player() != seated || player_state.game_id() != &game_config.game_id {
+            return Err(PokerError::InvalidAccountData.into());
+        }
+        stacks[seat as usize] = player_state.chips();
+    }
+    Ok(stacks)
+}
+
+/// Reset a PlayerState account for the next hand
+///
+/// Keeps the player key and chip count; everything else, including the lock
+/// public keys stored past the serialized state, starts over.
+fn reset_player_account(data: &mut [u8]) -> Result<(), PokerError> {
+    let mut player_state = PlayerState::from_bytes(data).ok_or(PokerError::InvalidAccountData)?;
+    player_state.reset_for_new_game();
+    data[..PLAYER_STATE_SIZE].copy_from_slice(&player_state.to_bytes());
+    PlayerStateMut::from_bytes(data)
+        .ok_or(PokerError::InvalidAccountData)?
+        .clear_lock_public_keys();
+    Ok(())
+}
+
+/// The only seat with chips left, if exactly one player still has any
+fn sit_and_go_winner(stacks: &[u64]) -> Option<u8> {
+    let mut with_chips = stacks.iter().enumerate().filter(|&(_, &chips)| chips > 0);
+    match (with_chips.next(), with_chips.next()) {
+        (Some((seat, _)), None) => Some(seat as u8),
+        _ => None,
+    }
+}
+
+/// End the tournament: the table closes and `current_turn` names the winner's seat
+fn finish_sit_and_go(game_state: &mut GameState, game_config: &mut GameConfig, winner: u8) {
+    game_state.game_phase = GamePhase::Finished as u8;
+    game_state.texas_state = TexasHoldEmState::Finished as u8;
+    game_state.current_turn = winner;
+    game_config.set_accepting_players(false);
+}
+
 impl GameState {
-    pub fn reset(&mut self) {
+    /// Clear the hand for a fresh `deck_size`-card deck (52, or 36 short)
+    pub fn reset(&mut self, deck_size: u8) {
         self.game_phase = GamePhase::WaitingForPlayers as u8;
         self.shuffling_state = ShufflingState::NotStarted as u8;
         self.drawing_state = DrawingState::NotDrawn as u8;
-        self.texas_state = TexasHoldEmState::NotStarted as u8;
-        self.betting_round_state = BettingRoundState::PreFlop as u8;
+        // Blinds are posted once the deck is locked, as in the first hand
+        self.texas_state = TexasHoldEmState::Betting as u8;
+        self.betting_round_state = BettingRoundState::Blinds as u8;
         self.community_cards_state = CommunityCardsState::FlopAwaiting as u8;
         self.current_turn = 0;
-        self.cards_left_in_deck = DECK_SIZE as u8;
+        self.cards_left_in_deck = deck_size;
         self.num_folded_players = 0;
         self.pot_size = 0;
         self.pot = 0;
@@ -163,6 +251,10 @@ impl GameState {
         self.cards_drawn = 0;
         self.card_to_reveal = 0;
         self.active_player_count = 0;
+        self.clear_action_log();
+        self.players_to_act = 0;
+        // reveal_round keeps counting across hands, so a reveal signed in
+        // an earlier hand can't match a card of this one
     }
 }
 
@@ -189,3 +281,226 @@ impl PlayerState {
         self.reset_for_next_game();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(seats: u8, dealer_index: u8) -> (GameConfig, PlayerList) {
+        let mut game_config =
+            GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], seats, 10, 100, 0);
+        game_config.dealer_index = dealer_index;
+        game_config.current_players = seats;
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for i in 1..=seats {
+            player_list.add_player([i; 32]);
+        }
+        (game_config, player_list)
+    }
+
+    #[test]
+    fn test_sit_and_go_ends_with_one_stack() {
+        let (mut game_config, _) = table(3, 0);
+        game_config.game_mode = GameMode::SitAndGo as u8;
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+
+        // Two players still have chips: keep dealing
+        assert_eq!(sit_and_go_winner(&[0, 1200, 300]), None);
+
+        // Seat 1 has busted everyone
+        let winner = sit_and_go_winner(&[0, 1500, 0]).unwrap();
+        assert_eq!(winner, 1);
+        finish_sit_and_go(&mut game_state, &mut game_config, winner);
+        assert_eq!(game_state.game_phase(), GamePhase::Finished);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Finished);
+        assert_eq!(game_state.current_turn, 1);
+        assert!(!game_config.is_accepting_players());
+
+        // Nobody with chips is not a win either
+        assert_eq!(sit_and_go_winner(&[0, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_reset_clears_deck_counters() {
+        use crate::state::player_state::PLAYER_STATE_ACCOUNT_SIZE;
+        use crate::test_utils::make_deck_state;
+
+        // Mid-hand: flop dealt, a turn card up for reveal
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.cards_left_in_deck = 41;
+        game_state.cards_drawn = 6;
+        game_state.card_to_reveal = 41;
+        game_state.is_deck_submitted = 1;
+        game_state.player_cards_opened = 2;
+        game_state.is_everybody_all_in = 1;
+        game_state.reveal_round = 5;
+        game_state.reset(DECK_SIZE as u8);
+        assert_eq!(game_state.cards_left_in_deck, DECK_SIZE as u8);
+        assert_eq!(game_state.cards_drawn, 0);
+        assert_eq!(game_state.card_to_reveal, 0);
+        assert_eq!(game_state.pending_reveal_count(), 0);
+        assert!(!game_state.is_deck_submitted());
+        assert_eq!(game_state.player_cards_opened, 0);
+        assert!(!game_state.is_everybody_all_in());
+        // Still counting, so last hand's reveals stay stale
+        assert_eq!(game_state.reveal_round, 5);
+
+        // A short deck is drawn from its 36th card down
+        game_state.reset(SHORT_DECK_SIZE as u8);
+        assert_eq!(game_state.cards_left_in_deck, SHORT_DECK_SIZE as u8);
+
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+        community_cards.add_opened_card(&[1u8; 32], &[2u8; 32], 12);
+        community_cards.reset();
+        assert_eq!(community_cards, CommunityCards::new(0, [0u8; 32]));
+
+        let mut deck_data = make_deck_state();
+        let mut deck_state = DeckStateMut::from_bytes(&mut deck_data).unwrap();
+        deck_state.set_card_point(51, &[3u8; 32], &[4u8; 32]);
+        deck_state.set_card_owner(51, &[5u8; 32]);
+        deck_state.reset_for_next_game();
+        assert_eq!(deck_state.get_card_point(51), (&[0u8; 32], &[0u8; 32]));
+        assert_eq!(deck_state.get_card_owner(51), &[0u8; 32]);
+
+        // Hole cards, the lock commitment and the committed keys all go
+        let mut player_state = PlayerState::new(0, [0u8; 32], [6u8; 32], 1, 700, [0u8; 32]);
+        player_state.hole_cards = [51, 49];
+        player_state.hole_cards_count = 2;
+        player_state.lock_committed = 1;
+        let mut account = player_state.to_bytes().to_vec();
+        account.resize(PLAYER_STATE_ACCOUNT_SIZE, 0);
+        PlayerStateMut::from_bytes(&mut account)
+            .unwrap()
+            .set_lock_public_keys(0, &[[8u8; 32]; 2])
+            .unwrap();
+
+        reset_player_account(&mut account).unwrap();
+        let restored = PlayerState::from_bytes(&account).unwrap();
+        assert_eq!(restored.hole_cards, [255, 255]);
+        assert_eq!(restored.hole_cards_count, 0);
+        assert_eq!(restored.lock_committed, 0);
+        assert_eq!(restored.chips, 700);
+        assert!(account[PLAYER_STATE_SIZE..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_next_hand_clears_revealed_bitmap() {
+        // Last hand ended with every seat having revealed the river
+        let (mut game_config, mut player_list) = table(3, 0);
+        for seat in 0..3 {
+            player_list.mark_revealed(seat);
+        }
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+        game_state.pot_claimed = 1;
+
+        prepare_next_hand(&mut game_config, &mut game_state, &mut player_list, 42);
+
+        let restored = PlayerList::from_bytes(&player_list.to_bytes()).unwrap();
+        assert_eq!(restored.revealed_bitmap, 0);
+        assert!((0..3).all(|seat| !restored.has_revealed(seat)));
+        // Players keep their seats for the new hand
+        assert_eq!(restored.get_player(2), Some(&[3u8; 32]));
+        assert_eq!(game_state.shuffling_state(), ShufflingState::Generating);
+        assert_eq!(game_state.last_action_timestamp, 42);
+        assert_eq!(game_config.game_number, 1);
+    }
+
+    #[test]
+    fn test_next_hand_skips_vacated_seat() {
+        use crate::instructions::{finish_generate, join_game::take_seat, leave::vacate_seat};
+
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 100, 0);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for player in 1..=3u8 {
+            take_seat(&mut game_config, &mut player_list, &[player; 32]).unwrap();
+        }
+
+        // Seat 1 leaves once the first hand is settled
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.game_phase = GamePhase::Drawing as u8;
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+        let mut player_state = PlayerState::new(0, [0u8; 32], [2u8; 32], 1, 500, [0u8; 32]);
+        vacate_seat(&mut game_config, &mut game_state, &mut player_list, &mut player_state, 1);
+        assert_eq!(player_list.count, 2);
+
+        prepare_next_hand(&mut game_config, &mut game_state, &mut player_list, 0);
+        assert_eq!(game_config.dealer_index, 2);
+        assert_eq!(game_state.current_turn, 2);
+
+        // Two generate steps finish the phase, never handing seat 1 the turn
+        finish_generate(&mut game_state, &game_config, &player_list);
+        assert_eq!(game_state.current_turn, 0);
+        assert_eq!(game_state.shuffling_state(), ShufflingState::Generating);
+        finish_generate(&mut game_state, &game_config, &player_list);
+        assert_eq!(game_state.shuffling_state(), ShufflingState::Shuffling);
+        assert_eq!(game_state.current_turn, 2);
+
+        // The next player to join takes the vacated seat
+        assert_eq!(take_seat(&mut game_config, &mut player_list, &[4u8; 32]), Ok(1));
+        assert_eq!(player_list.count, 3);
+    }
+
+    #[test]
+    fn test_button_rotates_with_full_table() {
+        let (mut game_config, player_list) = table(3, 2);
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.dealer_index, 0);
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.dealer_index, 1);
+        assert_eq!(game_config.dead_button, 0);
+    }
+
+    #[test]
+    fn test_button_alternates_heads_up_at_a_larger_table() {
+        // A 6-max table down to seats 1 and 4, button on seat 1
+        let (mut game_config, mut player_list) = table(6, 1);
+        for seat in [0, 2, 3, 5] {
+            player_list.remove_player(seat);
+        }
+        game_config.current_players = 2;
+        assert_eq!(game_config.bb_seat(&player_list), 4);
+
+        // The blinds swap between the two players each hand
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.dealer_index, 4);
+        assert_eq!(game_config.sb_seat(), 4);
+        assert_eq!(game_config.bb_seat(&player_list), 1);
+        assert_eq!(game_config.dead_button, 0);
+
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.dealer_index, 1);
+        assert_eq!(game_config.bb_seat(&player_list), 4);
+    }
+
+    #[test]
+    fn test_dead_button_after_small_blind_leaves() {
+        // Button 0, SB 1, BB 2; the small blind leaves after the hand
+        let (mut game_config, mut player_list) = table(4, 0);
+        player_list.remove_player(1);
+
+        // Hand 2: BB moves to seat 3, button lands on the empty seat 1
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.bb_seat(&player_list), 3);
+        assert_eq!(game_config.sb_seat(), 2);
+        assert_eq!(game_config.dealer_index, 1);
+        assert_eq!(game_config.dead_button, 1);
+        assert_eq!(game_config.dealer_seat(&player_list), 2);
+
+        // Hand 3: BB moves to seat 0, button is live again on seat 2
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.bb_seat(&player_list), 0);
+        assert_eq!(game_config.dealer_index, 2);
+        assert_eq!(game_config.dead_button, 0);
+
+        // Hand 4: BB skips the empty seat 1 and lands on seat 2; the small
+        // blind is dead and the big blind posts first
+        game_config.advance_dealer(&player_list);
+        assert_eq!(game_config.bb_seat(&player_list), 2);
+        assert_eq!(game_config.dealer_index, 0);
+        assert_eq!(game_config.dead_button, 0);
+        assert!(!game_config.has_small_blind(&player_list));
+        assert_eq!(game_config.first_blind_seat(&player_list), 2);
+    }
+}
@@ -0,0 +1,185 @@
+//! Straddle instruction - optional UTG blind raise posted before cards
+//!
+//! Allowed only when `GameConfig.allow_straddle` is set, after both blinds
+//! are in and before the first hole card is drawn. The straddle doubles the
+//! big blind and gives the straddler the last pre-flop action.
+
+use pinocchio::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar}, ProgramResult,
+};
+
+use crate::{error::PokerError, state::*, utils::assert_own_player_state};
+
+pub fn process_straddle(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let mut iter = accounts.iter();
+    let player = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_config_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let game_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_state_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !player.is_signer() {
+        return Err(PokerError::InvalidSigner.into());
+    }
+
+    let game_config = unsafe {
+        GameConfig::from_bytes(game_config_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut game_state = unsafe {
+        GameState::from_bytes(game_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_state = unsafe {
+        PlayerState::from_bytes(player_state_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    let mut player_list = unsafe {
+        PlayerList::from_bytes(player_list_acc.borrow_data_unchecked())
+            .ok_or(PokerError::InvalidAccountData)?
+    };
+
+    // Only the UTG player may straddle
+    let utg_seat = game_config.utg_seat(&player_list);
+    let utg_player = player_list.get_player(utg_seat)
+        .ok_or(PokerError::NotAPlayer)?;
+    if utg_player != player.key() {
+        return Err(PokerError::NotYourTurn.into());
+    }
+    assert_own_player_state(&player_state, player.key(), &game_config.game_id)?;
+
+    apply_straddle(&mut game_state, &game_config, &mut player_state)?;
+    if player_state.chips == 0 {
+        player_list.mark_all_in(utg_seat);
+    }
+
+    let clock = Clock::get()?;
+    game_state.last_action_timestamp = clock.unix_timestamp;
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
+        player_list_acc.borrow_mut_data_unchecked()[..PLAYER_LIST_SIZE]
+            .copy_from_slice(&player_list.to_bytes());
+    }
+
+    msg!("StraddlePlaced");
+    Ok(())
+}
+
+/// Post the straddle for the UTG player
+fn apply_straddle(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_state: &mut PlayerState,
+) -> ProgramResult {
+    if game_config.allow_straddle == 0 {
+        return Err(PokerError::StraddleNotAllowed.into());
+    }
+
+    // Window: blinds complete, no hole cards drawn, not already straddled
+    if game_state.texas_state() != TexasHoldEmState::Drawing
+        || game_state.cards_drawn != 0
+        || game_state.current_call_amount != game_config.big_blind()
+    {
+        return Err(PokerError::StraddleNotAllowed.into());
+    }
+
+    let amount = game_config.straddle_amount() - player_state.current_bet;
+    if player_state.chips < amount {
+        return Err(PokerError::InsufficientChips.into());
+    }
+
+    player_state.chips -= amount;
+    player_state.current_bet += amount;
+    game_state.pot += amount;
+    game_state.current_call_amount = player_state.current_bet;
+    game_state.record_action(player_state.seat_index, BetActionType::Blind, amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(allow_straddle: bool) -> (GameState, GameConfig, PlayerState) {
+        let mut game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 4, 10, 100, 0);
+        game_config.allow_straddle = allow_straddle as u8;
+
+        // Blinds posted: SB seat 1, BB seat 2, UTG seat 3
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Drawing as u8;
+        game_state.pot = 30;
+        game_state.current_call_amount = 20;
+
+        let player_state = PlayerState::new(0, [0u8; 32], [4u8; 32], 3, 1000, [0u8; 32]);
+        (game_state, game_config, player_state)
+    }
+
+    #[test]
+    fn test_straddle_doubles_call_and_shifts_last_action() {
+        let (mut game_state, game_config, mut player_state) = setup(true);
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..4 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        assert_eq!(game_config.preflop_seats(&player_list, game_state.current_call_amount), (3, 2));
+
+        apply_straddle(&mut game_state, &game_config, &mut player_state).unwrap();
+
+        assert_eq!(game_state.current_call_amount, 40);
+        assert_eq!(game_state.pot, 70);
+        assert_eq!(player_state.chips, 960);
+        // Action now starts left of the straddler, who acts last
+        assert_eq!(game_config.preflop_seats(&player_list, game_state.current_call_amount), (0, 3));
+
+        // Only one straddle per hand
+        assert_eq!(
+            apply_straddle(&mut game_state, &game_config, &mut player_state),
+            Err(PokerError::StraddleNotAllowed.into())
+        );
+    }
+
+    #[test]
+    fn test_straddle_rejects_foreign_player_state() {
+        let (_, game_config, player_state) = setup(true);
+        assert_eq!(assert_own_player_state(&player_state, &[4u8; 32], &game_config.game_id), Ok(()));
+
+        // The UTG signer passing another seat's PlayerState
+        assert_eq!(
+            assert_own_player_state(&player_state, &[5u8; 32], &game_config.game_id),
+            Err(PokerError::InvalidAccountData)
+        );
+        // The right player, but their PlayerState from another table
+        assert_eq!(
+            assert_own_player_state(&player_state, &[4u8; 32], &[9u8; 32]),
+            Err(PokerError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_straddle_requires_flag_and_window() {
+        let (mut game_state, game_config, mut player_state) = setup(false);
+        assert_eq!(
+            apply_straddle(&mut game_state, &game_config, &mut player_state),
+            Err(PokerError::StraddleNotAllowed.into())
+        );
+
+        let (mut game_state, game_config, mut player_state) = setup(true);
+        game_state.cards_drawn = 1;
+        assert_eq!(
+            apply_straddle(&mut game_state, &game_config, &mut player_state),
+            Err(PokerError::StraddleNotAllowed.into())
+        );
+    }
+}
@@ -5,10 +5,17 @@ use pinocchio::{
     sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
 
-use crate::{error::PokerError, poker::*, state::*};
+use crate::{
+    constants::{HOLE_CARDS_PER_PLAYER, MAX_COMMUNITY_CARDS},
+    error::PokerError,
+    instructions::claim_pot::{collect_player_states, settle_pot},
+    poker::*,
+    state::*,
+    utils::{assert_is_turn, get_next_turn, players_in_hand},
+};
 
 pub fn process_submit_best_hand(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
@@ -25,6 +32,8 @@ pub fn process_submit_best_hand(
     let accumulator_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let community_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
     let player_list_acc = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // On an auto-pay table the last submission also takes ClaimPot's accounts:
+    // vault, token program, one PlayerState per seat, then the payees' token accounts
 
     if !player.is_signer() {
         return Err(PokerError::InvalidSigner.into());
@@ -51,7 +60,7 @@ pub fn process_submit_best_hand(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
-    let _community_cards = unsafe {
+    let community_cards = unsafe {
         CommunityCards::from_bytes(community_acc.borrow_data_unchecked())
             .ok_or(PokerError::InvalidAccountData)?
     };
@@ -61,17 +70,22 @@ pub fn process_submit_best_hand(
             .ok_or(PokerError::InvalidAccountData)?
     };
 
+    // The hand is built from this PlayerState's hole cards, so it must be the signer's own
+    if player_state.player != *player.key() || player_state.game_id != game_config.game_id {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+
     // Validate state
     if game_state.texas_state() != TexasHoldEmState::SubmitBest {
         return Err(PokerError::InvalidTexasState.into());
     }
 
     // Validate turn
-    let current_player = player_list.get_player(game_state.current_turn)
-        .ok_or(PokerError::NotAPlayer)?;
-    if current_player != player.key() {
-        return Err(PokerError::NotYourTurn.into());
-    }
+    assert_is_turn(&game_state, &player_list, player.key())?;
+    assert_not_submitted(&player_state)?;
+
+    // Card IDs come from the accumulator's deck mapping
+    assert_deck_mapped(&game_state)?;
 
     // Parse the 5 cards from data
     let mut card_points = [([0u8; 32], [0u8; 32]); 5];
@@ -81,14 +95,27 @@ pub fn process_submit_best_hand(
         card_points[i].1.copy_from_slice(&data[offset + 32..offset + 64]);
     }
 
-    // Validate cards are from player's cards or community cards
-    // (simplified validation - in production would check more thoroughly)
+    // Validate cards are from player's opened hole cards or the board
+    let hole_points = [player_state.revealed_card_point(0), player_state.revealed_card_point(1)];
+    for (qx, qy) in card_points.iter() {
+        if !is_playable_card(qx, qy, &hole_points, &community_cards) {
+            return Err(PokerError::IllegalCard.into());
+        }
+    }
 
-    // Convert points to card IDs using accumulator
+    // Convert points to card IDs: board cards were resolved when opened,
+    // hole cards go through the accumulator
     let mut card_ids: [i8; 5] = [-1; 5];
     for (i, (qx, qy)) in card_points.iter().enumerate() {
-        if let Some(id) = accumulator.find_card_by_point(qx, qy) {
-            card_ids[i] = id;
+        let id = community_cards
+            .cached_id(qx, qy)
+            .or_else(|| accumulator.find_card_by_point(qx, qy));
+        if let Some(id) = id {
+            let card = CardId::try_from_u8(id as u8)?;
+            if game_config.is_short_deck() && !is_short_deck_card(card as i8) {
+                return Err(PokerError::IllegalCard.into());
+            }
+            card_ids[i] = card as i8;
         } else {
             return Err(PokerError::IllegalCard.into());
         }
@@ -104,7 +131,7 @@ pub fn process_submit_best_hand(
     }
 
     // Evaluate hand
-    let (hand_enum, rated_cards) = evaluate_hand(card_ids);
+    let (hand_enum, rated_cards) = evaluate_hand_for_deck(card_ids, game_config.deck_size);
 
     // Store results
     player_state.submitted_hand = hand_enum as u8;
@@ -113,36 +140,242 @@ pub fn process_submit_best_hand(
     // Rank against other submitted hands
     // (simplified - in production would compare with all players)
     player_state.hand_rank = 0;
+    player_state.has_submitted = 1;
 
     game_state.num_submitted_hands += 1;
 
     let clock = Clock::get()?;
     game_state.last_action_timestamp = clock.unix_timestamp;
 
-    // Check if all players have submitted
-    let players_in_play = game_config.max_players - game_state.num_folded_players;
+    advance_submissions(&mut game_state, &game_config, &player_list);
+
+    // Written before settling so the winner check sees this submission
+    unsafe {
+        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
+            .copy_from_slice(&player_state.to_bytes());
+    }
+    msg!("PlayerHand");
+
+    settle_if_auto_pay(
+        program_id,
+        game_config_acc,
+        &game_config,
+        &mut game_state,
+        &player_list,
+        &mut iter,
+    )?;
+
+    unsafe {
+        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
+            .copy_from_slice(&game_state.to_bytes());
+    }
+    Ok(())
+}
+
+/// Pay the pot out now if the last submission (or muck) ended showdown on
+/// an auto-pay table
+///
+/// Reads the same trailing accounts as ClaimPot: the pot account, the token
+/// program, every seat's PlayerState, then the payees. Shared with `Muck`.
+pub(crate) fn settle_if_auto_pay<'a>(
+    program_id: &Pubkey,
+    game_config_acc: &AccountInfo,
+    game_config: &GameConfig,
+    game_state: &mut GameState,
+    player_list: &PlayerList,
+    iter: &mut impl Iterator<Item = &'a AccountInfo>,
+) -> ProgramResult {
+    if !auto_pay_due(game_state, game_config) {
+        return Ok(());
+    }
+    let pot_account = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let _token_program = iter.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let player_states_accounts = collect_player_states(iter);
+    settle_pot(
+        program_id,
+        game_config_acc,
+        game_config,
+        game_state,
+        pot_account,
+        player_list,
+        &player_states_accounts,
+        iter,
+    )?;
+    msg!("PotClaimed");
+    Ok(())
+}
+
+/// Whether this submission ended showdown on a table that pays out at once
+pub(crate) fn auto_pay_due(game_state: &GameState, game_config: &GameConfig) -> bool {
+    game_config.auto_pay() && game_state.texas_state() == TexasHoldEmState::ClaimPot
+}
+
+/// Go to ClaimPot once every player still in the hand has submitted
+///
+/// Otherwise the turn passes to the next seat still in. Shared with `Muck`,
+/// which lowers the count of players still in.
+pub(crate) fn advance_submissions(
+    game_state: &mut GameState,
+    game_config: &GameConfig,
+    player_list: &PlayerList,
+) {
+    let players_in_play = players_in_hand(player_list, game_state.num_folded_players);
     if game_state.num_submitted_hands >= players_in_play {
         game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
-        game_state.current_turn = game_config.dealer_index;
+        game_state.current_turn = game_config.dealer_seat(player_list);
         msg!("TexasHoldEmStateChanged: ClaimPot");
     } else {
-        game_state.current_turn = next_active_player(
-            game_state.current_turn,
-            game_config.max_players,
-        );
+        game_state.current_turn =
+            get_next_turn(game_state.current_turn, player_list, game_config.max_players);
     }
+}
 
-    unsafe {
-        game_state_acc.borrow_mut_data_unchecked()[..GAME_STATE_SIZE]
-            .copy_from_slice(&game_state.to_bytes());
-        player_state_acc.borrow_mut_data_unchecked()[..PLAYER_STATE_SIZE]
-            .copy_from_slice(&player_state.to_bytes());
+/// Reject lookups against a deck mapping that was never submitted
+fn assert_deck_mapped(game_state: &GameState) -> Result<(), PokerError> {
+    if !game_state.is_deck_submitted() {
+        return Err(PokerError::DeckNotSubmitted);
     }
+    Ok(())
+}
 
-    msg!("PlayerHand");
+/// Reject a second submission, which would overcount `num_submitted_hands`
+pub(crate) fn assert_not_submitted(player_state: &PlayerState) -> Result<(), PokerError> {
+    if player_state.has_submitted() {
+        return Err(PokerError::HandAlreadySubmitted);
+    }
     Ok(())
 }
 
-fn next_active_player(current: u8, max: u8) -> u8 {
-    (current + 1) % max
+/// Whether a submitted point is one of the player's opened hole cards or an opened board card
+///
+/// `hole_points` are the decompressed hole cards (`None` if not opened).
+/// With duplicates rejected separately, this alone keeps a hand to the
+/// player's two hole cards plus the board.
+fn is_playable_card(
+    qx: &[u8; 32],
+    qy: &[u8; 32],
+    hole_points: &[Option<[u8; 64]>; HOLE_CARDS_PER_PLAYER as usize],
+    community_cards: &CommunityCards,
+) -> bool {
+    let opened = (community_cards.opened_count as usize).min(MAX_COMMUNITY_CARDS as usize);
+    hole_points
+        .iter()
+        .flatten()
+        .chain(community_cards.opened_cards[..opened].iter())
+        .any(|point| point[..32] == qx[..] && point[32..] == qy[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(seed: u8) -> ([u8; 32], [u8; 32]) {
+        ([seed; 32], [seed.wrapping_add(100); 32])
+    }
+
+    /// Valid curve point `seed * G`, as the player's opened hole card
+    fn hole_point(seed: u8) -> [u8; 64] {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let mut scalar = [0u8; 32];
+        scalar[31] = seed;
+        crate::crypto::bn254::bn254_mul(&generator, &scalar).unwrap()
+    }
+
+    fn split(point: &[u8; 64]) -> ([u8; 32], [u8; 32]) {
+        (point[..32].try_into().unwrap(), point[32..].try_into().unwrap())
+    }
+
+    fn table() -> (PlayerState, CommunityCards) {
+        use crate::crypto::bn254::bn254_g1_compress;
+
+        let mut player_state = PlayerState::new(0, [0u8; 32], [9u8; 32], 0, 1000, [0u8; 32]);
+        player_state.revealed_cards = [
+            bn254_g1_compress(&hole_point(1)).unwrap(),
+            bn254_g1_compress(&hole_point(2)).unwrap(),
+        ];
+        player_state.revealed_cards_count = 2;
+        let mut community_cards = CommunityCards::new(0, [0u8; 32]);
+        for seed in 10..15 {
+            let (qx, qy) = point(seed);
+            community_cards.add_opened_card(&qx, &qy, -1);
+        }
+        (player_state, community_cards)
+    }
+
+    #[test]
+    fn test_auto_pay_settles_on_last_submission() {
+        use crate::instructions::claim_pot::{assert_claimable, mark_pot_claimed};
+        use crate::test_utils::{make_game_config, make_game_state, make_player_list};
+
+        let mut game_config = GameConfig::from_bytes(&make_game_config(2, 10)).unwrap();
+        let player_list = PlayerList::from_bytes(&make_player_list(&[[1; 32], [2; 32]])).unwrap();
+        let mut game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::SubmitBest, 1, 200)).unwrap();
+
+        // First of two submissions: showdown isn't over
+        game_config.auto_pay = 1;
+        game_state.num_submitted_hands = 1;
+        advance_submissions(&mut game_state, &game_config, &player_list);
+        assert!(!auto_pay_due(&game_state, &game_config));
+
+        // The last one pays out in the same instruction
+        game_state.num_submitted_hands = 2;
+        advance_submissions(&mut game_state, &game_config, &player_list);
+        assert!(auto_pay_due(&game_state, &game_config));
+        assert_eq!(assert_claimable(&game_state), Ok(()));
+        mark_pot_claimed(&mut game_state);
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Finished);
+        assert_eq!(game_state.pot, 0);
+
+        // Nothing left for a separate ClaimPot
+        game_state.texas_state = TexasHoldEmState::ClaimPot as u8;
+        assert_eq!(assert_claimable(&game_state), Err(PokerError::PotAlreadyClaimed));
+
+        // Without the flag the pot waits for ClaimPot
+        game_config.auto_pay = 0;
+        assert!(!auto_pay_due(&game_state, &game_config));
+    }
+
+    #[test]
+    fn test_playable_cards() {
+        let (player_state, community_cards) = table();
+        let hole = [player_state.revealed_card_point(0), player_state.revealed_card_point(1)];
+        let (qx, qy) = split(&hole_point(2));
+        assert!(is_playable_card(&qx, &qy, &hole, &community_cards));
+        let (qx, qy) = point(12);
+        assert!(is_playable_card(&qx, &qy, &hole, &community_cards));
+        let (qx, qy) = point(20);
+        assert!(!is_playable_card(&qx, &qy, &hole, &community_cards));
+
+        // Unopened hole cards don't count
+        let mut half_open = player_state;
+        half_open.revealed_cards_count = 1;
+        let hole = [half_open.revealed_card_point(0), half_open.revealed_card_point(1)];
+        let (qx, qy) = split(&hole_point(2));
+        assert!(!is_playable_card(&qx, &qy, &hole, &community_cards));
+    }
+
+    #[test]
+    fn test_submit_before_deck_mapped_rejected() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::SubmitBest as u8;
+        assert_eq!(assert_deck_mapped(&game_state), Err(PokerError::DeckNotSubmitted));
+
+        game_state.is_deck_submitted = 1;
+        assert_eq!(assert_deck_mapped(&game_state), Ok(()));
+    }
+
+    #[test]
+    fn test_double_submission_rejected() {
+        let (mut player_state, _) = table();
+        assert_eq!(assert_not_submitted(&player_state), Ok(()));
+
+        player_state.has_submitted = 1;
+        assert_eq!(assert_not_submitted(&player_state), Err(PokerError::HandAlreadySubmitted));
+
+        player_state.reset_for_next_game();
+        assert_eq!(assert_not_submitted(&player_state), Ok(()));
+    }
 }
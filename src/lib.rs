@@ -14,6 +14,9 @@ pub mod poker;
 pub mod state;
 pub mod utils;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 // Re-export for convenience
 pub use constants::*;
 pub use error::PokerError;
@@ -0,0 +1,141 @@
+//! Legal betting actions
+//!
+//! The betting rules enforced by `bet.rs` and `fold.rs`, as a pure function
+//! bots and UIs can evaluate off-chain against fetched accounts.
+
+use crate::{
+    state::{GameConfig, GameState, PlayerState},
+    utils::{assert_can_act, assert_not_all_in},
+};
+
+/// What a player may do on their turn, and for how much
+///
+/// Amounts are chips put in by this action, as passed to `Bet`. Nothing is
+/// allowed when it isn't the player's turn to bet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActionSet {
+    pub can_fold: bool,
+    pub can_check: bool,
+    pub can_call: bool,
+    pub can_raise: bool,
+    /// Chips a call puts in (the whole stack when calling all-in for less)
+    pub call_amount: u64,
+    /// Smallest raise; the whole stack if a full raise can't be covered
+    pub min_bet: u64,
+    /// Largest raise: the player's stack
+    pub max_bet: u64,
+}
+
+/// Smallest legal raise increment: the last raise this round, at least a big blind
+pub fn min_raise(last_raise: u64, big_blind: u64) -> u64 {
+    last_raise.max(big_blind)
+}
+
+/// Actions open to `player_state` in the current betting round
+///
+/// Doesn't apply the optional effective-stack cap, which needs every seat's
+/// PlayerState; `Bet` may still cut a raise down to it.
+pub fn legal_actions(
+    game_state: &GameState,
+    player_state: &PlayerState,
+    game_config: &GameConfig,
+) -> ActionSet {
+    let can_act = assert_can_act(game_state.texas_state(), game_state.betting_round_state()).is_ok()
+        && game_state.current_turn == player_state.seat_index
+        && !player_state.is_folded()
+        && assert_not_all_in(player_state.chips).is_ok();
+    if !can_act {
+        return ActionSet::default();
+    }
+
+    let chips = player_state.chips;
+    let to_call = player_state.amount_to_call(game_state);
+    let full_raise = to_call.saturating_add(min_raise(game_state.last_raise, game_config.big_blind()));
+    let can_raise = chips > to_call;
+    ActionSet {
+        can_fold: true,
+        can_check: to_call == 0,
+        can_call: to_call > 0,
+        can_raise,
+        call_amount: to_call.min(chips),
+        min_bet: if can_raise { full_raise.min(chips) } else { 0 },
+        max_bet: if can_raise { chips } else { 0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{BettingRoundState, TexasHoldEmState};
+
+    fn table(call_amount: u64, last_raise: u64) -> (GameState, GameConfig) {
+        let game_config = GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], 3, 10, 20, 1000);
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.current_turn = 1;
+        game_state.current_call_amount = call_amount;
+        game_state.last_raise = last_raise;
+        (game_state, game_config)
+    }
+
+    fn player(chips: u64, current_bet: u64) -> PlayerState {
+        let mut player_state = PlayerState::new(0, [0u8; 32], [2u8; 32], 1, chips, [0u8; 32]);
+        player_state.current_bet = current_bet;
+        player_state
+    }
+
+    #[test]
+    fn test_no_bet_to_face() {
+        let (game_state, game_config) = table(0, 0);
+        let actions = legal_actions(&game_state, &player(500, 0), &game_config);
+        assert_eq!(
+            actions,
+            ActionSet {
+                can_fold: true,
+                can_check: true,
+                can_call: false,
+                can_raise: true,
+                call_amount: 0,
+                min_bet: 20,
+                max_bet: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn test_facing_a_bet() {
+        // 100 to call after a raise of 60, with 40 already in
+        let (game_state, game_config) = table(140, 60);
+        let actions = legal_actions(&game_state, &player(500, 40), &game_config);
+        assert!(!actions.can_check);
+        assert!(actions.can_call && actions.can_raise && actions.can_fold);
+        assert_eq!((actions.call_amount, actions.min_bet, actions.max_bet), (100, 160, 500));
+
+        // A stack between the call and a full raise can only shove
+        let actions = legal_actions(&game_state, &player(130, 40), &game_config);
+        assert_eq!((actions.call_amount, actions.min_bet, actions.max_bet), (100, 130, 130));
+
+        // A stack short of the call can only call all-in for less or fold
+        let actions = legal_actions(&game_state, &player(70, 40), &game_config);
+        assert!(actions.can_call && !actions.can_raise);
+        assert_eq!(actions.call_amount, 70);
+    }
+
+    #[test]
+    fn test_nothing_legal_out_of_turn() {
+        let (mut game_state, game_config) = table(0, 0);
+        let mut player_state = player(500, 0);
+
+        game_state.current_turn = 2;
+        assert_eq!(legal_actions(&game_state, &player_state, &game_config), ActionSet::default());
+
+        game_state.current_turn = 1;
+        player_state.is_folded = 1;
+        assert_eq!(legal_actions(&game_state, &player_state, &game_config), ActionSet::default());
+
+        player_state.is_folded = 0;
+        game_state.betting_round_state = BettingRoundState::Blinds as u8;
+        assert_eq!(legal_actions(&game_state, &player_state, &game_config), ActionSet::default());
+    }
+}
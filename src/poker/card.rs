@@ -2,6 +2,8 @@
 //!
 //! Ported from PokerHandUtils.sol
 
+use crate::{constants::SHORT_DECK_SIZE, error::PokerError};
+
 /// Card ID enum (0-51)
 /// Ace = 0, Two = 1, ... King = 12 for each suit
 /// Suits: Clubs (0-12), Diamonds (13-25), Hearts (26-38), Spades (39-51)
@@ -66,23 +68,28 @@ pub enum CardId {
     KingSpades = 51,
 }
 
-impl From<u8> for CardId {
-    fn from(value: u8) -> Self {
+impl CardId {
+    /// Checked conversion from a card index, rejecting anything outside 0-51
+    ///
+    /// Prefer this over `From<u8>`, which silently maps bad input to `AceClubs`.
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         if value < 52 {
-            unsafe { core::mem::transmute(value) }
+            Ok(unsafe { core::mem::transmute::<u8, CardId>(value) })
         } else {
-            CardId::AceClubs // Default
+            Err(PokerError::InvalidCardIndex)
         }
     }
 }
 
+impl From<u8> for CardId {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or(CardId::AceClubs)
+    }
+}
+
 impl From<i8> for CardId {
     fn from(value: i8) -> Self {
-        if value >= 0 && value < 52 {
-            unsafe { core::mem::transmute(value as u8) }
-        } else {
-            CardId::AceClubs // Default
-        }
+        u8::try_from(value).map_or(CardId::AceClubs, Self::from)
     }
 }
 
@@ -180,6 +187,33 @@ pub fn is_valid_card(code: i8) -> bool {
     code >= 0 && code < 52
 }
 
+/// Check if a card code is part of a short deck (Six through King, plus Ace)
+pub fn is_short_deck_card(code: i8) -> bool {
+    if !is_valid_card(code) {
+        return false;
+    }
+    let (value, _) = get_card_name(code);
+    value == CardValue::Ace || value >= CardValue::Six
+}
+
+/// Card ID at `position` of a deck of `deck_size` cards
+///
+/// A full deck is in ID order. A short deck keeps only its 36 cards, suit by
+/// suit (Ace, then Six through King), so positions `0..deck_size` never name
+/// a Two through Five.
+pub fn deck_card_id(deck_size: u8, position: usize) -> u8 {
+    if deck_size as usize != SHORT_DECK_SIZE {
+        return position as u8;
+    }
+    let per_suit = SHORT_DECK_SIZE / 4;
+    let suit = position / per_suit;
+    let value = match position % per_suit {
+        0 => CardValue::Ace as usize,
+        n => CardValue::Six as usize + n - 1,
+    };
+    (suit * 13 + value) as u8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +243,38 @@ mod tests {
         assert_eq!(get_card_order_value(CardValue::Two), 1);
         assert_eq!(get_card_order_value(CardValue::King), 12);
     }
+
+    #[test]
+    fn test_card_id_try_from_u8() {
+        assert_eq!(CardId::try_from_u8(0), Ok(CardId::AceClubs));
+        assert_eq!(CardId::try_from_u8(51), Ok(CardId::KingSpades));
+        assert_eq!(CardId::try_from_u8(52), Err(PokerError::InvalidCardIndex));
+        assert_eq!(CardId::try_from_u8(255), Err(PokerError::InvalidCardIndex));
+
+        // The lenient conversions fall back to AceClubs
+        assert_eq!(CardId::from(51u8), CardId::KingSpades);
+        assert_eq!(CardId::from(52u8), CardId::AceClubs);
+        assert_eq!(CardId::from(51i8), CardId::KingSpades);
+        assert_eq!(CardId::from(-1i8), CardId::AceClubs);
+    }
+
+    #[test]
+    fn test_short_deck_positions_skip_low_ranks() {
+        use crate::constants::DECK_SIZE;
+
+        let short = SHORT_DECK_SIZE as u8;
+        let mut seen = [false; DECK_SIZE];
+        for position in 0..SHORT_DECK_SIZE {
+            let id = deck_card_id(short, position);
+            assert!(is_short_deck_card(id as i8), "position {position} is card {id}");
+            assert!(!seen[id as usize]);
+            seen[id as usize] = true;
+        }
+        assert_eq!(deck_card_id(short, 0), CardId::AceClubs as u8);
+        assert_eq!(deck_card_id(short, 1), CardId::SixClubs as u8);
+        assert_eq!(deck_card_id(short, 35), CardId::KingSpades as u8);
+
+        // A full deck is every card in ID order
+        assert!((0..DECK_SIZE).all(|position| deck_card_id(DECK_SIZE as u8, position) == position as u8));
+    }
 }
@@ -4,6 +4,8 @@
 //!
 //! Evaluates 5-card poker hands and returns the hand type and ranked cards for tiebreaking
 
+use crate::constants::SHORT_DECK_SIZE;
+
 use super::card::{get_card_name, get_card_order_value};
 
 /// Poker hand types from best to worst
@@ -40,6 +42,23 @@ impl From<u8> for HandEnum {
     }
 }
 
+impl HandEnum {
+    /// Ranking strength for comparison (lower = better)
+    ///
+    /// In short-deck Hold'em a flush is harder to make than a full house,
+    /// so the two swap places.
+    pub fn strength(self, deck_size: u8) -> u8 {
+        if deck_size as usize == SHORT_DECK_SIZE {
+            match self {
+                HandEnum::FullHouse => return HandEnum::Flush as u8,
+                HandEnum::Flush => return HandEnum::FullHouse as u8,
+                _ => {}
+            }
+        }
+        self as u8
+    }
+}
+
 /// Sort hand by card order value (descending)
 pub fn sort_hand(cards: &mut [i8; 5]) {
     // Insertion sort (efficient for small arrays)
@@ -220,6 +239,164 @@ pub fn evaluate_hand(cards: [i8; 5]) -> (HandEnum, [i8; 5]) {
     (hand_val, ret_order)
 }
 
+/// Evaluate a 5-card hand for the given deck size
+///
+/// Short-deck games have no Two through Five, so A-6-7-8-9 plays as the
+/// lowest straight in place of the wheel.
+pub fn evaluate_hand_for_deck(cards: [i8; 5], deck_size: u8) -> (HandEnum, [i8; 5]) {
+    let (hand_val, ranked) = evaluate_hand(cards);
+    if deck_size as usize != SHORT_DECK_SIZE {
+        return (hand_val, ranked);
+    }
+
+    // Ace low short-deck straight (A-6-7-8-9)
+    if ranked == [13, 8, 7, 6, 5] {
+        match hand_val {
+            HandEnum::Flush => return (HandEnum::StraightFlush, [8, 7, 6, 5, 4]),
+            HandEnum::HighCard => return (HandEnum::Straight, [8, 7, 6, 5, 4]),
+            _ => {}
+        }
+    }
+
+    (hand_val, ranked)
+}
+
+/// Maximum length of a hand description in bytes
+pub const HAND_DESCRIPTION_CAPACITY: usize = 64;
+
+/// Fixed-capacity, stack-allocated text buffer for hand descriptions
+///
+/// Avoids heap allocation so it can be built and logged on-chain.
+#[derive(Clone, Copy)]
+pub struct HandDescription {
+    buf: [u8; HAND_DESCRIPTION_CAPACITY],
+    len: usize,
+}
+
+impl HandDescription {
+    /// Create an empty description
+    pub const fn new() -> Self {
+        Self { buf: [0u8; HAND_DESCRIPTION_CAPACITY], len: 0 }
+    }
+
+    /// Append text, truncating at capacity
+    pub fn push_str(&mut self, text: &str) {
+        for &byte in text.as_bytes() {
+            if self.len == HAND_DESCRIPTION_CAPACITY {
+                return;
+            }
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    /// View the description as a string slice
+    pub fn as_str(&self) -> &str {
+        // Only ASCII is ever pushed, so any prefix is valid UTF-8
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl Default for HandDescription {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rank name for an order value (Ace high = 13, Ace low = 0)
+fn rank_name(order_value: i8) -> &'static str {
+    match order_value {
+        0 | 13 => "Ace",
+        1 => "Two",
+        2 => "Three",
+        3 => "Four",
+        4 => "Five",
+        5 => "Six",
+        6 => "Seven",
+        7 => "Eight",
+        8 => "Nine",
+        9 => "Ten",
+        10 => "Jack",
+        11 => "Queen",
+        12 => "King",
+        _ => "?",
+    }
+}
+
+/// Plural rank name for an order value ("Aces", "Sixes", ...)
+fn rank_plural(order_value: i8) -> &'static str {
+    match order_value {
+        0 | 13 => "Aces",
+        1 => "Twos",
+        2 => "Threes",
+        3 => "Fours",
+        4 => "Fives",
+        5 => "Sixes",
+        6 => "Sevens",
+        7 => "Eights",
+        8 => "Nines",
+        9 => "Tens",
+        10 => "Jacks",
+        11 => "Queens",
+        12 => "Kings",
+        _ => "?",
+    }
+}
+
+/// Describe a hand for logs, e.g. "Full House, Aces over Kings"
+///
+/// `ranked` is the tiebreak ordering returned by `evaluate_hand`.
+pub fn describe_hand(hand: HandEnum, ranked: &[i8; 5]) -> HandDescription {
+    let mut description = HandDescription::new();
+    match hand {
+        HandEnum::RoyalFlush => description.push_str("Royal Flush"),
+        HandEnum::StraightFlush => {
+            description.push_str("Straight Flush, ");
+            description.push_str(rank_name(ranked[0]));
+            description.push_str(" high");
+        }
+        HandEnum::FourOfAKind => {
+            description.push_str("Four of a Kind, ");
+            description.push_str(rank_plural(ranked[0]));
+        }
+        HandEnum::FullHouse => {
+            description.push_str("Full House, ");
+            description.push_str(rank_plural(ranked[0]));
+            description.push_str(" over ");
+            description.push_str(rank_plural(ranked[1]));
+        }
+        HandEnum::Flush => {
+            description.push_str("Flush, ");
+            description.push_str(rank_name(ranked[0]));
+            description.push_str(" high");
+        }
+        HandEnum::Straight => {
+            description.push_str("Straight, ");
+            description.push_str(rank_name(ranked[0]));
+            description.push_str(" high");
+        }
+        HandEnum::ThreeOfAKind => {
+            description.push_str("Three of a Kind, ");
+            description.push_str(rank_plural(ranked[0]));
+        }
+        HandEnum::TwoPair => {
+            description.push_str("Two Pair, ");
+            description.push_str(rank_plural(ranked[0]));
+            description.push_str(" and ");
+            description.push_str(rank_plural(ranked[1]));
+        }
+        HandEnum::Pair => {
+            description.push_str("Pair of ");
+            description.push_str(rank_plural(ranked[0]));
+        }
+        HandEnum::HighCard => {
+            description.push_str("High Card, ");
+            description.push_str(rank_name(ranked[0]));
+        }
+    }
+    description
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +487,109 @@ mod tests {
         let (hand, _ranked) = evaluate_hand(cards);
         assert_eq!(hand, HandEnum::RoyalFlush);
     }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let short = SHORT_DECK_SIZE as u8;
+        assert!(HandEnum::Flush.strength(short) < HandEnum::FullHouse.strength(short));
+        assert!(HandEnum::FullHouse.strength(52) < HandEnum::Flush.strength(52));
+        assert_eq!(HandEnum::Straight.strength(short), HandEnum::Straight as u8);
+    }
+
+    #[test]
+    fn test_short_deck_ace_low_straight() {
+        // AC, 6D, 7H, 8S, 9C
+        let cards = [0, 18, 32, 46, 8];
+        let (hand, _ranked) = evaluate_hand_for_deck(cards, 52);
+        assert_eq!(hand, HandEnum::HighCard);
+
+        let (hand, ranked) = evaluate_hand_for_deck(cards, SHORT_DECK_SIZE as u8);
+        assert_eq!(hand, HandEnum::Straight);
+        assert_eq!(ranked, [8, 7, 6, 5, 4]);
+    }
+
+    #[test]
+    fn test_short_deck_ace_low_straight_flush() {
+        // AC, 6C, 7C, 8C, 9C
+        let cards = [0, 5, 6, 7, 8];
+        let (hand, _ranked) = evaluate_hand_for_deck(cards, SHORT_DECK_SIZE as u8);
+        assert_eq!(hand, HandEnum::StraightFlush);
+    }
+
+    #[test]
+    fn test_describe_full_house() {
+        // AC, AD, AH, KS, KC
+        let (hand, ranked) = evaluate_hand([0, 13, 26, 51, 12]);
+        assert_eq!(describe_hand(hand, &ranked).as_str(), "Full House, Aces over Kings");
+    }
+
+    #[test]
+    fn test_describe_two_pair() {
+        // AC, AD, KH, KS, 5C
+        let (hand, ranked) = evaluate_hand([0, 13, 38, 51, 4]);
+        assert_eq!(describe_hand(hand, &ranked).as_str(), "Two Pair, Aces and Kings");
+    }
+
+    #[test]
+    fn test_describe_straight() {
+        // 5C, 6D, 7H, 8S, 9C
+        let (hand, ranked) = evaluate_hand([4, 18, 32, 46, 8]);
+        assert_eq!(describe_hand(hand, &ranked).as_str(), "Straight, Nine high");
+
+        // Wheel: AC, 2D, 3H, 4S, 5C
+        let (hand, ranked) = evaluate_hand([0, 14, 28, 42, 4]);
+        assert_eq!(describe_hand(hand, &ranked).as_str(), "Straight, Five high");
+    }
+
+    /// Winner of two evaluated hands: 0 = tie, 1 = first, 2 = second
+    fn showdown(cards1: [i8; 5], cards2: [i8; 5]) -> u8 {
+        let (hand1, ranked1) = evaluate_hand(cards1);
+        let (hand2, ranked2) = evaluate_hand(cards2);
+        crate::poker::compare_hands(hand1, &ranked1, hand2, &ranked2)
+    }
+
+    #[test]
+    fn test_pair_kickers() {
+        // 9C, 9D, AH, 7S, 3C - kickers sorted high to low after the pair
+        let (hand, ranked) = evaluate_hand([8, 21, 26, 45, 2]);
+        assert_eq!(hand, HandEnum::Pair);
+        assert_eq!(ranked, [8, 13, 6, 2, -1]);
+
+        // Same pair, Ace kicker beats King kicker: 9H, 9S, KH, 7C, 3D
+        assert_eq!(showdown([8, 21, 26, 45, 2], [34, 47, 38, 6, 15]), 1);
+
+        // Same pair and top kickers, the last kicker decides: 9C, 9D, AH, 7S, 2C
+        assert_eq!(showdown([8, 21, 26, 45, 1], [34, 47, 39, 6, 15]), 2);
+        assert_eq!(showdown([8, 21, 26, 45, 2], [34, 47, 39, 6, 15]), 0);
+    }
+
+    #[test]
+    fn test_trips_kickers() {
+        // 5C, 5D, 5H with 3S then KC: the higher kicker arrives after the lower
+        let (hand, ranked) = evaluate_hand([4, 17, 30, 41, 12]);
+        assert_eq!(hand, HandEnum::ThreeOfAKind);
+        assert_eq!(ranked, [4, 12, 2, -1, -1]);
+
+        // Kickers in the other order, and before the third Five
+        assert_eq!(evaluate_hand([4, 17, 12, 30, 41]).1, [4, 12, 2, -1, -1]);
+        assert_eq!(evaluate_hand([41, 12, 4, 17, 30]).1, [4, 12, 2, -1, -1]);
+
+        // Trips on the board: the top kicker decides, then the lower one
+        // 5C, 5D, 5H, KS + 4C vs 5C, 5D, 5H, QS + AC
+        assert_eq!(showdown([4, 17, 30, 51, 3], [4, 17, 30, 50, 0]), 2);
+        // 5C, 5D, 5H, KS + 4C vs 5C, 5D, 5H, KS + 3C
+        assert_eq!(showdown([4, 17, 30, 51, 3], [4, 17, 30, 51, 2]), 1);
+    }
+
+    #[test]
+    fn test_two_pair_kicker() {
+        // AC, AD, KH, KS + QC vs JC: the fifth card decides
+        let (hand, ranked) = evaluate_hand([0, 13, 38, 51, 11]);
+        assert_eq!(hand, HandEnum::TwoPair);
+        assert_eq!(ranked, [13, 12, 11, -1, -1]);
+        assert_eq!(showdown([0, 13, 38, 51, 11], [0, 13, 38, 51, 10]), 1);
+
+        // Lower pair listed first still ranks the higher pair first: KH, KS, AC, AD, 2C
+        assert_eq!(evaluate_hand([38, 51, 0, 13, 1]).1, [13, 12, 1, -1, -1]);
+    }
 }
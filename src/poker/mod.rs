@@ -2,10 +2,14 @@
 //!
 //! Implements hand evaluation and card handling
 
+pub mod actions;
 pub mod card;
 pub mod hand_utils;
+pub mod odds;
 pub mod ranking;
 
+pub use actions::*;
 pub use card::*;
 pub use hand_utils::*;
+pub use odds::*;
 pub use ranking::*;
@@ -0,0 +1,36 @@
+//! Pot odds
+//!
+//! Client-side helper for bots and tests; not used by any instruction
+
+/// Pot odds as a reduced `pot : to_call` ratio
+///
+/// A free check (`to_call == 0`) reduces to `1:0`; an empty pot with nothing
+/// to call is `0:0`.
+pub fn pot_odds(pot: u64, to_call: u64) -> (u64, u64) {
+    let divisor = gcd(pot, to_call);
+    if divisor == 0 {
+        return (0, 0);
+    }
+    (pot / divisor, to_call / divisor)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pot_odds_reduces_ratio() {
+        assert_eq!(pot_odds(100, 50), (2, 1));
+        assert_eq!(pot_odds(150, 100), (3, 2));
+        assert_eq!(pot_odds(7, 3), (7, 3));
+        assert_eq!(pot_odds(100, 0), (1, 0));
+        assert_eq!(pot_odds(0, 0), (0, 0));
+    }
+}
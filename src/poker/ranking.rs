@@ -2,6 +2,8 @@
 //!
 //! Ported from TexasHoldEmApi.sol
 
+use crate::constants::DECK_SIZE;
+
 use super::hand_utils::HandEnum;
 
 /// Compare two hands
@@ -12,10 +14,24 @@ pub fn compare_hands(
     hand2: HandEnum,
     hand_cards2: &[i8],
 ) -> u8 {
-    // Lower enum value = better hand (RoyalFlush=0 is best)
-    if (hand1 as u8) > (hand2 as u8) {
+    compare_hands_for_deck(hand1, hand_cards1, hand2, hand_cards2, DECK_SIZE as u8)
+}
+
+/// Compare two hands using the ranking order for the given deck size
+/// Returns: 0 = tie, 1 = hand1 wins, 2 = hand2 wins
+pub fn compare_hands_for_deck(
+    hand1: HandEnum,
+    hand_cards1: &[i8],
+    hand2: HandEnum,
+    hand_cards2: &[i8],
+    deck_size: u8,
+) -> u8 {
+    // Lower strength = better hand (RoyalFlush=0 is best)
+    let strength1 = hand1.strength(deck_size);
+    let strength2 = hand2.strength(deck_size);
+    if strength1 > strength2 {
         return 2; // hand2 wins
-    } else if (hand1 as u8) < (hand2 as u8) {
+    } else if strength1 < strength2 {
         return 1; // hand1 wins
     }
 
@@ -99,6 +115,51 @@ pub fn calculate_side_pot_diffs(mut bets: Vec<u64>) -> Vec<u64> {
     bets
 }
 
+/// A main or side pot: its size and a bitmap of the seats eligible to win it
+pub type PotLayer = (u64, u8);
+
+/// Reconstruct the main pot and side pots from what each seat put in this hand
+///
+/// `seats[i]` is seat i's total committed chips and whether it folded. Each
+/// distinct stack level of a live seat closes a pot that only seats at or above
+/// that level can win; chips a folded seat put in above the last level are
+/// dead money in the last pot. ClaimPot pays each layer out separately.
+pub fn pot_breakdown(seats: &[(u64, bool)]) -> Vec<PotLayer> {
+    let mut levels: Vec<u64> = seats
+        .iter()
+        .filter(|&&(committed, folded)| !folded && committed > 0)
+        .map(|&(committed, _)| committed)
+        .collect();
+    levels.sort();
+    levels.dedup();
+
+    let mut pots = Vec::with_capacity(levels.len());
+    let mut floor = 0u64;
+    for step in calculate_side_pot_diffs(levels) {
+        let ceiling = floor + step;
+        let mut amount = 0u64;
+        let mut eligible = 0u8;
+        for (seat, &(committed, folded)) in seats.iter().enumerate() {
+            amount += committed.min(ceiling).saturating_sub(floor);
+            if !folded && committed >= ceiling {
+                eligible |= 1 << seat;
+            }
+        }
+        pots.push((amount, eligible));
+        floor = ceiling;
+    }
+
+    // Folded chips above the highest live stack
+    let dead: u64 = seats.iter().map(|&(committed, _)| committed.saturating_sub(floor)).sum();
+    if dead > 0 {
+        match pots.last_mut() {
+            Some(last) => last.0 += dead,
+            None => pots.push((dead, 0)),
+        }
+    }
+    pots
+}
+
 /// Sort array using bubble sort
 pub fn sort_array(arr: &mut [u64]) {
     let n = arr.len();
@@ -164,6 +225,22 @@ mod tests {
         assert_eq!(result, 0); // Tie
     }
 
+    #[test]
+    fn test_compare_hands_short_deck() {
+        let cards1 = [13, 12, 10, 8, 5];
+        let cards2 = [13, 12, 0, 0, 0];
+
+        // Standard deck: Full House beats Flush
+        let result =
+            compare_hands_for_deck(HandEnum::Flush, &cards1, HandEnum::FullHouse, &cards2, 52);
+        assert_eq!(result, 2);
+
+        // Short deck: Flush beats Full House
+        let result =
+            compare_hands_for_deck(HandEnum::Flush, &cards1, HandEnum::FullHouse, &cards2, 36);
+        assert_eq!(result, 1);
+    }
+
     #[test]
     fn test_side_pot_calculation() {
         let bets = vec![50, 100, 150];
@@ -171,6 +248,23 @@ mod tests {
         assert_eq!(diffs, vec![50, 50, 50]);
     }
 
+    #[test]
+    fn test_pot_breakdown_staggered_all_ins() {
+        // Seat 0 all-in for 50, seat 1 all-in for 100, seats 2 and 3 call 150,
+        // seat 4 folded after putting in 20
+        let seats = [(50, false), (100, false), (150, false), (150, false), (20, true)];
+        let pots = pot_breakdown(&seats);
+        assert_eq!(pots, vec![(220, 0b01111), (150, 0b01110), (100, 0b01100)]);
+        assert_eq!(pots.iter().map(|&(amount, _)| amount).sum::<u64>(), 470);
+
+        // A folded seat that put in more than any live stack adds dead money
+        let seats = [(50, false), (80, true), (50, false)];
+        assert_eq!(pot_breakdown(&seats), vec![(180, 0b101)]);
+
+        // No side pots when everyone matched
+        assert_eq!(pot_breakdown(&[(40, false), (40, false)]), vec![(80, 0b11)]);
+    }
+
     #[test]
     fn test_distribute_chips() {
         let (per_player, remainder) = distribute_chips(100, 3, 1);
@@ -34,6 +34,19 @@ pub enum PokerInstruction {
     TestCompression = 24,
     MapDeckPart1 = 25,
     MapDeckPart2 = 26,
+    GenerateChunk = 27,
+    Straddle = 28,
+    #[cfg(feature = "benchmark")]
+    Benchmark = 29,
+    RevealCombined = 30,
+    DealFlop = 31,
+    Muck = 32,
+    #[cfg(feature = "test-deck")]
+    SetDeck = 33,
+    InitializeAndJoin = 34,
+    CancelGame = 35,
+    CommitLockKeys = 36,
+    InitPlayerStats = 37,
 }
 
 impl TryFrom<u8> for PokerInstruction {
@@ -68,6 +81,19 @@ impl TryFrom<u8> for PokerInstruction {
             24 => Ok(PokerInstruction::TestCompression),
             25 => Ok(PokerInstruction::MapDeckPart1),
             26 => Ok(PokerInstruction::MapDeckPart2),
+            27 => Ok(PokerInstruction::GenerateChunk),
+            28 => Ok(PokerInstruction::Straddle),
+            #[cfg(feature = "benchmark")]
+            29 => Ok(PokerInstruction::Benchmark),
+            30 => Ok(PokerInstruction::RevealCombined),
+            31 => Ok(PokerInstruction::DealFlop),
+            32 => Ok(PokerInstruction::Muck),
+            #[cfg(feature = "test-deck")]
+            33 => Ok(PokerInstruction::SetDeck),
+            34 => Ok(PokerInstruction::InitializeAndJoin),
+            35 => Ok(PokerInstruction::CancelGame),
+            36 => Ok(PokerInstruction::CommitLockKeys),
+            37 => Ok(PokerInstruction::InitPlayerStats),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
@@ -195,5 +221,51 @@ pub fn process_instruction(
             msg!("Instruction: MapDeckPart2");
             process_map_deck_part2(program_id, accounts, data)
         }
+        PokerInstruction::GenerateChunk => {
+            msg!("Instruction: GenerateChunk");
+            process_generate_chunk(program_id, accounts, data)
+        }
+        PokerInstruction::Straddle => {
+            msg!("Instruction: Straddle");
+            process_straddle(program_id, accounts, data)
+        }
+        #[cfg(feature = "benchmark")]
+        PokerInstruction::Benchmark => {
+            msg!("Instruction: Benchmark");
+            process_benchmark(program_id, accounts, data)
+        }
+        PokerInstruction::RevealCombined => {
+            msg!("Instruction: RevealCombined");
+            process_reveal_combined(program_id, accounts, data)
+        }
+        PokerInstruction::DealFlop => {
+            msg!("Instruction: DealFlop");
+            process_deal_flop(program_id, accounts, data)
+        }
+        PokerInstruction::Muck => {
+            msg!("Instruction: Muck");
+            process_muck(program_id, accounts, data)
+        }
+        #[cfg(feature = "test-deck")]
+        PokerInstruction::SetDeck => {
+            msg!("Instruction: SetDeck");
+            process_set_deck(program_id, accounts, data)
+        }
+        PokerInstruction::InitializeAndJoin => {
+            msg!("Instruction: InitializeAndJoin");
+            process_initialize_and_join(program_id, accounts, data)
+        }
+        PokerInstruction::CancelGame => {
+            msg!("Instruction: CancelGame");
+            process_cancel_game(program_id, accounts, data)
+        }
+        PokerInstruction::CommitLockKeys => {
+            msg!("Instruction: CommitLockKeys");
+            process_commit_lock_keys(program_id, accounts, data)
+        }
+        PokerInstruction::InitPlayerStats => {
+            msg!("Instruction: InitPlayerStats");
+            process_init_player_stats(program_id, accounts, data)
+        }
     }
 }
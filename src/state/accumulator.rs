@@ -9,6 +9,8 @@
 use pinocchio::pubkey::{find_program_address, Pubkey};
 
 use crate::constants::{ACCUMULATOR_SEED, DECK_SIZE};
+use crate::crypto::bn254::add_mod_bn254;
+use crate::poker::deck_card_id;
 
 // Layout offsets for zero-copy access
 const BUMP_OFFSET: usize = 0;
@@ -17,6 +19,9 @@ const ACCUMULATOR_OFFSET: usize = 33; // 1 + 32
 const DECK_QX_OFFSET: usize = ACCUMULATOR_OFFSET + (DECK_SIZE * 32); // 33 + 1664 = 1697
 const DECK_QY_OFFSET: usize = DECK_QX_OFFSET + (DECK_SIZE * 32); // 1697 + 1664 = 3361
 
+// The last field must end exactly at the account size
+const _: () = assert!(DECK_QY_OFFSET + DECK_SIZE * 32 == ACCUMULATOR_STATE_SIZE);
+
 /// Size of AccumulatorState account in bytes
 /// bump(1) + game_id(32) + accumulator(52*32) + deck_qx(52*32) + deck_qy(52*32) = 5025 bytes
 pub const ACCUMULATOR_STATE_SIZE: usize = 1 + 32 + (DECK_SIZE * 32) + (DECK_SIZE * 32) + (DECK_SIZE * 32);
@@ -66,19 +71,13 @@ impl AccumulatorState {
     }
 
     /// Add a value to the accumulator at index
-    /// Performs modular addition in the field
+    /// Performs modular addition in the BN254 scalar field
     pub fn add_to_accumulator(&mut self, index: usize, value: &[u8; 32]) {
         if index >= DECK_SIZE {
             return;
         }
 
-        // Simple 256-bit addition with overflow handling
-        let mut carry: u16 = 0;
-        for i in (0..32).rev() {
-            let sum = self.accumulator[index][i] as u16 + value[i] as u16 + carry;
-            self.accumulator[index][i] = sum as u8;
-            carry = sum >> 8;
-        }
+        self.accumulator[index] = add_mod_bn254(&self.accumulator[index], value);
     }
 
     /// Set the deck mapping (qx, qy) for a card
@@ -100,6 +99,9 @@ impl AccumulatorState {
     /// Find card ID by EC point coordinates
     /// Returns the card index (0-51) if found, None otherwise
     pub fn find_card_by_point(&self, qx: &[u8; 32], qy: &[u8; 32]) -> Option<i8> {
+        if is_identity(qx, qy) {
+            return None;
+        }
         for i in 0..DECK_SIZE {
             if self.deck_qx[i] == *qx && self.deck_qy[i] == *qy {
                 return Some(i as i8);
@@ -213,6 +215,9 @@ impl<'a> AccumulatorStateRef<'a> {
     /// Find card ID by EC point coordinates
     /// Returns the card index (0-51) if found, None otherwise
     pub fn find_card_by_point(&self, qx: &[u8; 32], qy: &[u8; 32]) -> Option<i8> {
+        if is_identity(qx, qy) {
+            return None;
+        }
         for i in 0..DECK_SIZE {
             if self.get_deck_qx(i) == qx && self.get_deck_qy(i) == qy {
                 return Some(i as i8);
@@ -222,6 +227,36 @@ impl<'a> AccumulatorStateRef<'a> {
     }
 }
 
+/// Whether a point is the all-zero encoding of the identity
+///
+/// Unmapped deck entries are zeroed, so an identity query would otherwise
+/// match them and return a card that was never mapped.
+fn is_identity(qx: &[u8; 32], qy: &[u8; 32]) -> bool {
+    *qx == [0u8; 32] && *qy == [0u8; 32]
+}
+
+/// Verify the deck mapping holds `deck_size` distinct, populated card points
+///
+/// A duplicated or missing entry would make two cards indistinguishable
+/// (or one unreachable) when `find_card_by_point` maps hands back to IDs.
+/// Only the cards of the table's deck are checked (see `deck_card_id`); a
+/// short deck leaves its Twos through Fives unmapped.
+pub fn verify_deck_mapping(acc: &AccumulatorStateRef, deck_size: u8) -> bool {
+    let deck_size = deck_size as usize;
+    for i in 0..deck_size {
+        let qx = acc.get_deck_qx(deck_card_id(deck_size as u8, i) as usize);
+        if *qx == [0u8; 32] {
+            return false;
+        }
+        for j in (i + 1)..deck_size {
+            if acc.get_deck_qx(deck_card_id(deck_size as u8, j) as usize) == qx {
+                return false;
+            }
+        }
+    }
+    true
+}
+
 /// Zero-copy mutable view into AccumulatorState account data.
 /// Stack cost: ~16 bytes (just the slice reference)
 pub struct AccumulatorStateMut<'a> {
@@ -278,18 +313,11 @@ impl<'a> AccumulatorStateMut<'a> {
         self.data[offset..offset + 32].copy_from_slice(value);
     }
 
-    /// Add a value to the accumulator at index (modular addition)
+    /// Add a value to the accumulator at index (modulo the BN254 scalar field)
     pub fn add_to_accumulator(&mut self, index: usize, value: &[u8; 32]) {
         debug_assert!(index < DECK_SIZE);
-        let offset = ACCUMULATOR_OFFSET + index * 32;
-
-        // Simple 256-bit addition with overflow handling
-        let mut carry: u16 = 0;
-        for i in (0..32).rev() {
-            let sum = self.data[offset + i] as u16 + value[i] as u16 + carry;
-            self.data[offset + i] = sum as u8;
-            carry = sum >> 8;
-        }
+        let sum = add_mod_bn254(self.get_accumulator(index), value);
+        self.set_accumulator(index, &sum);
     }
 
     /// Get deck_qx value at index (0-51)
@@ -339,6 +367,9 @@ impl<'a> AccumulatorStateMut<'a> {
 
     /// Find card ID by EC point coordinates
     pub fn find_card_by_point(&self, qx: &[u8; 32], qy: &[u8; 32]) -> Option<i8> {
+        if is_identity(qx, qy) {
+            return None;
+        }
         for i in 0..DECK_SIZE {
             if self.get_deck_qx(i) == qx && self.get_deck_qy(i) == qy {
                 return Some(i as i8);
@@ -361,3 +392,100 @@ impl<'a> AccumulatorStateMut<'a> {
         self.set_game_id(game_id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapped_deck() -> Vec<u8> {
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        for i in 0..DECK_SIZE {
+            acc.set_deck_mapping(i, &[i as u8 + 1; 32], &[0xAA; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_verify_deck_mapping() {
+        let data = mapped_deck();
+        assert!(verify_deck_mapping(&AccumulatorStateRef::from_bytes(&data).unwrap(), DECK_SIZE as u8));
+    }
+
+    #[test]
+    fn test_verify_deck_mapping_rejects_duplicate() {
+        let mut data = mapped_deck();
+        {
+            let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+            acc.set_deck_mapping(51, &[1u8; 32], &[0xAA; 32]);
+        }
+        assert!(!verify_deck_mapping(&AccumulatorStateRef::from_bytes(&data).unwrap(), DECK_SIZE as u8));
+    }
+
+    #[test]
+    fn test_verify_deck_mapping_rejects_missing_card() {
+        let mut data = mapped_deck();
+        {
+            let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+            acc.set_deck_mapping(30, &[0u8; 32], &[0u8; 32]);
+        }
+        assert!(!verify_deck_mapping(&AccumulatorStateRef::from_bytes(&data).unwrap(), DECK_SIZE as u8));
+    }
+
+    #[test]
+    fn test_add_to_accumulator_stays_in_field() {
+        use crate::constants::BN254_N;
+
+        // n - 1 plus a full-width keccak-sized value: wraps past 2^256 without reduction
+        let mut n_minus_one = BN254_N;
+        n_minus_one[31] -= 1;
+        let value = [0xFF; 32];
+
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+        acc.set_accumulator(0, &n_minus_one);
+        acc.add_to_accumulator(0, &value);
+        let sum = *acc.get_accumulator(0);
+        assert!(sum < BN254_N);
+
+        // (n - 1) + (2^256 - 1) = 2^256 - 2 (mod n)
+        let expected = [
+            0x0e, 0x0a, 0x77, 0xc1, 0x9a, 0x07, 0xdf, 0x2f,
+            0x66, 0x6e, 0xa3, 0x6f, 0x78, 0x79, 0x46, 0x2e,
+            0x36, 0xfc, 0x76, 0x95, 0x9f, 0x60, 0xcd, 0x29,
+            0xac, 0x96, 0x34, 0x1c, 0x4f, 0xff, 0xff, 0xf9,
+        ];
+        assert_eq!(sum, expected);
+
+        // The owned state agrees with the zero-copy view
+        let mut owned = AccumulatorState::default();
+        owned.accumulator[0] = n_minus_one;
+        owned.add_to_accumulator(0, &value);
+        assert_eq!(owned.accumulator[0], sum);
+    }
+
+    #[test]
+    fn test_identity_lookup_before_mapping() {
+        // Nothing mapped yet: every entry is zero and must not match (0, 0)
+        let data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        let acc = AccumulatorStateRef::from_bytes(&data).unwrap();
+        assert_eq!(acc.find_card_by_point(&[0u8; 32], &[0u8; 32]), None);
+        assert_eq!(
+            AccumulatorState::new(0, [0u8; 32]).find_card_by_point(&[0u8; 32], &[0u8; 32]),
+            None
+        );
+
+        // Once mapped, real points still resolve
+        let data = mapped_deck();
+        let acc = AccumulatorStateRef::from_bytes(&data).unwrap();
+        assert_eq!(acc.find_card_by_point(&[5u8; 32], &[0xAA; 32]), Some(4));
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        assert!(AccumulatorStateRef::from_bytes(&data).is_some());
+        assert!(AccumulatorStateRef::from_bytes(&data[..ACCUMULATOR_STATE_SIZE - 1]).is_none());
+        assert!(AccumulatorStateMut::from_bytes(&mut data[..ACCUMULATOR_STATE_SIZE - 1]).is_none());
+    }
+}
@@ -6,14 +6,16 @@ use pinocchio::pubkey::{find_program_address, Pubkey};
 
 use crate::constants::{COMMUNITY_CARDS_SEED, MAX_COMMUNITY_CARDS};
 use crate::state::deck_state::EC_POINT_SIZE;
+use crate::state::enums::CardStage;
 
 /// Size of CommunityCards account in bytes
-/// bump(1) + game_id(32) + card_indices(5) + card_count(1) + opened_cards(5*64) + opened_count(1) = 360 bytes
-pub const COMMUNITY_CARDS_SIZE: usize = 1 + 32 + 5 + 1 + (5 * EC_POINT_SIZE) + 1;
+/// bump(1) + game_id(32) + card_indices(5) + card_count(1) + opened_cards(5*64) + opened_count(1)
+/// + opened_ids(5) = 365 bytes
+pub const COMMUNITY_CARDS_SIZE: usize = 1 + 32 + 5 + 1 + (5 * EC_POINT_SIZE) + 1 + 5;
 
 /// Community cards state
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct CommunityCards {
     /// PDA bump seed
     pub bump: u8,
@@ -27,6 +29,9 @@ pub struct CommunityCards {
     pub opened_cards: [[u8; EC_POINT_SIZE]; MAX_COMMUNITY_CARDS as usize],
     /// Number of opened cards
     pub opened_count: u8,
+    /// Card IDs of the opened cards, resolved against the accumulator when
+    /// each card is opened (-1 = not resolved)
+    pub opened_ids: [i8; MAX_COMMUNITY_CARDS as usize],
 }
 
 impl Default for CommunityCards {
@@ -38,6 +43,7 @@ impl Default for CommunityCards {
             card_count: 0,
             opened_cards: [[0u8; EC_POINT_SIZE]; MAX_COMMUNITY_CARDS as usize],
             opened_count: 0,
+            opened_ids: [-1; MAX_COMMUNITY_CARDS as usize],
         }
     }
 }
@@ -67,18 +73,31 @@ impl CommunityCards {
         true
     }
 
-    /// Add an opened card point
-    pub fn add_opened_card(&mut self, qx: &[u8; 32], qy: &[u8; 32]) -> bool {
+    /// Add an opened card point along with its card ID (-1 if unknown)
+    pub fn add_opened_card(&mut self, qx: &[u8; 32], qy: &[u8; 32], id: i8) -> bool {
         if self.opened_count >= MAX_COMMUNITY_CARDS {
             return false;
         }
         let idx = self.opened_count as usize;
         self.opened_cards[idx][..32].copy_from_slice(qx);
         self.opened_cards[idx][32..].copy_from_slice(qy);
+        self.opened_ids[idx] = id;
         self.opened_count += 1;
         true
     }
 
+    /// Cached card ID of an opened card, found by its point
+    ///
+    /// Saves an accumulator scan per board card at showdown. `None` if the
+    /// point isn't on the board or its ID wasn't resolved when opened.
+    pub fn cached_id(&self, qx: &[u8; 32], qy: &[u8; 32]) -> Option<i8> {
+        let opened = (self.opened_count as usize).min(MAX_COMMUNITY_CARDS as usize);
+        let slot = self.opened_cards[..opened]
+            .iter()
+            .position(|card| card[..32] == qx[..] && card[32..] == qy[..])?;
+        Some(self.opened_ids[slot]).filter(|&id| id >= 0)
+    }
+
     /// Check if an index is a community card
     pub fn is_community_card(&self, index: u8) -> bool {
         for i in 0..self.card_count as usize {
@@ -89,6 +108,27 @@ impl CommunityCards {
         false
     }
 
+    /// Whether all five board cards have been opened
+    pub fn is_board_complete(&self) -> bool {
+        self.opened_count >= MAX_COMMUNITY_CARDS
+    }
+
+    /// Board stage of the card at `index` (0-4), `Preflop` if out of range
+    pub fn stage_of(index: usize) -> CardStage {
+        if index >= MAX_COMMUNITY_CARDS as usize {
+            return CardStage::Preflop;
+        }
+        CardStage::from(index as u8 + 1)
+    }
+
+    /// Stage of the most recently opened card, `Preflop` before the flop
+    pub fn current_stage(&self) -> CardStage {
+        match self.opened_count {
+            0 => CardStage::Preflop,
+            n => Self::stage_of(n as usize - 1),
+        }
+    }
+
     /// Get an opened card's EC point
     pub fn get_opened_card(&self, index: usize) -> Option<([u8; 32], [u8; 32])> {
         if index >= self.opened_count as usize {
@@ -107,6 +147,7 @@ impl CommunityCards {
         self.card_count = 0;
         self.opened_cards = [[0u8; EC_POINT_SIZE]; MAX_COMMUNITY_CARDS as usize];
         self.opened_count = 0;
+        self.opened_ids = [-1; MAX_COMMUNITY_CARDS as usize];
     }
 
     /// Serialize to bytes
@@ -132,6 +173,11 @@ impl CommunityCards {
         }
 
         bytes[offset] = self.opened_count;
+        offset += 1;
+
+        for (byte, id) in bytes[offset..offset + 5].iter_mut().zip(self.opened_ids) {
+            *byte = id as u8;
+        }
 
         bytes
     }
@@ -165,6 +211,12 @@ impl CommunityCards {
         }
 
         let opened_count = data[offset];
+        offset += 1;
+
+        let mut opened_ids = [-1i8; MAX_COMMUNITY_CARDS as usize];
+        for (id, &byte) in opened_ids.iter_mut().zip(&data[offset..offset + 5]) {
+            *id = byte as i8;
+        }
 
         Some(Self {
             bump,
@@ -173,6 +225,75 @@ impl CommunityCards {
             card_count,
             opened_cards,
             opened_count,
+            opened_ids,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        fn arb_community_cards()(
+            header in any::<(u8, [u8; 32])>(),
+            card_indices in any::<[u8; MAX_COMMUNITY_CARDS as usize]>(),
+            counts in any::<(u8, u8)>(),
+            opened_cards in proptest::array::uniform5(proptest::array::uniform(any::<u8>())),
+            opened_ids in any::<[i8; MAX_COMMUNITY_CARDS as usize]>(),
+        ) -> CommunityCards {
+            CommunityCards {
+                bump: header.0,
+                game_id: header.1,
+                card_indices,
+                card_count: counts.0,
+                opened_cards,
+                opened_count: counts.1,
+                opened_ids,
+            }
+        }
+    }
+
+    #[test]
+    fn test_stage_of() {
+        assert_eq!(CommunityCards::stage_of(0), CardStage::Flop1);
+        assert_eq!(CommunityCards::stage_of(2), CardStage::Flop3);
+        assert_eq!(CommunityCards::stage_of(4), CardStage::River);
+        assert_eq!(CommunityCards::stage_of(5), CardStage::Preflop);
+    }
+
+    #[test]
+    fn test_current_stage_follows_opened_count() {
+        let expected = [
+            CardStage::Preflop,
+            CardStage::Flop1,
+            CardStage::Flop2,
+            CardStage::Flop3,
+            CardStage::Turn,
+            CardStage::River,
+        ];
+        let mut community_cards = CommunityCards::default();
+        for (opened_count, stage) in expected.iter().enumerate() {
+            community_cards.opened_count = opened_count as u8;
+            assert_eq!(community_cards.current_stage(), *stage);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(community_cards in arb_community_cards()) {
+            prop_assert_eq!(
+                CommunityCards::from_bytes(&community_cards.to_bytes()),
+                Some(community_cards)
+            );
+        }
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let bytes = CommunityCards::new(0, [0u8; 32]).to_bytes();
+        assert_eq!(bytes.len(), COMMUNITY_CARDS_SIZE);
+        assert!(CommunityCards::from_bytes(&bytes[..COMMUNITY_CARDS_SIZE - 1]).is_none());
+    }
+}
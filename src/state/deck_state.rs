@@ -26,6 +26,9 @@ const GAME_ID_OFFSET: usize = 1;
 const WORK_DECK_OFFSET: usize = 33; // 1 + 32
 const CARD_OWNERS_OFFSET: usize = WORK_DECK_OFFSET + (DECK_SIZE * EC_POINT_SIZE); // 33 + 3328 = 3361
 
+// The last field must end exactly at the account size
+const _: () = assert!(CARD_OWNERS_OFFSET + DECK_SIZE * 32 == DECK_STATE_SIZE);
+
 /// Deck state account containing the shuffled deck
 #[repr(C)]
 #[derive(Clone, Debug)]
@@ -334,3 +337,16 @@ impl<'a> DeckStateMut<'a> {
         self.set_game_id(game_id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_size() {
+        let mut data = vec![0u8; DECK_STATE_SIZE];
+        assert!(DeckStateRef::from_bytes(&data).is_some());
+        assert!(DeckStateRef::from_bytes(&data[..DECK_STATE_SIZE - 1]).is_none());
+        assert!(DeckStateMut::from_bytes(&mut data[..DECK_STATE_SIZE - 1]).is_none());
+    }
+}
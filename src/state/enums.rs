@@ -1,6 +1,11 @@
 //! State machine enums matching the Solidity implementation
 //!
 //! Ported from TexasHoldEmTypes.sol
+//!
+//! The state machine enums are read back with `try_from_u8`, a strict
+//! conversion for persisted state where an unknown byte means corruption.
+
+use crate::error::PokerError;
 
 /// Major game phases for the mental poker protocol
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -14,19 +19,25 @@ pub enum GamePhase {
     Finished = 4,
 }
 
-impl From<u8> for GamePhase {
-    fn from(value: u8) -> Self {
+impl GamePhase {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         match value {
-            0 => GamePhase::WaitingForPlayers,
-            1 => GamePhase::Shuffling,
-            2 => GamePhase::Drawing,
-            3 => GamePhase::Opening,
-            4 => GamePhase::Finished,
-            _ => GamePhase::WaitingForPlayers,
+            0 => Ok(GamePhase::WaitingForPlayers),
+            1 => Ok(GamePhase::Shuffling),
+            2 => Ok(GamePhase::Drawing),
+            3 => Ok(GamePhase::Opening),
+            4 => Ok(GamePhase::Finished),
+            _ => Err(PokerError::InvalidAccountData),
         }
     }
 }
 
+impl From<u8> for GamePhase {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
 /// Substate for the shuffling subprotocol
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -39,19 +50,25 @@ pub enum ShufflingState {
     Locking = 4,
 }
 
-impl From<u8> for ShufflingState {
-    fn from(value: u8) -> Self {
+impl ShufflingState {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         match value {
-            0 => ShufflingState::NotStarted,
-            1 => ShufflingState::Committing,
-            2 => ShufflingState::Generating,
-            3 => ShufflingState::Shuffling,
-            4 => ShufflingState::Locking,
-            _ => ShufflingState::NotStarted,
+            0 => Ok(ShufflingState::NotStarted),
+            1 => Ok(ShufflingState::Committing),
+            2 => Ok(ShufflingState::Generating),
+            3 => Ok(ShufflingState::Shuffling),
+            4 => Ok(ShufflingState::Locking),
+            _ => Err(PokerError::InvalidAccountData),
         }
     }
 }
 
+impl From<u8> for ShufflingState {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
 /// Substate for the drawing subprotocol
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -62,17 +79,23 @@ pub enum DrawingState {
     Revealing = 2,
 }
 
-impl From<u8> for DrawingState {
-    fn from(value: u8) -> Self {
+impl DrawingState {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         match value {
-            0 => DrawingState::NotDrawn,
-            1 => DrawingState::Picking,
-            2 => DrawingState::Revealing,
-            _ => DrawingState::NotDrawn,
+            0 => Ok(DrawingState::NotDrawn),
+            1 => Ok(DrawingState::Picking),
+            2 => Ok(DrawingState::Revealing),
+            _ => Err(PokerError::InvalidAccountData),
         }
     }
 }
 
+impl From<u8> for DrawingState {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
 /// Major Texas Hold'em game states
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -90,24 +113,30 @@ pub enum TexasHoldEmState {
     Finished = 9,
 }
 
-impl From<u8> for TexasHoldEmState {
-    fn from(value: u8) -> Self {
+impl TexasHoldEmState {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         match value {
-            0 => TexasHoldEmState::NotStarted,
-            1 => TexasHoldEmState::Setup,
-            2 => TexasHoldEmState::Drawing,
-            3 => TexasHoldEmState::CommunityCardsAwaiting,
-            4 => TexasHoldEmState::Betting,
-            5 => TexasHoldEmState::Revealing,
-            6 => TexasHoldEmState::SubmitBest,
-            7 => TexasHoldEmState::ClaimPot,
-            8 => TexasHoldEmState::StartNext,
-            9 => TexasHoldEmState::Finished,
-            _ => TexasHoldEmState::NotStarted,
+            0 => Ok(TexasHoldEmState::NotStarted),
+            1 => Ok(TexasHoldEmState::Setup),
+            2 => Ok(TexasHoldEmState::Drawing),
+            3 => Ok(TexasHoldEmState::CommunityCardsAwaiting),
+            4 => Ok(TexasHoldEmState::Betting),
+            5 => Ok(TexasHoldEmState::Revealing),
+            6 => Ok(TexasHoldEmState::SubmitBest),
+            7 => Ok(TexasHoldEmState::ClaimPot),
+            8 => Ok(TexasHoldEmState::StartNext),
+            9 => Ok(TexasHoldEmState::Finished),
+            _ => Err(PokerError::InvalidAccountData),
         }
     }
 }
 
+impl From<u8> for TexasHoldEmState {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
 /// Major betting round states
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -120,19 +149,25 @@ pub enum BettingRoundState {
     Showdown = 4,
 }
 
-impl From<u8> for BettingRoundState {
-    fn from(value: u8) -> Self {
+impl BettingRoundState {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
         match value {
-            0 => BettingRoundState::Blinds,
-            1 => BettingRoundState::PreFlop,
-            2 => BettingRoundState::PostFlop,
-            3 => BettingRoundState::PostTurn,
-            4 => BettingRoundState::Showdown,
-            _ => BettingRoundState::Blinds,
+            0 => Ok(BettingRoundState::Blinds),
+            1 => Ok(BettingRoundState::PreFlop),
+            2 => Ok(BettingRoundState::PostFlop),
+            3 => Ok(BettingRoundState::PostTurn),
+            4 => Ok(BettingRoundState::Showdown),
+            _ => Err(PokerError::InvalidAccountData),
         }
     }
 }
 
+impl From<u8> for BettingRoundState {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
 /// Community cards dealing state
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -144,14 +179,119 @@ pub enum CommunityCardsState {
     RiverAwaiting = 3,
 }
 
+impl CommunityCardsState {
+    pub fn try_from_u8(value: u8) -> Result<Self, PokerError> {
+        match value {
+            0 => Ok(CommunityCardsState::Opening),
+            1 => Ok(CommunityCardsState::FlopAwaiting),
+            2 => Ok(CommunityCardsState::TurnAwaiting),
+            3 => Ok(CommunityCardsState::RiverAwaiting),
+            _ => Err(PokerError::InvalidAccountData),
+        }
+    }
+}
+
 impl From<u8> for CommunityCardsState {
+    fn from(value: u8) -> Self {
+        Self::try_from_u8(value).unwrap_or_default()
+    }
+}
+
+/// Board position of a community card, used by clients to label the board
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum CardStage {
+    #[default]
+    Preflop = 0,
+    Flop1 = 1,
+    Flop2 = 2,
+    Flop3 = 3,
+    Turn = 4,
+    River = 5,
+}
+
+impl From<u8> for CardStage {
     fn from(value: u8) -> Self {
         match value {
-            0 => CommunityCardsState::Opening,
-            1 => CommunityCardsState::FlopAwaiting,
-            2 => CommunityCardsState::TurnAwaiting,
-            3 => CommunityCardsState::RiverAwaiting,
-            _ => CommunityCardsState::Opening,
+            0 => CardStage::Preflop,
+            1 => CardStage::Flop1,
+            2 => CardStage::Flop2,
+            3 => CardStage::Flop3,
+            4 => CardStage::Turn,
+            5 => CardStage::River,
+            _ => CardStage::Preflop,
         }
     }
 }
+
+/// Betting action recorded in the per-round action log
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum BetActionType {
+    #[default]
+    None = 0,
+    Blind = 1,
+    Check = 2,
+    Call = 3,
+    Raise = 4,
+    Fold = 5,
+    /// A call for less than the full amount, with the player's last chips
+    AllIn = 6,
+}
+
+impl From<u8> for BetActionType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BetActionType::None,
+            1 => BetActionType::Blind,
+            2 => BetActionType::Check,
+            3 => BetActionType::Call,
+            4 => BetActionType::Raise,
+            5 => BetActionType::Fold,
+            6 => BetActionType::AllIn,
+            _ => BetActionType::None,
+        }
+    }
+}
+
+/// Table format: an open-ended cash game or a sit-and-go that ends with one player left
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum GameMode {
+    #[default]
+    CashGame = 0,
+    SitAndGo = 1,
+}
+
+impl From<u8> for GameMode {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => GameMode::SitAndGo,
+            _ => GameMode::CashGame,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range_state_bytes_rejected() {
+        assert_eq!(GamePhase::try_from_u8(4), Ok(GamePhase::Finished));
+        assert_eq!(GamePhase::try_from_u8(5), Err(PokerError::InvalidAccountData));
+        assert_eq!(ShufflingState::try_from_u8(4), Ok(ShufflingState::Locking));
+        assert_eq!(ShufflingState::try_from_u8(5), Err(PokerError::InvalidAccountData));
+        assert_eq!(DrawingState::try_from_u8(2), Ok(DrawingState::Revealing));
+        assert_eq!(DrawingState::try_from_u8(3), Err(PokerError::InvalidAccountData));
+        assert_eq!(TexasHoldEmState::try_from_u8(9), Ok(TexasHoldEmState::Finished));
+        assert_eq!(TexasHoldEmState::try_from_u8(10), Err(PokerError::InvalidAccountData));
+        assert_eq!(BettingRoundState::try_from_u8(4), Ok(BettingRoundState::Showdown));
+        assert_eq!(BettingRoundState::try_from_u8(5), Err(PokerError::InvalidAccountData));
+        assert_eq!(CommunityCardsState::try_from_u8(3), Ok(CommunityCardsState::RiverAwaiting));
+        assert_eq!(CommunityCardsState::try_from_u8(4), Err(PokerError::InvalidAccountData));
+
+        // The lenient conversion still falls back to the default
+        assert_eq!(TexasHoldEmState::from(0xFF), TexasHoldEmState::NotStarted);
+    }
+}
@@ -4,17 +4,24 @@
 
 use pinocchio::pubkey::{find_program_address, Pubkey};
 
-use crate::constants::{GAME_CONFIG_SEED, DEFAULT_TIMEOUT_SECONDS, DEFAULT_SLASH_PERCENTAGE};
+use crate::constants::{
+    DECK_SIZE, DEFAULT_SLASH_PERCENTAGE, DEFAULT_TIMEOUT_SECONDS, GAME_CONFIG_SEED, SHORT_DECK_SIZE,
+};
+use crate::state::{enums::GameMode, PlayerList};
 
 /// Size of GameConfig account in bytes
 /// bump(1) + game_id(32) + authority(32) + token_mint(32) + max_players(1) + current_players(1)
 /// + small_blind(8) + min_buy_in(8) + dealer_index(1) + is_accepting_players(1) + created_at(8)
-/// + timeout_seconds(4) + slash_percentage(1) + game_number(4) = 134 bytes
-pub const GAME_CONFIG_SIZE: usize = 1 + 32 + 32 + 32 + 1 + 1 + 8 + 8 + 1 + 1 + 8 + 4 + 1 + 4;
+/// + timeout_seconds(4) + slash_percentage(1) + game_number(4) + deck_size(1)
+/// + rotate_dealer(1) + dead_button(1) + allow_straddle(1) + chip_decimals(1)
+/// + cap_to_effective_stack(1) + min_buy_in_big_blinds(1) + game_mode(1)
+/// + auto_pay(1) + stats_enabled(1) = 144 bytes
+pub const GAME_CONFIG_SIZE: usize =
+    1 + 32 + 32 + 32 + 1 + 1 + 8 + 8 + 1 + 1 + 8 + 4 + 1 + 4 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1;
 
 /// Game configuration account
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct GameConfig {
     /// PDA bump seed
     pub bump: u8,
@@ -44,6 +51,26 @@ pub struct GameConfig {
     pub slash_percentage: u8,
     /// Game number (increments each round)
     pub game_number: u32,
+    /// Number of cards in the deck (52 standard, 36 short-deck)
+    pub deck_size: u8,
+    /// Whether the dealer button moves each hand (0 = fixed dealer)
+    pub rotate_dealer: u8, // bool as u8
+    /// Whether the button sits on an empty seat this hand (dead button)
+    pub dead_button: u8, // bool as u8
+    /// Whether UTG may post a straddle before cards are dealt
+    pub allow_straddle: u8, // bool as u8
+    /// Token decimals per whole chip; pots are split in whole chips
+    pub chip_decimals: u8,
+    /// Whether bets are capped at the largest opponent stack (no uncallable over-bets)
+    pub cap_to_effective_stack: u8, // bool as u8
+    /// Minimum buy-in in big blinds at the current blinds (0 = only `min_buy_in`)
+    pub min_buy_in_big_blinds: u8,
+    /// Cash game or sit-and-go (see `GameMode`)
+    pub game_mode: u8,
+    /// Whether the `SubmitBestHand` or `Muck` ending showdown pays out the pot (no `ClaimPot` needed)
+    pub auto_pay: u8, // bool as u8
+    /// Whether every seat's PlayerStats is updated when a pot is settled
+    pub stats_enabled: u8, // bool as u8
 }
 
 impl GameConfig {
@@ -73,6 +100,16 @@ impl GameConfig {
             timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
             slash_percentage: DEFAULT_SLASH_PERCENTAGE,
             game_number: 0,
+            deck_size: DECK_SIZE as u8,
+            rotate_dealer: 1,
+            dead_button: 0,
+            allow_straddle: 0,
+            chip_decimals: 0,
+            cap_to_effective_stack: 0,
+            min_buy_in_big_blinds: 0,
+            game_mode: GameMode::CashGame as u8,
+            auto_pay: 0,
+            stats_enabled: 0,
         }
     }
 
@@ -91,6 +128,171 @@ impl GameConfig {
         self.is_accepting_players = if accepting { 1 } else { 0 };
     }
 
+    /// Whether only two players are seated, whatever the table size
+    pub fn is_heads_up(&self) -> bool {
+        self.current_players == 2
+    }
+
+    /// Small blind seat (the dealer posts the small blind heads-up)
+    pub fn sb_seat(&self) -> u8 {
+        if self.is_heads_up() {
+            self.dealer_index % self.max_players
+        } else {
+            (self.dealer_index + 1) % self.max_players
+        }
+    }
+
+    /// Whether the small blind is posted this hand
+    ///
+    /// Under the dead-button rule the small blind seat can be one whose player
+    /// has left. That hand has a dead small blind: nobody posts it and the big
+    /// blind posts first. Heads-up the button always posts the small blind.
+    pub fn has_small_blind(&self, player_list: &PlayerList) -> bool {
+        self.is_heads_up() || player_list.is_seat_occupied(self.sb_seat())
+    }
+
+    /// Big blind seat: the first occupied seat after the small blind seat
+    ///
+    /// Heads-up at a larger table the other player may sit anywhere, and a
+    /// dead small blind leaves an empty seat before the big blind.
+    pub fn bb_seat(&self, player_list: &PlayerList) -> u8 {
+        self.next_occupied_seat(player_list, self.sb_seat())
+    }
+
+    /// First seat to post a blind: the big blind when the small blind is dead
+    pub fn first_blind_seat(&self, player_list: &PlayerList) -> u8 {
+        if self.has_small_blind(player_list) {
+            self.sb_seat()
+        } else {
+            self.bb_seat(player_list)
+        }
+    }
+
+    /// First seat to act pre-flop (under the gun; the small blind heads-up)
+    pub fn utg_seat(&self, player_list: &PlayerList) -> u8 {
+        if self.is_heads_up() {
+            self.sb_seat()
+        } else {
+            self.next_occupied_seat(player_list, self.bb_seat(player_list))
+        }
+    }
+
+    /// Seat that takes the dealer's turns: dealing the board and showdown hand-offs
+    ///
+    /// The button itself, unless it is dead (or its player has since left);
+    /// then the first occupied seat after it deals.
+    pub fn dealer_seat(&self, player_list: &PlayerList) -> u8 {
+        if self.dead_button == 0 && player_list.is_seat_occupied(self.dealer_index) {
+            self.dealer_index
+        } else {
+            self.next_occupied_seat(player_list, self.dealer_index)
+        }
+    }
+
+    /// Advance the button for the next hand using the dead-button rule
+    ///
+    /// The big blind always moves to the next occupied seat after the previous
+    /// big blind, and the button is placed relative to it. If that puts the
+    /// button on a seat whose player has left, the button is dead for the hand.
+    /// Heads-up the button is the other player, who posts the small blind.
+    /// Fixed-dealer games keep the button where it is.
+    pub fn advance_dealer(&mut self, player_list: &PlayerList) {
+        if self.rotate_dealer == 0 {
+            return;
+        }
+
+        let max_players = self.max_players;
+        let next_bb = self.next_occupied_seat(player_list, self.bb_seat(player_list));
+
+        // Heads-up the button posts the small blind, otherwise SB sits between them
+        let button_seat = if self.is_heads_up() {
+            (1..max_players)
+                .map(|offset| (next_bb + max_players - offset) % max_players)
+                .find(|&seat| player_list.is_seat_occupied(seat))
+                .unwrap_or(next_bb)
+        } else {
+            (next_bb + max_players - 2) % max_players
+        };
+
+        self.dealer_index = button_seat;
+        self.dead_button = (!player_list.is_seat_occupied(button_seat)) as u8;
+    }
+
+    /// First occupied seat after `seat`, wrapping around the table
+    fn next_occupied_seat(&self, player_list: &PlayerList, seat: u8) -> u8 {
+        (1..self.max_players)
+            .map(|offset| (seat + offset) % self.max_players)
+            .find(|&next| player_list.is_seat_occupied(next))
+            .unwrap_or(seat)
+    }
+
+    /// Big blind amount
+    pub fn big_blind(&self) -> u64 {
+        self.small_blind * 2
+    }
+
+    /// Straddle amount (twice the big blind)
+    pub fn straddle_amount(&self) -> u64 {
+        self.big_blind() * 2
+    }
+
+    /// Pre-flop (first_to_act, last_to_act) seats for the given call amount
+    ///
+    /// A call above the big blind means UTG straddled: action starts one
+    /// seat later and the straddler closes the round instead of the big blind.
+    pub fn preflop_seats(&self, player_list: &PlayerList, call_amount: u64) -> (u8, u8) {
+        let utg = self.utg_seat(player_list);
+        if call_amount > self.big_blind() {
+            ((utg + 1) % self.max_players, utg)
+        } else {
+            (utg, self.bb_seat(player_list))
+        }
+    }
+
+    /// Smallest deposit accepted at the current blinds
+    ///
+    /// `min_buy_in` is fixed at initialization, so a buy-in floor expressed in
+    /// big blinds keeps up if the blinds are raised later.
+    pub fn required_buy_in(&self) -> u64 {
+        let in_big_blinds = self.big_blind().saturating_mul(self.min_buy_in_big_blinds as u64);
+        self.min_buy_in.max(in_big_blinds)
+    }
+
+    /// Number of non-owner reveals that complete a card
+    ///
+    /// Always every seat but the owner: cards are locked by every seat, so the
+    /// owner can only open one once each other seat's lock is off. See "No
+    /// Reveal Threshold" in `docs/architecture.md` for why this isn't
+    /// configurable.
+    pub fn reveals_needed(&self, player_list: &PlayerList) -> u8 {
+        player_list.occupied_count().saturating_sub(1)
+    }
+
+    /// Table format (cash game unless configured as a sit-and-go)
+    pub fn game_mode(&self) -> GameMode {
+        GameMode::from(self.game_mode)
+    }
+
+    /// Check if showdown pays out without a separate `ClaimPot`
+    pub fn auto_pay(&self) -> bool {
+        self.auto_pay != 0
+    }
+
+    /// Check if settling a pot must update every seat's PlayerStats
+    pub fn stats_enabled(&self) -> bool {
+        self.stats_enabled != 0
+    }
+
+    /// Smallest token amount that makes up one whole chip
+    pub fn chip_unit(&self) -> u64 {
+        10u64.pow(self.chip_decimals as u32)
+    }
+
+    /// Check if this is a short-deck (36-card) game
+    pub fn is_short_deck(&self) -> bool {
+        self.deck_size as usize == SHORT_DECK_SIZE
+    }
+
     /// Serialize to bytes
     pub fn to_bytes(&self) -> [u8; GAME_CONFIG_SIZE] {
         let mut bytes = [0u8; GAME_CONFIG_SIZE];
@@ -136,6 +338,36 @@ impl GameConfig {
         offset += 1;
 
         bytes[offset..offset + 4].copy_from_slice(&self.game_number.to_le_bytes());
+        offset += 4;
+
+        bytes[offset] = self.deck_size;
+        offset += 1;
+
+        bytes[offset] = self.rotate_dealer;
+        offset += 1;
+
+        bytes[offset] = self.dead_button;
+        offset += 1;
+
+        bytes[offset] = self.allow_straddle;
+        offset += 1;
+
+        bytes[offset] = self.chip_decimals;
+        offset += 1;
+
+        bytes[offset] = self.cap_to_effective_stack;
+        offset += 1;
+
+        bytes[offset] = self.min_buy_in_big_blinds;
+        offset += 1;
+
+        bytes[offset] = self.game_mode;
+        offset += 1;
+
+        bytes[offset] = self.auto_pay;
+        offset += 1;
+
+        bytes[offset] = self.stats_enabled;
 
         bytes
     }
@@ -191,6 +423,36 @@ impl GameConfig {
         offset += 1;
 
         let game_number = u32::from_le_bytes(data[offset..offset + 4].try_into().ok()?);
+        offset += 4;
+
+        let deck_size = data[offset];
+        offset += 1;
+
+        let rotate_dealer = data[offset];
+        offset += 1;
+
+        let dead_button = data[offset];
+        offset += 1;
+
+        let allow_straddle = data[offset];
+        offset += 1;
+
+        let chip_decimals = data[offset];
+        offset += 1;
+
+        let cap_to_effective_stack = data[offset];
+        offset += 1;
+
+        let min_buy_in_big_blinds = data[offset];
+        offset += 1;
+
+        let game_mode = data[offset];
+        offset += 1;
+
+        let auto_pay = data[offset];
+        offset += 1;
+
+        let stats_enabled = data[offset];
 
         Some(Self {
             bump,
@@ -207,6 +469,201 @@ impl GameConfig {
             timeout_seconds,
             slash_percentage,
             game_number,
+            deck_size,
+            rotate_dealer,
+            dead_button,
+            allow_straddle,
+            chip_decimals,
+            cap_to_effective_stack,
+            min_buy_in_big_blinds,
+            game_mode,
+            auto_pay,
+            stats_enabled,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::seated_player_list;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        fn arb_game_config()(
+            keys in any::<([u8; 32], [u8; 32], [u8; 32])>(),
+            seats in any::<(u8, u8, u8, u8, u8)>(),
+            amounts in any::<(u64, u64, i64, u32, u32)>(),
+            flags in any::<(u8, u8, u8, u8, u8, u8, u8, u8, u8)>(),
+            toggles in any::<(u8, u8)>(),
+        ) -> GameConfig {
+            let mut config = GameConfig::new(
+                seats.0, keys.0, keys.1, keys.2, seats.1, amounts.0, amounts.1, amounts.2,
+            );
+            config.current_players = seats.2;
+            config.dealer_index = seats.3;
+            config.is_accepting_players = seats.4;
+            config.timeout_seconds = amounts.3;
+            config.game_number = amounts.4;
+            config.slash_percentage = flags.0;
+            config.deck_size = flags.1;
+            config.rotate_dealer = flags.2;
+            config.dead_button = flags.3;
+            config.allow_straddle = flags.4;
+            config.chip_decimals = flags.5;
+            config.cap_to_effective_stack = flags.6;
+            config.min_buy_in_big_blinds = flags.7;
+            config.game_mode = flags.8;
+            config.auto_pay = toggles.0;
+            config.stats_enabled = toggles.1;
+            config
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(config in arb_game_config()) {
+            prop_assert_eq!(GameConfig::from_bytes(&config.to_bytes()), Some(config));
+        }
+    }
+
+    fn config_with(max_players: u8, dealer_index: u8) -> GameConfig {
+        let mut config =
+            GameConfig::new(0, [0u8; 32], [0u8; 32], [0u8; 32], max_players, 10, 100, 0);
+        config.dealer_index = dealer_index;
+        config.current_players = max_players;
+        config
+    }
+
+    #[test]
+    fn test_seats_heads_up() {
+        let players = seated_player_list(2);
+        let config = config_with(2, 0);
+        assert_eq!(config.sb_seat(), 0);
+        assert_eq!(config.bb_seat(&players), 1);
+        assert_eq!(config.utg_seat(&players), 0);
+
+        let config = config_with(2, 1);
+        assert_eq!(config.sb_seat(), 1);
+        assert_eq!(config.bb_seat(&players), 0);
+        assert_eq!(config.utg_seat(&players), 1);
+    }
+
+    #[test]
+    fn test_seats_heads_up_at_a_larger_table() {
+        // A 6-max table down to seats 1 and 4
+        let mut players = seated_player_list(5);
+        for seat in [0, 2, 3] {
+            players.remove_player(seat);
+        }
+        let mut config = config_with(6, 1);
+        config.current_players = 2;
+        assert_eq!(config.sb_seat(), 1);
+        assert_eq!(config.bb_seat(&players), 4);
+        assert_eq!(config.utg_seat(&players), 1);
+
+        config.dealer_index = 4;
+        assert_eq!(config.sb_seat(), 4);
+        assert_eq!(config.bb_seat(&players), 1);
+        assert_eq!(config.utg_seat(&players), 4);
+    }
+
+    #[test]
+    fn test_seats_three_players() {
+        let players = seated_player_list(3);
+        let config = config_with(3, 0);
+        assert_eq!(config.sb_seat(), 1);
+        assert_eq!(config.bb_seat(&players), 2);
+        assert_eq!(config.utg_seat(&players), 0);
+
+        let config = config_with(3, 2);
+        assert_eq!(config.sb_seat(), 0);
+        assert_eq!(config.bb_seat(&players), 1);
+        assert_eq!(config.utg_seat(&players), 2);
+    }
+
+    #[test]
+    fn test_seats_six_players() {
+        let players = seated_player_list(6);
+        let config = config_with(6, 0);
+        assert_eq!(config.sb_seat(), 1);
+        assert_eq!(config.bb_seat(&players), 2);
+        assert_eq!(config.utg_seat(&players), 3);
+
+        let config = config_with(6, 4);
+        assert_eq!(config.sb_seat(), 5);
+        assert_eq!(config.bb_seat(&players), 0);
+        assert_eq!(config.utg_seat(&players), 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut config = config_with(4, 2);
+        config.current_players = 3;
+        config.game_number = 7;
+        config.deck_size = SHORT_DECK_SIZE as u8;
+        config.rotate_dealer = 0;
+        config.dead_button = 1;
+        config.allow_straddle = 1;
+        config.chip_decimals = 6;
+        config.cap_to_effective_stack = 1;
+
+        let restored = GameConfig::from_bytes(&config.to_bytes()).unwrap();
+        assert_eq!(restored.max_players, 4);
+        assert_eq!(restored.dealer_index, 2);
+        assert_eq!(restored.game_number, 7);
+        assert_eq!(restored.deck_size, SHORT_DECK_SIZE as u8);
+        assert_eq!(restored.rotate_dealer, 0);
+        assert_eq!(restored.dead_button, 1);
+        assert_eq!(restored.allow_straddle, 1);
+        assert_eq!(restored.chip_decimals, 6);
+        assert_eq!(restored.chip_unit(), 1_000_000);
+        assert_eq!(restored.cap_to_effective_stack, 1);
+        assert_eq!(restored.to_bytes(), config.to_bytes());
+    }
+
+    #[test]
+    fn test_dealer_rotates_by_default() {
+        let mut config = config_with(3, 2);
+        let player_list = seated_player_list(3);
+        config.advance_dealer(&player_list);
+        assert_eq!(config.dealer_index, 0);
+        config.advance_dealer(&player_list);
+        assert_eq!(config.dealer_index, 1);
+    }
+
+    #[test]
+    fn test_fixed_dealer_stays_put() {
+        let mut config = config_with(2, 1);
+        config.rotate_dealer = 0;
+        let player_list = seated_player_list(2);
+        for _ in 0..3 {
+            config.advance_dealer(&player_list);
+            assert_eq!(config.dealer_index, 1);
+        }
+    }
+
+    #[test]
+    fn test_required_buy_in_tracks_blinds() {
+        // 10/20 blinds, 100 minimum, 20 big blinds required
+        let mut config = config_with(4, 0);
+        assert_eq!(config.required_buy_in(), 100);
+        config.min_buy_in_big_blinds = 20;
+        assert_eq!(config.required_buy_in(), 400);
+
+        // After raising the blinds to 50/100 the stale minimum no longer applies
+        config.small_blind = 50;
+        assert_eq!(config.required_buy_in(), 2000);
+
+        // Without a multiple only the fixed minimum is enforced
+        config.min_buy_in_big_blinds = 0;
+        assert_eq!(config.required_buy_in(), 100);
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let bytes = config_with(4, 0).to_bytes();
+        assert_eq!(bytes.len(), GAME_CONFIG_SIZE);
+        assert!(GameConfig::from_bytes(&bytes[..GAME_CONFIG_SIZE - 1]).is_none());
+    }
+}
@@ -6,13 +6,43 @@ use pinocchio::pubkey::{find_program_address, Pubkey};
 
 use crate::constants::{GAME_STATE_SEED, DECK_SIZE};
 use crate::state::enums::*;
+use crate::error::PokerError;
+use crate::utils::read_u64;
+
+/// Number of actions kept in the per-round action log
+pub const ACTION_LOG_CAPACITY: usize = 8;
+
+/// Size of one action log entry: seat(1) + action_type(1) + amount(8)
+pub const ACTION_ENTRY_SIZE: usize = 1 + 1 + 8;
 
 /// Size of GameState account in bytes
-pub const GAME_STATE_SIZE: usize = 1 + 32 + 6 + 8 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 32 + 1 + 1 + 1 + 1 + 8 + 8 + 1; // ~125 bytes
+/// bump(1) + game_id(32) + states(6) + turn counters(6) + pot(8) + current_call_amount(8)
+/// + last_to_call(32) + is_everybody_all_in(1) + pot_claimed(1) + deck tracking(3)
+/// + last_action_timestamp(8) + action_log_round(1) + action_count(1) + action_log(8*10)
+/// + players_to_act(1) + last_raise(8) + reveal_round(2) = 199 bytes
+pub const GAME_STATE_SIZE: usize = 1 + 32 + 6 + 6 + 8 + 8 + 32 + 1 + 1 + 3 + 8
+    + 1 + 1 + (ACTION_LOG_CAPACITY * ACTION_ENTRY_SIZE) + 1 + 8 + 2;
+
+/// A single betting action, kept for UI replays
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BetAction {
+    /// Seat index of the acting player
+    pub seat: u8,
+    /// BetActionType as u8
+    pub action_type: u8,
+    /// Chips put in with this action
+    pub amount: u64,
+}
+
+impl BetAction {
+    pub fn action_type(&self) -> BetActionType {
+        BetActionType::from(self.action_type)
+    }
+}
 
 /// Game state machine account
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct GameState {
     /// PDA bump seed
     pub bump: u8,
@@ -50,11 +80,11 @@ pub struct GameState {
     // Betting
     /// Current pot amount
     pub pot: u64,
-    /// Current pot size (alias for pot)
+    /// Current pot size (alias for pot, not stored)
     pub pot_size: u64,
     /// Current call amount
     pub current_call_amount: u64,
-    /// Current bet amount
+    /// Current bet amount (alias for current_call_amount, not stored)
     pub current_bet: u64,
     /// Last raise amount
     pub last_raise: u64,
@@ -76,6 +106,24 @@ pub struct GameState {
     // Timing
     /// Last action timestamp for slash mechanism
     pub last_action_timestamp: i64,
+
+    // Action log
+    /// Betting round the action log belongs to
+    pub action_log_round: u8,
+    /// Number of actions recorded this round (may exceed the log capacity;
+    /// past 255 it wraps back to the capacity, see `next_action_count`)
+    pub action_count: u8,
+    /// Ring buffer of the most recent actions this round
+    pub action_log: [BetAction; ACTION_LOG_CAPACITY],
+
+    // Round completion
+    /// Seats that still have to act before the betting round closes
+    pub players_to_act: u8,
+
+    // Reveal ordering
+    /// Incremented each time a card (or dealt batch) goes up for reveal;
+    /// every `Reveal` must carry the current value
+    pub reveal_round: u16,
 }
 
 impl GameState {
@@ -108,6 +156,11 @@ impl GameState {
             cards_left_in_deck: DECK_SIZE as u8,
             is_deck_submitted: 0,
             last_action_timestamp: timestamp,
+            action_log_round: BettingRoundState::Blinds as u8,
+            action_count: 0,
+            action_log: [BetAction::default(); ACTION_LOG_CAPACITY],
+            players_to_act: 0,
+            reveal_round: 0,
         }
     }
 
@@ -149,6 +202,81 @@ impl GameState {
         self.is_everybody_all_in != 0
     }
 
+    /// Deck indices awaiting reveal, `card_to_reveal` first
+    ///
+    /// Cards are dealt from the top of the deck, so a batch dealt together
+    /// (the flop via `DealFlop`) spans `cards_left_in_deck..=card_to_reveal`.
+    /// A single drawn or dealt card is a batch of one.
+    pub fn pending_reveal_cards(&self) -> impl Iterator<Item = u8> {
+        (self.cards_left_in_deck..=self.card_to_reveal).rev()
+    }
+
+    /// Number of cards in the current reveal batch
+    pub fn pending_reveal_count(&self) -> usize {
+        (self.card_to_reveal as usize + 1).saturating_sub(self.cards_left_in_deck as usize)
+    }
+
+    /// Put the pending batch up for reveal under a fresh `reveal_round`
+    ///
+    /// The round is never reset, so a reveal signed for an earlier card can
+    /// not be replayed against a later one.
+    pub fn begin_reveal(&mut self) {
+        self.drawing_state = DrawingState::Revealing as u8;
+        self.reveal_round = self.reveal_round.wrapping_add(1);
+    }
+
+    /// Record a betting action in the per-round log
+    ///
+    /// The log is cleared lazily when the first action of a new round arrives,
+    /// so the previous round stays readable until then.
+    pub fn record_action(&mut self, seat: u8, action_type: BetActionType, amount: u64) {
+        if self.action_log_round != self.betting_round_state {
+            self.clear_action_log();
+        }
+        let slot = self.action_count as usize % ACTION_LOG_CAPACITY;
+        self.action_log[slot] = BetAction {
+            seat,
+            action_type: action_type as u8,
+            amount,
+        };
+        self.action_count = next_action_count(self.action_count);
+    }
+
+    /// Clear the action log and bind it to the current betting round
+    pub fn clear_action_log(&mut self) {
+        self.action_log_round = self.betting_round_state;
+        self.action_count = 0;
+        self.action_log = [BetAction::default(); ACTION_LOG_CAPACITY];
+    }
+
+    /// Logged actions for the round, oldest first
+    pub fn recent_actions(&self) -> impl Iterator<Item = BetAction> + '_ {
+        let len = (self.action_count as usize).min(ACTION_LOG_CAPACITY);
+        let start = self.action_count as usize - len;
+        (start..self.action_count as usize)
+            .map(move |i| self.action_log[i % ACTION_LOG_CAPACITY])
+    }
+
+    /// Open a betting round in which every active player must act
+    pub fn start_betting_round(&mut self, active_players: u8) {
+        self.players_to_act = active_players;
+        self.last_raise = 0;
+    }
+
+    /// Count one seat's action towards closing the round
+    ///
+    /// A raise re-opens action, so everyone else must respond again.
+    /// Returns true once nobody is left to act.
+    pub fn note_player_acted(&mut self, raised: bool, active_players: u8) -> bool {
+        self.players_to_act = players_left_to_act(self.players_to_act, raised, active_players);
+        self.players_to_act == 0
+    }
+
+    /// Whether the current betting round is over (see `is_betting_round_complete`)
+    pub fn is_betting_round_complete(&self, seats: Option<&[SeatBetting]>) -> bool {
+        is_betting_round_complete(self.players_to_act, seats)
+    }
+
     /// Serialize to bytes
     pub fn to_bytes(&self) -> [u8; GAME_STATE_SIZE] {
         let mut bytes = [0u8; GAME_STATE_SIZE];
@@ -205,6 +333,26 @@ impl GameState {
         offset += 1;
 
         bytes[offset..offset + 8].copy_from_slice(&self.last_action_timestamp.to_le_bytes());
+        offset += 8;
+
+        bytes[offset] = self.action_log_round;
+        offset += 1;
+        bytes[offset] = self.action_count;
+        offset += 1;
+        for action in &self.action_log {
+            bytes[offset] = action.seat;
+            bytes[offset + 1] = action.action_type;
+            bytes[offset + 2..offset + 10].copy_from_slice(&action.amount.to_le_bytes());
+            offset += ACTION_ENTRY_SIZE;
+        }
+
+        bytes[offset] = self.players_to_act;
+        offset += 1;
+
+        bytes[offset..offset + 8].copy_from_slice(&self.last_raise.to_le_bytes());
+        offset += 8;
+
+        bytes[offset..offset + 2].copy_from_slice(&self.reveal_round.to_le_bytes());
 
         bytes
     }
@@ -214,6 +362,7 @@ impl GameState {
         if data.len() < GAME_STATE_SIZE {
             return None;
         }
+        validate_state_bytes(data).ok()?;
 
         let mut offset = 0;
 
@@ -270,6 +419,27 @@ impl GameState {
         offset += 1;
 
         let last_action_timestamp = i64::from_le_bytes(data[offset..offset + 8].try_into().ok()?);
+        offset += 8;
+
+        let action_log_round = data[offset];
+        offset += 1;
+        let action_count = data[offset];
+        offset += 1;
+        let mut action_log = [BetAction::default(); ACTION_LOG_CAPACITY];
+        for action in &mut action_log {
+            action.seat = data[offset];
+            action.action_type = data[offset + 1];
+            action.amount = u64::from_le_bytes(data[offset + 2..offset + 10].try_into().ok()?);
+            offset += ACTION_ENTRY_SIZE;
+        }
+
+        let players_to_act = data[offset];
+        offset += 1;
+
+        let last_raise = u64::from_le_bytes(data[offset..offset + 8].try_into().ok()?);
+        offset += 8;
+
+        let reveal_round = u16::from_le_bytes(data[offset..offset + 2].try_into().ok()?);
 
         Some(Self {
             bump,
@@ -290,7 +460,7 @@ impl GameState {
             pot_size: pot,
             current_call_amount,
             current_bet: current_call_amount,
-            last_raise: 0,
+            last_raise,
             last_to_call,
             is_everybody_all_in,
             pot_claimed,
@@ -298,6 +468,672 @@ impl GameState {
             cards_left_in_deck,
             is_deck_submitted,
             last_action_timestamp,
+            action_log_round,
+            action_count,
+            action_log,
+            players_to_act,
+            reveal_round,
         })
     }
 }
+
+/// Betting status of one seat, used to detect an all-in close of the round
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SeatBetting {
+    pub is_folded: bool,
+    pub chips: u64,
+    pub current_bet: u64,
+}
+
+/// Whether the current betting round is over
+///
+/// The round closes once nobody is left to act: everyone checked around, or
+/// the last player facing a bet called it. When the status of every seat is
+/// known it also closes as soon as at most one unfolded player still has
+/// chips and that player has matched the highest bet, since all-in players
+/// cannot act any more.
+pub fn is_betting_round_complete(players_to_act: u8, seats: Option<&[SeatBetting]>) -> bool {
+    if players_to_act == 0 {
+        return true;
+    }
+    seats.is_some_and(no_further_betting)
+}
+
+/// Whether at most one unfolded player still has chips and has matched the
+/// highest bet, so nobody can bet again for the rest of the hand
+pub fn no_further_betting(seats: &[SeatBetting]) -> bool {
+    let live = || seats.iter().filter(|seat| !seat.is_folded);
+    let highest_bet = live().map(|seat| seat.current_bet).max().unwrap_or(0);
+    let mut can_bet = live().filter(|seat| seat.chips > 0);
+    match (can_bet.next(), can_bet.next()) {
+        (None, _) => true,
+        (Some(seat), None) => seat.current_bet >= highest_bet,
+        _ => false,
+    }
+}
+
+/// Action count after one more action is logged
+///
+/// The count must keep advancing for the ring buffer to rotate, and must not
+/// drop below the capacity or `recent_actions` would lose entries. u8 range
+/// is a multiple of the capacity, so wrapping 255 back to the capacity lands
+/// on the same slot as 256 would.
+fn next_action_count(count: u8) -> u8 {
+    count.checked_add(1).unwrap_or(ACTION_LOG_CAPACITY as u8)
+}
+
+// Wrapping from 255 to the capacity must keep the slot sequence intact
+const _: () = assert!((u8::MAX as usize + 1).is_multiple_of(ACTION_LOG_CAPACITY));
+
+/// Seats left to act after one more action (see `GameState::note_player_acted`)
+fn players_left_to_act(players_to_act: u8, raised: bool, active_players: u8) -> u8 {
+    if raised {
+        active_players.saturating_sub(1)
+    } else {
+        players_to_act.saturating_sub(1)
+    }
+}
+
+// =============================================================================
+// Zero-Copy Reference Types (Stack-Efficient)
+// =============================================================================
+
+// Layout offsets for zero-copy access (must match to_bytes/from_bytes)
+const GAME_PHASE_OFFSET: usize = 33;
+const SHUFFLING_STATE_OFFSET: usize = 34;
+const DRAWING_STATE_OFFSET: usize = 35;
+const TEXAS_STATE_OFFSET: usize = 36;
+const BETTING_ROUND_STATE_OFFSET: usize = 37;
+const COMMUNITY_CARDS_STATE_OFFSET: usize = 38;
+const CURRENT_TURN_OFFSET: usize = 39;
+const NUM_FOLDED_PLAYERS_OFFSET: usize = 41;
+const POT_OFFSET: usize = 45;
+const CURRENT_CALL_AMOUNT_OFFSET: usize = 53;
+const LAST_TO_CALL_OFFSET: usize = 61;
+const IS_EVERYBODY_ALL_IN_OFFSET: usize = 93;
+const LAST_ACTION_TIMESTAMP_OFFSET: usize = 98;
+const ACTION_LOG_ROUND_OFFSET: usize = 106;
+const ACTION_COUNT_OFFSET: usize = 107;
+const ACTION_LOG_OFFSET: usize = 108;
+const PLAYERS_TO_ACT_OFFSET: usize = ACTION_LOG_OFFSET + ACTION_LOG_CAPACITY * ACTION_ENTRY_SIZE;
+const LAST_RAISE_OFFSET: usize = PLAYERS_TO_ACT_OFFSET + 1;
+const REVEAL_ROUND_OFFSET: usize = LAST_RAISE_OFFSET + 8;
+
+// The last field must end exactly at the account size
+const _: () = assert!(REVEAL_ROUND_OFFSET + 2 == GAME_STATE_SIZE);
+
+/// Check every state machine byte holds a known variant
+///
+/// The enums' `From<u8>` falls back to a default, which would quietly turn
+/// a corrupted account into a fresh-looking one, so reads reject it instead.
+fn validate_state_bytes(data: &[u8]) -> Result<(), PokerError> {
+    GamePhase::try_from_u8(data[GAME_PHASE_OFFSET])?;
+    ShufflingState::try_from_u8(data[SHUFFLING_STATE_OFFSET])?;
+    DrawingState::try_from_u8(data[DRAWING_STATE_OFFSET])?;
+    TexasHoldEmState::try_from_u8(data[TEXAS_STATE_OFFSET])?;
+    BettingRoundState::try_from_u8(data[BETTING_ROUND_STATE_OFFSET])?;
+    CommunityCardsState::try_from_u8(data[COMMUNITY_CARDS_STATE_OFFSET])?;
+    Ok(())
+}
+
+/// Zero-copy immutable view into GameState account data.
+/// Stack cost: ~16 bytes (just the slice reference)
+#[derive(Clone, Copy)]
+pub struct GameStateRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> GameStateRef<'a> {
+    /// Create a zero-copy reference from account data bytes
+    #[inline]
+    pub fn from_bytes(data: &'a [u8]) -> Option<Self> {
+        if data.len() < GAME_STATE_SIZE {
+            return None;
+        }
+        validate_state_bytes(data).ok()?;
+        Some(Self { data })
+    }
+
+    /// Get the Texas Hold'em state
+    #[inline]
+    pub fn texas_state(&self) -> TexasHoldEmState {
+        TexasHoldEmState::from(self.data[TEXAS_STATE_OFFSET])
+    }
+
+    /// Get the betting round state
+    #[inline]
+    pub fn betting_round_state(&self) -> BettingRoundState {
+        BettingRoundState::from(self.data[BETTING_ROUND_STATE_OFFSET])
+    }
+
+    /// Get the current turn index
+    #[inline]
+    pub fn current_turn(&self) -> u8 {
+        self.data[CURRENT_TURN_OFFSET]
+    }
+
+    /// Get the number of folded players
+    #[inline]
+    pub fn num_folded_players(&self) -> u8 {
+        self.data[NUM_FOLDED_PLAYERS_OFFSET]
+    }
+
+    /// Get the pot amount
+    #[inline]
+    pub fn pot(&self) -> u64 {
+        read_u64(self.data, POT_OFFSET)
+    }
+
+    /// Get the current call amount
+    #[inline]
+    pub fn current_call_amount(&self) -> u64 {
+        read_u64(self.data, CURRENT_CALL_AMOUNT_OFFSET)
+    }
+
+    /// Get the size of the last raise this round
+    #[inline]
+    pub fn last_raise(&self) -> u64 {
+        read_u64(self.data, LAST_RAISE_OFFSET)
+    }
+
+    /// Get the last player to call
+    #[inline]
+    pub fn last_to_call(&self) -> &Pubkey {
+        unsafe { &*(self.data[LAST_TO_CALL_OFFSET..].as_ptr() as *const Pubkey) }
+    }
+
+    /// Get the last action timestamp
+    #[inline]
+    pub fn last_action_timestamp(&self) -> i64 {
+        read_u64(self.data, LAST_ACTION_TIMESTAMP_OFFSET) as i64
+    }
+}
+
+/// Zero-copy mutable view into GameState account data.
+/// Stack cost: ~16 bytes (just the slice reference)
+pub struct GameStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> GameStateMut<'a> {
+    /// Create a zero-copy mutable reference from account data bytes
+    #[inline]
+    pub fn from_bytes(data: &'a mut [u8]) -> Option<Self> {
+        if data.len() < GAME_STATE_SIZE {
+            return None;
+        }
+        validate_state_bytes(data).ok()?;
+        Some(Self { data })
+    }
+
+    /// Get the game phase
+    #[inline]
+    pub fn game_phase(&self) -> GamePhase {
+        GamePhase::from(self.data[GAME_PHASE_OFFSET])
+    }
+
+    /// Get the Texas Hold'em state
+    #[inline]
+    pub fn texas_state(&self) -> TexasHoldEmState {
+        TexasHoldEmState::from(self.data[TEXAS_STATE_OFFSET])
+    }
+
+    /// Set the Texas Hold'em state
+    #[inline]
+    pub fn set_texas_state(&mut self, state: TexasHoldEmState) {
+        self.data[TEXAS_STATE_OFFSET] = state as u8;
+    }
+
+    /// Get the betting round state
+    #[inline]
+    pub fn betting_round_state(&self) -> BettingRoundState {
+        BettingRoundState::from(self.data[BETTING_ROUND_STATE_OFFSET])
+    }
+
+    /// Set the community cards state
+    #[inline]
+    pub fn set_community_cards_state(&mut self, state: CommunityCardsState) {
+        self.data[COMMUNITY_CARDS_STATE_OFFSET] = state as u8;
+    }
+
+    /// Get the current turn index
+    #[inline]
+    pub fn current_turn(&self) -> u8 {
+        self.data[CURRENT_TURN_OFFSET]
+    }
+
+    /// Set the current turn index
+    #[inline]
+    pub fn set_current_turn(&mut self, turn: u8) {
+        self.data[CURRENT_TURN_OFFSET] = turn;
+    }
+
+    /// Get the number of folded players
+    #[inline]
+    pub fn num_folded_players(&self) -> u8 {
+        self.data[NUM_FOLDED_PLAYERS_OFFSET]
+    }
+
+    /// Get the pot amount
+    #[inline]
+    pub fn pot(&self) -> u64 {
+        read_u64(self.data, POT_OFFSET)
+    }
+
+    /// Set the pot amount
+    #[inline]
+    pub fn set_pot(&mut self, pot: u64) {
+        self.data[POT_OFFSET..POT_OFFSET + 8].copy_from_slice(&pot.to_le_bytes());
+    }
+
+    /// Get the current call amount
+    #[inline]
+    pub fn current_call_amount(&self) -> u64 {
+        read_u64(self.data, CURRENT_CALL_AMOUNT_OFFSET)
+    }
+
+    /// Set the current call amount
+    #[inline]
+    pub fn set_current_call_amount(&mut self, amount: u64) {
+        self.data[CURRENT_CALL_AMOUNT_OFFSET..CURRENT_CALL_AMOUNT_OFFSET + 8]
+            .copy_from_slice(&amount.to_le_bytes());
+    }
+
+    /// Get the size of the last raise this round
+    #[inline]
+    pub fn last_raise(&self) -> u64 {
+        read_u64(self.data, LAST_RAISE_OFFSET)
+    }
+
+    /// Set the size of the last raise this round
+    #[inline]
+    pub fn set_last_raise(&mut self, amount: u64) {
+        self.data[LAST_RAISE_OFFSET..LAST_RAISE_OFFSET + 8].copy_from_slice(&amount.to_le_bytes());
+    }
+
+    /// Get the last player to call
+    #[inline]
+    pub fn last_to_call(&self) -> &Pubkey {
+        unsafe { &*(self.data[LAST_TO_CALL_OFFSET..].as_ptr() as *const Pubkey) }
+    }
+
+    /// Set the last player to call
+    #[inline]
+    pub fn set_last_to_call(&mut self, player: &Pubkey) {
+        self.data[LAST_TO_CALL_OFFSET..LAST_TO_CALL_OFFSET + 32].copy_from_slice(player);
+    }
+
+    /// Flag that nobody can bet again this hand, so the board runs out
+    #[inline]
+    pub fn set_everybody_all_in(&mut self) {
+        self.data[IS_EVERYBODY_ALL_IN_OFFSET] = 1;
+    }
+
+    /// Set the last action timestamp
+    #[inline]
+    pub fn set_last_action_timestamp(&mut self, timestamp: i64) {
+        self.data[LAST_ACTION_TIMESTAMP_OFFSET..LAST_ACTION_TIMESTAMP_OFFSET + 8]
+            .copy_from_slice(&timestamp.to_le_bytes());
+    }
+
+    /// Get the number of seats still to act this round
+    #[inline]
+    pub fn players_to_act(&self) -> u8 {
+        self.data[PLAYERS_TO_ACT_OFFSET]
+    }
+
+    /// Count one seat's action towards closing the round (see `GameState::note_player_acted`)
+    pub fn note_player_acted(&mut self, raised: bool, active_players: u8) -> bool {
+        let remaining = players_left_to_act(self.players_to_act(), raised, active_players);
+        self.data[PLAYERS_TO_ACT_OFFSET] = remaining;
+        remaining == 0
+    }
+
+    /// Whether the current betting round is over (see `is_betting_round_complete`)
+    pub fn is_betting_round_complete(&self, seats: Option<&[SeatBetting]>) -> bool {
+        is_betting_round_complete(self.players_to_act(), seats)
+    }
+
+    /// Record a betting action in the per-round log (see `GameState::record_action`)
+    pub fn record_action(&mut self, seat: u8, action_type: BetActionType, amount: u64) {
+        let round = self.data[BETTING_ROUND_STATE_OFFSET];
+        if self.data[ACTION_LOG_ROUND_OFFSET] != round {
+            self.data[ACTION_LOG_ROUND_OFFSET] = round;
+            self.data[ACTION_COUNT_OFFSET] = 0;
+            let log_end = ACTION_LOG_OFFSET + ACTION_LOG_CAPACITY * ACTION_ENTRY_SIZE;
+            self.data[ACTION_LOG_OFFSET..log_end].fill(0);
+        }
+        let count = self.data[ACTION_COUNT_OFFSET];
+        let offset = ACTION_LOG_OFFSET + (count as usize % ACTION_LOG_CAPACITY) * ACTION_ENTRY_SIZE;
+        self.data[offset] = seat;
+        self.data[offset + 1] = action_type as u8;
+        self.data[offset + 2..offset + 10].copy_from_slice(&amount.to_le_bytes());
+        self.data[ACTION_COUNT_OFFSET] = next_action_count(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        fn arb_bet_action()(
+            seat in any::<u8>(),
+            action_type in any::<u8>(),
+            amount in any::<u64>(),
+        ) -> BetAction {
+            BetAction { seat, action_type, amount }
+        }
+    }
+
+    prop_compose! {
+        fn arb_game_state()(
+            header in any::<(u8, [u8; 32], i64)>(),
+            states in (0..5u8, 0..5u8, 0..3u8, 0..10u8, 0..5u8, 0..4u8),
+            counters in any::<[u8; 6]>(),
+            betting in any::<(u64, u64, Pubkey, u64)>(),
+            flags in any::<[u8; 5]>(),
+            log in any::<(u8, u8, u8)>(),
+            action_log in proptest::array::uniform8(arb_bet_action()),
+            reveal_round in any::<u16>(),
+        ) -> GameState {
+            let mut game_state = GameState::new(header.0, header.1, header.2);
+            game_state.game_phase = states.0;
+            game_state.shuffling_state = states.1;
+            game_state.drawing_state = states.2;
+            game_state.texas_state = states.3;
+            game_state.betting_round_state = states.4;
+            game_state.community_cards_state = states.5;
+            game_state.current_turn = counters[0];
+            game_state.active_player_count = counters[1];
+            game_state.num_folded_players = counters[2];
+            game_state.cards_drawn = counters[3];
+            game_state.player_cards_opened = counters[4];
+            game_state.num_submitted_hands = counters[5];
+            game_state.pot = betting.0;
+            game_state.current_call_amount = betting.1;
+            game_state.last_to_call = betting.2;
+            // Aliases rebuilt on read rather than stored
+            game_state.pot_size = betting.0;
+            game_state.current_bet = betting.1;
+            game_state.is_everybody_all_in = flags[0];
+            game_state.pot_claimed = flags[1];
+            game_state.card_to_reveal = flags[2];
+            game_state.cards_left_in_deck = flags[3];
+            game_state.is_deck_submitted = flags[4];
+            game_state.action_log_round = log.0;
+            game_state.action_count = log.1;
+            game_state.players_to_act = log.2;
+            game_state.last_raise = betting.3;
+            game_state.action_log = action_log;
+            game_state.reveal_round = reveal_round;
+            game_state
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(game_state in arb_game_state()) {
+            prop_assert_eq!(GameState::from_bytes(&game_state.to_bytes()), Some(game_state));
+        }
+    }
+
+    fn seat(chips: u64, current_bet: u64) -> SeatBetting {
+        SeatBetting { is_folded: false, chips, current_bet }
+    }
+
+    #[test]
+    fn test_round_complete_after_check_around() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.start_betting_round(3);
+        for _ in 0..2 {
+            game_state.note_player_acted(false, 3);
+            assert!(!game_state.is_betting_round_complete(None));
+        }
+        game_state.note_player_acted(false, 3);
+        assert!(game_state.is_betting_round_complete(None));
+    }
+
+    #[test]
+    fn test_round_complete_on_call_after_raise() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.start_betting_round(3);
+        game_state.note_player_acted(false, 3);
+        // Raise re-opens action for the other two
+        game_state.note_player_acted(true, 3);
+        game_state.note_player_acted(false, 3);
+        assert!(!game_state.is_betting_round_complete(None));
+        // Last caller closes it
+        game_state.note_player_acted(false, 3);
+        assert!(game_state.is_betting_round_complete(None));
+    }
+
+    #[test]
+    fn test_round_complete_when_all_in() {
+        // Everyone but one player is all-in and the bet is matched
+        let seats = [seat(0, 500), seat(400, 500), seat(0, 300)];
+        assert!(is_betting_round_complete(2, Some(&seats)));
+
+        // The remaining player still faces the all-in and must act
+        let facing = [seat(0, 500), seat(400, 100), seat(0, 300)];
+        assert!(!is_betting_round_complete(2, Some(&facing)));
+
+        // Nobody has chips left
+        assert!(is_betting_round_complete(1, Some(&[seat(0, 500), seat(0, 200)])));
+
+        // Two players can still bet, so action continues
+        assert!(!is_betting_round_complete(1, Some(&[seat(10, 100), seat(10, 100)])));
+
+        // Folded seats are ignored
+        let folded = SeatBetting { is_folded: true, chips: 900, current_bet: 0 };
+        assert!(is_betting_round_complete(2, Some(&[seat(0, 200), seat(50, 200), folded])));
+    }
+
+    #[test]
+    fn test_action_log_bet_call_fold() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+
+        game_state.record_action(3, BetActionType::Raise, 40);
+        game_state.record_action(4, BetActionType::Call, 40);
+        game_state.record_action(5, BetActionType::Fold, 0);
+
+        let restored = GameState::from_bytes(&game_state.to_bytes()).unwrap();
+        let actions: Vec<BetAction> = restored.recent_actions().collect();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            (actions[0].seat, actions[0].action_type(), actions[0].amount),
+            (3, BetActionType::Raise, 40)
+        );
+        assert_eq!(
+            (actions[1].seat, actions[1].action_type(), actions[1].amount),
+            (4, BetActionType::Call, 40)
+        );
+        assert_eq!(
+            (actions[2].seat, actions[2].action_type(), actions[2].amount),
+            (5, BetActionType::Fold, 0)
+        );
+
+        // First action of the next round starts a fresh log
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.record_action(4, BetActionType::Check, 0);
+        let actions: Vec<BetAction> = game_state.recent_actions().collect();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].action_type(), BetActionType::Check);
+    }
+
+    #[test]
+    fn test_action_log_keeps_most_recent() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        for i in 0..(ACTION_LOG_CAPACITY as u8 + 3) {
+            game_state.record_action(i % 6, BetActionType::Call, i as u64);
+        }
+        let amounts: Vec<u64> = game_state.recent_actions().map(|a| a.amount).collect();
+        assert_eq!(amounts.len(), ACTION_LOG_CAPACITY);
+        assert_eq!(amounts[0], 3);
+        assert_eq!(*amounts.last().unwrap(), ACTION_LOG_CAPACITY as u64 + 2);
+    }
+
+    #[test]
+    fn test_action_log_keeps_rotating_past_255() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        let mut data = game_state.to_bytes();
+        for i in 0..300u64 {
+            game_state.record_action(0, BetActionType::Call, i);
+            GameStateMut::from_bytes(&mut data)
+                .unwrap()
+                .record_action(0, BetActionType::Call, i);
+        }
+        assert_eq!(data, game_state.to_bytes());
+
+        let amounts: Vec<u64> = game_state.recent_actions().map(|a| a.amount).collect();
+        let expected: Vec<u64> = (300 - ACTION_LOG_CAPACITY as u64..300).collect();
+        assert_eq!(amounts, expected);
+    }
+
+    #[test]
+    fn test_zero_copy_matches_owned() {
+        let mut game_state = GameState::new(0, [9u8; 32], 1_700_000_000);
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.current_turn = 4;
+        game_state.num_folded_players = 1;
+        game_state.pot = 1234;
+        game_state.current_call_amount = 200;
+        game_state.last_to_call = [3u8; 32];
+
+        let bytes = game_state.to_bytes();
+        let view = GameStateRef::from_bytes(&bytes).unwrap();
+        assert_eq!(view.texas_state(), TexasHoldEmState::Betting);
+        assert_eq!(view.betting_round_state(), BettingRoundState::PostFlop);
+        assert_eq!(view.current_turn(), 4);
+        assert_eq!(view.num_folded_players(), 1);
+        assert_eq!(view.pot(), 1234);
+        assert_eq!(view.current_call_amount(), 200);
+        assert_eq!(view.last_to_call(), &[3u8; 32]);
+        assert_eq!(view.last_action_timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_zero_copy_writes_round_trip() {
+        let mut game_state = GameState::new(0, [9u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PreFlop as u8;
+
+        let mut bytes = game_state.to_bytes();
+        {
+            let mut view = GameStateMut::from_bytes(&mut bytes).unwrap();
+            view.set_texas_state(TexasHoldEmState::CommunityCardsAwaiting);
+            view.set_community_cards_state(CommunityCardsState::FlopAwaiting);
+            view.set_current_turn(2);
+            view.set_pot(500);
+            view.set_current_call_amount(100);
+            view.set_last_to_call(&[7u8; 32]);
+            view.set_last_action_timestamp(42);
+            view.record_action(2, BetActionType::Raise, 100);
+        }
+
+        // Same mutations through the owned type must produce identical bytes
+        game_state.texas_state = TexasHoldEmState::CommunityCardsAwaiting as u8;
+        game_state.community_cards_state = CommunityCardsState::FlopAwaiting as u8;
+        game_state.current_turn = 2;
+        game_state.pot = 500;
+        game_state.current_call_amount = 100;
+        game_state.last_to_call = [7u8; 32];
+        game_state.last_action_timestamp = 42;
+        game_state.record_action(2, BetActionType::Raise, 100);
+        assert_eq!(bytes, game_state.to_bytes());
+
+        let restored = GameState::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.texas_state(), TexasHoldEmState::CommunityCardsAwaiting);
+        assert_eq!(restored.pot, 500);
+        assert_eq!(restored.recent_actions().count(), 1);
+    }
+
+    #[test]
+    fn test_last_raise_survives_round_trip() {
+        let mut game_state = GameState::new(0, [9u8; 32], 0);
+        game_state.last_raise = 150;
+        game_state.players_to_act = 2;
+
+        let mut bytes = game_state.to_bytes();
+        let restored = GameState::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.last_raise, 150);
+        assert_eq!(restored.players_to_act, 2);
+
+        let mut view = GameStateMut::from_bytes(&mut bytes).unwrap();
+        assert_eq!(view.last_raise(), 150);
+        view.set_last_raise(300);
+        assert_eq!(GameState::from_bytes(&bytes).unwrap().last_raise, 300);
+    }
+
+    #[test]
+    fn test_raise_reopens_action() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.start_betting_round(3);
+
+        // Seat A checks, seat B checks
+        assert!(!game_state.note_player_acted(false, 3));
+        assert!(!game_state.note_player_acted(false, 3));
+        assert_eq!(game_state.players_to_act, 1);
+
+        // Seat C raises: A and B must act again
+        assert!(!game_state.note_player_acted(true, 3));
+        assert_eq!(game_state.players_to_act, 2);
+
+        // A calls, B calls: round closes
+        assert!(!game_state.note_player_acted(false, 3));
+        assert!(game_state.note_player_acted(false, 3));
+
+        let restored = GameState::from_bytes(&game_state.to_bytes()).unwrap();
+        assert_eq!(restored.players_to_act, 0);
+    }
+
+    #[test]
+    fn test_zero_copy_players_to_act_matches_owned() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.start_betting_round(4);
+        let mut bytes = game_state.to_bytes();
+
+        let mut view = GameStateMut::from_bytes(&mut bytes).unwrap();
+        assert_eq!(view.players_to_act(), 4);
+        view.note_player_acted(false, 4);
+        view.note_player_acted(true, 4);
+        assert_eq!(view.players_to_act(), 3);
+
+        game_state.note_player_acted(false, 4);
+        game_state.note_player_acted(true, 4);
+        assert_eq!(bytes, game_state.to_bytes());
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let game_state = GameState::new(0, [0u8; 32], 0);
+        let mut data = game_state.to_bytes().to_vec();
+        assert_eq!(data.len(), GAME_STATE_SIZE);
+        assert!(GameState::from_bytes(&data[..GAME_STATE_SIZE - 1]).is_none());
+        assert!(GameStateRef::from_bytes(&data[..GAME_STATE_SIZE - 1]).is_none());
+        assert!(GameStateMut::from_bytes(&mut data[..GAME_STATE_SIZE - 1]).is_none());
+    }
+
+    #[test]
+    fn test_corrupted_state_byte_rejected() {
+        let valid = GameState::new(0, [0u8; 32], 0).to_bytes();
+        let out_of_range = [
+            (GAME_PHASE_OFFSET, 5),
+            (SHUFFLING_STATE_OFFSET, 5),
+            (DRAWING_STATE_OFFSET, 3),
+            (TEXAS_STATE_OFFSET, 10),
+            (BETTING_ROUND_STATE_OFFSET, 5),
+            (COMMUNITY_CARDS_STATE_OFFSET, 4),
+        ];
+        for (offset, byte) in out_of_range {
+            let mut data = valid;
+            data[offset] = byte;
+            assert!(GameState::from_bytes(&data).is_none());
+            assert!(GameStateRef::from_bytes(&data).is_none());
+            assert!(GameStateMut::from_bytes(&mut data).is_none());
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub mod game_config;
 pub mod game_state;
 pub mod player_state;
 pub mod player_list;
+pub mod player_stats;
 
 pub use accumulator::*;
 pub use community_cards::*;
@@ -17,3 +18,4 @@ pub use game_config::*;
 pub use game_state::*;
 pub use player_state::*;
 pub use player_list::*;
+pub use player_stats::*;
@@ -8,27 +8,41 @@ use crate::constants::{MAX_PLAYERS, PLAYER_LIST_SEED};
 
 const MAX_PLAYERS_USIZE: usize = MAX_PLAYERS as usize;
 
+/// Pubkey stored in a seat nobody holds
+const EMPTY_SEAT: Pubkey = [0u8; 32];
+
+// One bit per seat in `revealed_bitmap`, `folded_bitmap` and `all_in_bitmap`
+const _: () = assert!(MAX_PLAYERS as u32 <= u8::BITS);
+
 /// Size of PlayerList account in bytes
-/// bump(1) + game_id(32) + count(1) + players(6*32) + revealed_bitmap(1) = 227 bytes
-pub const PLAYER_LIST_SIZE: usize = 1 + 32 + 1 + (MAX_PLAYERS_USIZE * 32) + 1;
+/// bump(1) + game_id(32) + count(1) + players(6*32) + revealed_bitmap(1)
+/// + folded_bitmap(1) + all_in_bitmap(1) = 229 bytes
+pub const PLAYER_LIST_SIZE: usize = 1 + 32 + 1 + (MAX_PLAYERS_USIZE * 32) + 1 + 1 + 1;
 
 /// Player list in seat order
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PlayerList {
     /// PDA bump seed
     pub bump: u8,
     /// Game ID reference
     pub game_id: [u8; 32],
-    /// Number of players
+    /// Number of occupied seats
     pub count: u8,
     /// Number of players (alias for count)
     pub player_count: u8,
-    /// Players in seat order
+    /// Players in seat order, zeroed for empty seats
     pub players: [Pubkey; MAX_PLAYERS_USIZE],
     /// Bitmap of players who have revealed for current card
-    /// Bit i is set if player at index i has revealed
+    /// Bit i corresponds to seat i and is only ever set for occupied seats
     pub revealed_bitmap: u8,
+    /// Bitmap of seats that have folded this hand
+    /// Mirrors each PlayerState's `is_folded`, so turn order can skip folded
+    /// seats without every PlayerState account
+    pub folded_bitmap: u8,
+    /// Bitmap of seats that have no chips left behind this hand
+    /// All-in seats stay in the hand but are skipped when betting
+    pub all_in_bitmap: u8,
 }
 
 impl PlayerList {
@@ -41,6 +55,8 @@ impl PlayerList {
             player_count: 0,
             players: [[0u8; 32]; MAX_PLAYERS_USIZE],
             revealed_bitmap: 0,
+            folded_bitmap: 0,
+            all_in_bitmap: 0,
         }
     }
 
@@ -49,24 +65,36 @@ impl PlayerList {
         find_program_address(&[PLAYER_LIST_SEED, game_id], program_id)
     }
 
-    /// Add a player to the list
+    /// Seat a player in the lowest empty seat
+    ///
+    /// Seats vacated by `leave` are handed out again, so the table never runs
+    /// out of seats while one is free.
     pub fn add_player(&mut self, player: Pubkey) -> Option<u8> {
-        if self.count >= MAX_PLAYERS {
-            return None;
-        }
-        let index = self.count;
+        let index = (0..MAX_PLAYERS).find(|&seat| !self.is_seat_occupied(seat))?;
         self.players[index as usize] = player;
-        self.count += 1;
+        self.count = self.occupied_count();
         self.player_count = self.count;
         Some(index)
     }
 
-    /// Get player at index
+    /// Number of seats that hold a player
+    pub fn occupied_count(&self) -> u8 {
+        (0..MAX_PLAYERS).filter(|&seat| self.is_seat_occupied(seat)).count() as u8
+    }
+
+    /// Get the player in seat `index`, `None` for an empty seat
+    ///
+    /// Seats keep their index when someone leaves, so this checks the seat
+    /// itself rather than bounding on `count`.
     pub fn get_player(&self, index: u8) -> Option<&Pubkey> {
-        if index >= self.count {
-            return None;
-        }
-        Some(&self.players[index as usize])
+        self.players
+            .get(index as usize)
+            .filter(|player| **player != EMPTY_SEAT)
+    }
+
+    /// Check whether a seat holds a player (seats vacated by `leave` are zeroed)
+    pub fn is_seat_occupied(&self, index: u8) -> bool {
+        self.get_player(index).is_some()
     }
 
     /// Find player by pubkey and return their pubkey
@@ -74,27 +102,24 @@ impl PlayerList {
         self.find_player_index(player)
     }
 
-    /// Find player index by pubkey
+    /// Find player index by pubkey, skipping empty seats
     pub fn find_player_index(&self, player: &Pubkey) -> Option<u8> {
-        for i in 0..self.count as usize {
-            if self.players[i] == *player {
-                return Some(i as u8);
-            }
-        }
-        None
+        (0..MAX_PLAYERS).find(|&seat| self.get_player(seat) == Some(player))
     }
 
-    /// Check if player has revealed for current card
+    /// Check if the player in seat `index` has revealed for current card
     pub fn has_revealed(&self, index: u8) -> bool {
-        if index >= MAX_PLAYERS {
+        if !self.is_seat_occupied(index) {
             return false;
         }
         (self.revealed_bitmap & (1 << index)) != 0
     }
 
-    /// Mark player as having revealed
+    /// Mark the player in seat `index` as having revealed
+    ///
+    /// Nobody sits in an empty seat, so marking one is a no-op.
     pub fn mark_revealed(&mut self, index: u8) {
-        if index < MAX_PLAYERS {
+        if self.is_seat_occupied(index) {
             self.revealed_bitmap |= 1 << index;
         }
     }
@@ -104,6 +129,47 @@ impl PlayerList {
         self.revealed_bitmap = 0;
     }
 
+    /// Check if the player in seat `index` has folded this hand
+    pub fn has_folded(&self, index: u8) -> bool {
+        self.is_seat_occupied(index) && (self.folded_bitmap & (1 << index)) != 0
+    }
+
+    /// Mark the player in seat `index` as folded (no-op for an empty seat)
+    pub fn mark_folded(&mut self, index: u8) {
+        if self.is_seat_occupied(index) {
+            self.folded_bitmap |= 1 << index;
+        }
+    }
+
+    /// Check if the player in seat `index` is all-in this hand
+    pub fn is_all_in(&self, index: u8) -> bool {
+        self.is_seat_occupied(index) && (self.all_in_bitmap & (1 << index)) != 0
+    }
+
+    /// Mark the player in seat `index` as all-in (no-op for an empty seat)
+    pub fn mark_all_in(&mut self, index: u8) {
+        if self.is_seat_occupied(index) {
+            self.all_in_bitmap |= 1 << index;
+        }
+    }
+
+    /// Whether the player in seat `index` can still bet: seated, not folded, not all-in
+    pub fn can_bet(&self, index: u8) -> bool {
+        self.is_seat_occupied(index) && !self.has_folded(index) && !self.is_all_in(index)
+    }
+
+    /// Number of seats that can still bet this hand
+    pub fn count_can_bet(&self) -> u8 {
+        (0..MAX_PLAYERS).filter(|&seat| self.can_bet(seat)).count() as u8
+    }
+
+    /// Reset state for next game (players keep their seats)
+    pub fn reset_for_next_game(&mut self) {
+        self.reset_revealed();
+        self.folded_bitmap = 0;
+        self.all_in_bitmap = 0;
+    }
+
     /// Count revealed players
     pub fn count_revealed(&self) -> u8 {
         self.revealed_bitmap.count_ones() as u8
@@ -129,6 +195,12 @@ impl PlayerList {
         }
 
         bytes[offset] = self.revealed_bitmap;
+        offset += 1;
+
+        bytes[offset] = self.folded_bitmap;
+        offset += 1;
+
+        bytes[offset] = self.all_in_bitmap;
 
         bytes
     }
@@ -158,6 +230,12 @@ impl PlayerList {
         }
 
         let revealed_bitmap = data[offset];
+        offset += 1;
+
+        let folded_bitmap = data[offset];
+        offset += 1;
+
+        let all_in_bitmap = data[offset];
 
         Some(Self {
             bump,
@@ -166,6 +244,170 @@ impl PlayerList {
             player_count: count,
             players,
             revealed_bitmap,
+            folded_bitmap,
+            all_in_bitmap,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        fn arb_player_list()(
+            bump in any::<u8>(),
+            game_id in any::<[u8; 32]>(),
+            count in 0..=MAX_PLAYERS,
+            players in any::<[Pubkey; MAX_PLAYERS_USIZE]>(),
+            revealed_bitmap in any::<u8>(),
+            folded_bitmap in any::<u8>(),
+            all_in_bitmap in any::<u8>(),
+        ) -> PlayerList {
+            PlayerList {
+                bump,
+                game_id,
+                count,
+                player_count: count,
+                players,
+                revealed_bitmap,
+                folded_bitmap,
+                all_in_bitmap,
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_seats_revealed_at_max_players() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..MAX_PLAYERS {
+            player_list.add_player([seat + 1; 32]);
+        }
+        for seat in 0..MAX_PLAYERS {
+            player_list.mark_revealed(seat);
+        }
+
+        assert_eq!(player_list.count_revealed(), MAX_PLAYERS);
+        assert!((0..MAX_PLAYERS).all(|seat| player_list.has_revealed(seat)));
+        let restored = PlayerList::from_bytes(&player_list.to_bytes()).unwrap();
+        assert_eq!(restored.count_revealed(), MAX_PLAYERS);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(player_list in arb_player_list()) {
+            prop_assert_eq!(PlayerList::from_bytes(&player_list.to_bytes()), Some(player_list));
+        }
+    }
+
+    #[test]
+    fn test_reset_for_next_game_clears_revealed_bitmap() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        player_list.add_player([1u8; 32]);
+        player_list.add_player([2u8; 32]);
+        player_list.mark_revealed(0);
+        player_list.mark_revealed(1);
+        assert_eq!(player_list.count_revealed(), 2);
+
+        player_list.reset_for_next_game();
+        let restored = PlayerList::from_bytes(&player_list.to_bytes()).unwrap();
+        assert_eq!(restored.revealed_bitmap, 0);
+        assert_eq!(restored.count, 2);
+        assert_eq!(restored.players[1], [2u8; 32]);
+    }
+
+    #[test]
+    fn test_folds_clear_with_the_hand_and_the_seat() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..3u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        player_list.mark_folded(1);
+        player_list.mark_folded(2);
+        player_list.mark_folded(4);
+        assert!(player_list.has_folded(1));
+        assert!(!player_list.has_folded(0));
+        assert!(!player_list.has_folded(4));
+
+        // A vacated seat is not folded for whoever takes it next
+        player_list.remove_player(2);
+        player_list.add_player([9u8; 32]);
+        assert!(!player_list.has_folded(2));
+
+        player_list.reset_for_next_game();
+        let restored = PlayerList::from_bytes(&player_list.to_bytes()).unwrap();
+        assert_eq!(restored.folded_bitmap, 0);
+    }
+
+    #[test]
+    fn test_mark_revealed_out_of_range_is_noop() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        player_list.add_player([1u8; 32]);
+        player_list.add_player([2u8; 32]);
+
+        player_list.mark_revealed(2);
+        player_list.mark_revealed(MAX_PLAYERS - 1);
+        player_list.mark_revealed(MAX_PLAYERS);
+        assert_eq!(player_list.revealed_bitmap, 0);
+        assert_eq!(player_list.count_revealed(), 0);
+        assert!(!player_list.has_revealed(2));
+
+        player_list.mark_revealed(1);
+        assert!(player_list.has_revealed(1));
+        assert_eq!(player_list.count_revealed(), 1);
+    }
+
+    #[test]
+    fn test_seats_stable_after_middle_removal() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..4u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        player_list.remove_player(1);
+
+        // The hole reads as empty instead of a zero pubkey
+        assert_eq!(player_list.get_player(1), None);
+        assert!(!player_list.is_seat_occupied(1));
+        assert_eq!(player_list.find_player_index(&[0u8; 32]), None);
+
+        // Everyone else keeps their seat
+        let seated: Vec<u8> = (0..MAX_PLAYERS).filter(|&seat| player_list.is_seat_occupied(seat)).collect();
+        assert_eq!(seated, [0, 2, 3]);
+        assert_eq!(player_list.find_player_index(&[3u8; 32]), Some(2));
+        assert_eq!(player_list.find_player_index(&[2u8; 32]), None);
+
+        // The vacated seat can't be marked as revealed
+        player_list.mark_revealed(1);
+        assert_eq!(player_list.count_revealed(), 0);
+
+        let restored = PlayerList::from_bytes(&player_list.to_bytes()).unwrap();
+        assert_eq!(restored.get_player(3), Some(&[4u8; 32]));
+        assert_eq!(restored.get_player(1), None);
+    }
+
+    #[test]
+    fn test_vacated_seat_is_reused() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..MAX_PLAYERS {
+            player_list.add_player([seat + 1; 32]);
+        }
+        assert_eq!(player_list.add_player([9u8; 32]), None);
+
+        player_list.remove_player(2);
+        assert_eq!(player_list.occupied_count(), MAX_PLAYERS - 1);
+        assert_eq!(player_list.count, MAX_PLAYERS - 1);
+
+        // The next player takes the free seat instead of being turned away
+        assert_eq!(player_list.add_player([9u8; 32]), Some(2));
+        assert_eq!(player_list.occupied_count(), MAX_PLAYERS);
+        assert_eq!(player_list.find_player_index(&[9u8; 32]), Some(2));
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let bytes = PlayerList::new(0, [0u8; 32]).to_bytes();
+        assert_eq!(bytes.len(), PLAYER_LIST_SIZE);
+        assert!(PlayerList::from_bytes(&bytes[..PLAYER_LIST_SIZE - 1]).is_none());
+    }
+}
@@ -4,19 +4,39 @@
 
 use pinocchio::pubkey::{find_program_address, Pubkey};
 
-use crate::constants::PLAYER_STATE_SEED;
+use crate::constants::{DECK_SIZE, PLAYER_STATE_SEED};
+use crate::crypto::bn254::{bn254_g1_decompress, COMPRESSED_G1_SIZE, G1_POINT_SIZE};
+use crate::error::PokerError;
 use crate::poker::HandEnum;
+use crate::state::accumulator::AccumulatorStateRef;
+use crate::state::game_state::{GameState, GameStateMut, SeatBetting};
+use crate::utils::read_u64;
 
 /// Size of PlayerState account in bytes
 /// bump(1) + game_id(32) + player(32) + seat_index(1) + chips(8) + current_bet(8) +
 /// commitment(32) + has_committed(1) + hole_cards(2) + hole_cards_count(1) +
-/// revealed_cards(128) + revealed_cards_count(1) + is_folded(1) + has_revealed_current(1) +
-/// submitted_hand(1) + hand_cards(5) + hand_rank(1) + shuffle_part1_done(1) + lock_part1_done(1) = 258 bytes
-pub const PLAYER_STATE_SIZE: usize = 258;
+/// revealed_cards(2*32) + revealed_cards_count(1) + is_folded(1) + has_revealed_current(1) +
+/// submitted_hand(1) + hand_cards(5) + hand_rank(1) + shuffle_part1_done(1) + lock_part1_done(1) +
+/// generate_progress(1) + has_generated(1) + has_submitted(1) + reveal_key_count(1) +
+/// reveal_keys(16*8) + lock_committed(1) = 327 bytes
+pub const PLAYER_STATE_SIZE: usize = 197 + 1 + REVEAL_KEY_LOG_CAPACITY * 8 + 1;
+
+/// Allocated size of a PlayerState account
+///
+/// The serialized PlayerState is followed by one compressed lock public key
+/// per deck card, read and written only through the zero-copy views so the
+/// owned struct stays small enough for the stack.
+pub const PLAYER_STATE_ACCOUNT_SIZE: usize = PLAYER_STATE_SIZE + DECK_SIZE * COMPRESSED_G1_SIZE;
+
+/// Number of reveal key fingerprints kept per hand
+///
+/// A player reveals at most the other players' hole cards plus the five
+/// community cards: 2 * (MAX_PLAYERS - 1) + 5 = 15.
+pub const REVEAL_KEY_LOG_CAPACITY: usize = 16;
 
 /// Per-player state account
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PlayerState {
     /// PDA bump seed
     pub bump: u8,
@@ -46,8 +66,11 @@ pub struct PlayerState {
     pub hole_cards_count: u8,
 
     // Revealed cards (EC points after decryption)
-    /// Revealed hole card points (2 x 64 bytes)
-    pub revealed_cards: [([u8; 32], [u8; 32]); 2],
+    /// Revealed hole card points, compressed (2 x 32 bytes)
+    ///
+    /// Only compared at hand submission, so they are decompressed lazily
+    /// via `revealed_card_point`.
+    pub revealed_cards: [[u8; COMPRESSED_G1_SIZE]; 2],
     /// Number of revealed cards
     pub revealed_cards_count: u8,
 
@@ -70,6 +93,25 @@ pub struct PlayerState {
     pub shuffle_part1_done: u8,
     /// Whether lock Part1 has been submitted (0 = no, 1 = yes)
     pub lock_part1_done: u8,
+    /// Next deck index to derive for chunked Generate (0 = not started)
+    pub generate_progress: u8,
+    /// Whether the player has contributed to the accumulator this hand
+    pub has_generated: u8,
+    /// Whether the player has submitted their best hand this hand
+    pub has_submitted: u8,
+
+    // Replay protection for reveals
+    /// Number of reveal keys used this hand
+    pub reveal_key_count: u8,
+    /// Fingerprints of the reveal keys used this hand
+    pub reveal_keys: [u64; REVEAL_KEY_LOG_CAPACITY],
+
+    // Lock commitment
+    /// Whether `LockPart1` has fixed the committed per-card lock public keys
+    ///
+    /// Reveals verify each inverse key against those keys, and
+    /// `CommitLockKeys` can no longer change them once this is set.
+    pub lock_committed: u8, // bool as u8
 }
 
 impl PlayerState {
@@ -93,7 +135,7 @@ impl PlayerState {
             has_committed: 1,
             hole_cards: [255, 255],
             hole_cards_count: 0,
-            revealed_cards: [([0u8; 32], [0u8; 32]); 2],
+            revealed_cards: [[0u8; COMPRESSED_G1_SIZE]; 2],
             revealed_cards_count: 0,
             is_folded: 0,
             has_revealed_current: 0,
@@ -102,6 +144,12 @@ impl PlayerState {
             hand_rank: 0,
             shuffle_part1_done: 0,
             lock_part1_done: 0,
+            generate_progress: 0,
+            has_generated: 0,
+            has_submitted: 0,
+            reveal_key_count: 0,
+            reveal_keys: [0; REVEAL_KEY_LOG_CAPACITY],
+            lock_committed: 0,
         }
     }
 
@@ -122,6 +170,46 @@ impl PlayerState {
         self.has_revealed_current != 0
     }
 
+    pub fn has_generated(&self) -> bool {
+        self.has_generated != 0
+    }
+
+    pub fn has_submitted(&self) -> bool {
+        self.has_submitted != 0
+    }
+
+    /// Chips still needed to match the table's current call amount
+    pub fn amount_to_call(&self, game_state: &GameState) -> u64 {
+        game_state.current_call_amount.saturating_sub(self.current_bet)
+    }
+
+    /// Decompress a revealed hole card point (index 0 or 1)
+    ///
+    /// Returns `None` if the card hasn't been revealed or the stored bytes
+    /// aren't a valid compressed point.
+    pub fn revealed_card_point(&self, index: usize) -> Option<[u8; G1_POINT_SIZE]> {
+        if index >= self.revealed_cards_count.min(2) as usize {
+            return None;
+        }
+        bn254_g1_decompress(&self.revealed_cards[index]).ok()
+    }
+
+    /// Card IDs of the revealed hole cards, via the accumulator's deck mapping
+    ///
+    /// A card that isn't revealed yet or doesn't map to a deck point is -1.
+    pub fn hole_card_ids(&self, acc: &AccumulatorStateRef) -> [i8; 2] {
+        let mut ids = [-1i8; 2];
+        for (index, id) in ids.iter_mut().enumerate() {
+            if let Some(point) = self.revealed_card_point(index) {
+                let (qx, qy) = point.split_at(32);
+                *id = acc
+                    .find_card_by_point(qx.try_into().unwrap(), qy.try_into().unwrap())
+                    .unwrap_or(-1);
+            }
+        }
+        ids
+    }
+
     /// Reset state for next game
     pub fn reset_for_next_game(&mut self) {
         self.current_bet = 0;
@@ -129,7 +217,7 @@ impl PlayerState {
         self.has_committed = 0;
         self.hole_cards = [255, 255];
         self.hole_cards_count = 0;
-        self.revealed_cards = [([0u8; 32], [0u8; 32]); 2];
+        self.revealed_cards = [[0u8; COMPRESSED_G1_SIZE]; 2];
         self.revealed_cards_count = 0;
         self.is_folded = 0;
         self.has_revealed_current = 0;
@@ -138,6 +226,12 @@ impl PlayerState {
         self.hand_rank = 0;
         self.shuffle_part1_done = 0;
         self.lock_part1_done = 0;
+        self.generate_progress = 0;
+        self.has_generated = 0;
+        self.has_submitted = 0;
+        self.reveal_key_count = 0;
+        self.reveal_keys = [0; REVEAL_KEY_LOG_CAPACITY];
+        self.lock_committed = 0;
     }
 
     /// Serialize to bytes
@@ -175,11 +269,9 @@ impl PlayerState {
         bytes[offset] = self.hole_cards_count;
         offset += 1;
 
-        for (x, y) in &self.revealed_cards {
-            bytes[offset..offset + 32].copy_from_slice(x);
-            offset += 32;
-            bytes[offset..offset + 32].copy_from_slice(y);
-            offset += 32;
+        for card in &self.revealed_cards {
+            bytes[offset..offset + COMPRESSED_G1_SIZE].copy_from_slice(card);
+            offset += COMPRESSED_G1_SIZE;
         }
 
         bytes[offset] = self.revealed_cards_count;
@@ -206,6 +298,26 @@ impl PlayerState {
         offset += 1;
 
         bytes[offset] = self.lock_part1_done;
+        offset += 1;
+
+        bytes[offset] = self.generate_progress;
+        offset += 1;
+
+        bytes[offset] = self.has_generated;
+        offset += 1;
+
+        bytes[offset] = self.has_submitted;
+        offset += 1;
+
+        bytes[offset] = self.reveal_key_count;
+        offset += 1;
+
+        for key in &self.reveal_keys {
+            bytes[offset..offset + 8].copy_from_slice(&key.to_le_bytes());
+            offset += 8;
+        }
+
+        bytes[offset] = self.lock_committed;
 
         bytes
     }
@@ -252,12 +364,10 @@ impl PlayerState {
         let hole_cards_count = data[offset];
         offset += 1;
 
-        let mut revealed_cards = [([0u8; 32], [0u8; 32]); 2];
-        for (x, y) in &mut revealed_cards {
-            x.copy_from_slice(&data[offset..offset + 32]);
-            offset += 32;
-            y.copy_from_slice(&data[offset..offset + 32]);
-            offset += 32;
+        let mut revealed_cards = [[0u8; COMPRESSED_G1_SIZE]; 2];
+        for card in &mut revealed_cards {
+            card.copy_from_slice(&data[offset..offset + COMPRESSED_G1_SIZE]);
+            offset += COMPRESSED_G1_SIZE;
         }
 
         let revealed_cards_count = data[offset];
@@ -285,6 +395,27 @@ impl PlayerState {
         offset += 1;
 
         let lock_part1_done = data[offset];
+        offset += 1;
+
+        let generate_progress = data[offset];
+        offset += 1;
+
+        let has_generated = data[offset];
+        offset += 1;
+
+        let has_submitted = data[offset];
+        offset += 1;
+
+        let reveal_key_count = data[offset];
+        offset += 1;
+
+        let mut reveal_keys = [0u64; REVEAL_KEY_LOG_CAPACITY];
+        for key in reveal_keys.iter_mut() {
+            *key = u64::from_le_bytes(data[offset..offset + 8].try_into().ok()?);
+            offset += 8;
+        }
+
+        let lock_committed = data[offset];
 
         Some(Self {
             bump,
@@ -306,6 +437,532 @@ impl PlayerState {
             hand_rank,
             shuffle_part1_done,
             lock_part1_done,
+            generate_progress,
+            has_generated,
+            has_submitted,
+            reveal_key_count,
+            reveal_keys,
+            lock_committed,
+        })
+    }
+}
+
+// =============================================================================
+// Zero-Copy Reference Types (Stack-Efficient)
+// =============================================================================
+
+// Layout offsets for zero-copy access (must match to_bytes/from_bytes)
+const GAME_ID_OFFSET: usize = 1;
+const PLAYER_OFFSET: usize = 33;
+const SEAT_INDEX_OFFSET: usize = 65;
+const CHIPS_OFFSET: usize = 66;
+const CURRENT_BET_OFFSET: usize = 74;
+const HOLE_CARDS_OFFSET: usize = 115;
+const REVEALED_CARDS_OFFSET: usize = 118;
+const REVEALED_CARD_SIZE: usize = COMPRESSED_G1_SIZE;
+const IS_FOLDED_OFFSET: usize = 183;
+const REVEAL_KEY_COUNT_OFFSET: usize = 197;
+const REVEAL_KEYS_OFFSET: usize = 198;
+const LOCK_COMMITTED_OFFSET: usize = REVEAL_KEYS_OFFSET + REVEAL_KEY_LOG_CAPACITY * 8;
+
+// The last field must end exactly at the account size
+const _: () = assert!(LOCK_COMMITTED_OFFSET + 1 == PLAYER_STATE_SIZE);
+
+// Lock public keys live past the serialized state
+const LOCK_KEYS_OFFSET: usize = PLAYER_STATE_SIZE;
+
+/// Zero-copy immutable view into PlayerState account data.
+/// Stack cost: ~16 bytes (just the slice reference)
+#[derive(Clone, Copy)]
+pub struct PlayerStateRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> PlayerStateRef<'a> {
+    /// Create a zero-copy reference from account data bytes
+    #[inline]
+    pub fn from_bytes(data: &'a [u8]) -> Option<Self> {
+        if data.len() < PLAYER_STATE_SIZE {
+            return None;
+        }
+        Some(Self { data })
+    }
+
+    /// Get the game ID
+    #[inline]
+    pub fn game_id(&self) -> &[u8; 32] {
+        unsafe { &*(self.data[GAME_ID_OFFSET..].as_ptr() as *const [u8; 32]) }
+    }
+
+    /// Get the player's public key
+    #[inline]
+    pub fn player(&self) -> &Pubkey {
+        unsafe { &*(self.data[PLAYER_OFFSET..].as_ptr() as *const Pubkey) }
+    }
+
+    /// Get the seat index
+    #[inline]
+    pub fn seat_index(&self) -> u8 {
+        self.data[SEAT_INDEX_OFFSET]
+    }
+
+    /// Get the chip count
+    #[inline]
+    pub fn chips(&self) -> u64 {
+        read_u64(self.data, CHIPS_OFFSET)
+    }
+
+    /// Get the current bet in this round
+    #[inline]
+    pub fn current_bet(&self) -> u64 {
+        read_u64(self.data, CURRENT_BET_OFFSET)
+    }
+
+    /// Check whether the player has folded
+    #[inline]
+    pub fn is_folded(&self) -> bool {
+        self.data[IS_FOLDED_OFFSET] != 0
+    }
+
+    /// Betting status used to detect an all-in close of the round
+    #[inline]
+    pub fn seat_betting(&self) -> SeatBetting {
+        SeatBetting {
+            is_folded: self.is_folded(),
+            chips: self.chips(),
+            current_bet: self.current_bet(),
+        }
+    }
+
+    /// Get the hole card indices
+    #[inline]
+    pub fn hole_cards(&self) -> [u8; 2] {
+        [self.data[HOLE_CARDS_OFFSET], self.data[HOLE_CARDS_OFFSET + 1]]
+    }
+
+    /// Get a revealed hole card's compressed point (index 0 or 1)
+    #[inline]
+    pub fn revealed_card(&self, index: usize) -> &[u8; COMPRESSED_G1_SIZE] {
+        revealed_card_at(self.data, index)
+    }
+
+    /// Committed lock public key for a deck card
+    #[inline]
+    pub fn lock_public_key(&self, index: usize) -> Option<&[u8; COMPRESSED_G1_SIZE]> {
+        lock_public_key_at(self.data, index)
+    }
+
+    /// Whether each of the first `deck_size` cards has a valid lock public key
+    ///
+    /// A short deck only uses its first 36 card indices.
+    pub fn has_lock_public_keys(&self, deck_size: usize) -> bool {
+        (0..deck_size).all(|index| {
+            self.lock_public_key(index)
+                .is_some_and(|key| bn254_g1_decompress(key).is_ok())
         })
     }
 }
+
+/// Zero-copy mutable view into PlayerState account data.
+/// Stack cost: ~16 bytes (just the slice reference)
+pub struct PlayerStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> PlayerStateMut<'a> {
+    /// Create a zero-copy mutable reference from account data bytes
+    #[inline]
+    pub fn from_bytes(data: &'a mut [u8]) -> Option<Self> {
+        if data.len() < PLAYER_STATE_SIZE {
+            return None;
+        }
+        Some(Self { data })
+    }
+
+    /// Get the game ID
+    #[inline]
+    pub fn game_id(&self) -> &[u8; 32] {
+        unsafe { &*(self.data[GAME_ID_OFFSET..].as_ptr() as *const [u8; 32]) }
+    }
+
+    /// Get the player's public key
+    #[inline]
+    pub fn player(&self) -> &Pubkey {
+        unsafe { &*(self.data[PLAYER_OFFSET..].as_ptr() as *const Pubkey) }
+    }
+
+    /// Get the seat index
+    #[inline]
+    pub fn seat_index(&self) -> u8 {
+        self.data[SEAT_INDEX_OFFSET]
+    }
+
+    /// Get the chip count
+    #[inline]
+    pub fn chips(&self) -> u64 {
+        read_u64(self.data, CHIPS_OFFSET)
+    }
+
+    /// Set the chip count
+    #[inline]
+    pub fn set_chips(&mut self, chips: u64) {
+        self.data[CHIPS_OFFSET..CHIPS_OFFSET + 8].copy_from_slice(&chips.to_le_bytes());
+    }
+
+    /// Get the current bet in this round
+    #[inline]
+    pub fn current_bet(&self) -> u64 {
+        read_u64(self.data, CURRENT_BET_OFFSET)
+    }
+
+    /// Set the current bet in this round
+    #[inline]
+    pub fn set_current_bet(&mut self, bet: u64) {
+        self.data[CURRENT_BET_OFFSET..CURRENT_BET_OFFSET + 8].copy_from_slice(&bet.to_le_bytes());
+    }
+
+    /// Chips still needed to match the table's current call amount
+    #[inline]
+    pub fn amount_to_call(&self, game_state: &GameStateMut) -> u64 {
+        game_state.current_call_amount().saturating_sub(self.current_bet())
+    }
+
+    /// Check whether the player has folded
+    #[inline]
+    pub fn is_folded(&self) -> bool {
+        self.data[IS_FOLDED_OFFSET] != 0
+    }
+
+    /// Set the folded flag
+    #[inline]
+    pub fn set_folded(&mut self, folded: bool) {
+        self.data[IS_FOLDED_OFFSET] = folded as u8;
+    }
+
+    /// Get the hole card indices
+    #[inline]
+    pub fn hole_cards(&self) -> [u8; 2] {
+        [self.data[HOLE_CARDS_OFFSET], self.data[HOLE_CARDS_OFFSET + 1]]
+    }
+
+    /// Set the hole card indices
+    #[inline]
+    pub fn set_hole_cards(&mut self, cards: [u8; 2]) {
+        self.data[HOLE_CARDS_OFFSET..HOLE_CARDS_OFFSET + 2].copy_from_slice(&cards);
+    }
+
+    /// Get a revealed hole card's compressed point (index 0 or 1)
+    #[inline]
+    pub fn revealed_card(&self, index: usize) -> &[u8; COMPRESSED_G1_SIZE] {
+        revealed_card_at(self.data, index)
+    }
+
+    /// Set a revealed hole card's compressed point (index 0 or 1)
+    #[inline]
+    pub fn set_revealed_card(&mut self, index: usize, card: &[u8; COMPRESSED_G1_SIZE]) {
+        let offset = REVEALED_CARDS_OFFSET + index * REVEALED_CARD_SIZE;
+        self.data[offset..offset + REVEALED_CARD_SIZE].copy_from_slice(card);
+    }
+
+    /// Committed lock public key for a deck card
+    ///
+    /// `None` if the index is off the deck, no key has been committed for
+    /// the card, or the account predates the lock key area.
+    #[inline]
+    pub fn lock_public_key(&self, index: usize) -> Option<&[u8; COMPRESSED_G1_SIZE]> {
+        lock_public_key_at(self.data, index)
+    }
+
+    /// Forget the lock public keys committed for the last hand
+    pub fn clear_lock_public_keys(&mut self) {
+        let end = LOCK_KEYS_OFFSET + DECK_SIZE * COMPRESSED_G1_SIZE;
+        if let Some(keys) = self.data.get_mut(LOCK_KEYS_OFFSET..end) {
+            keys.fill(0);
+        }
+    }
+
+    /// Whether `LockPart1` has fixed the lock public keys for this hand
+    #[inline]
+    pub fn is_lock_committed(&self) -> bool {
+        self.data[LOCK_COMMITTED_OFFSET] != 0
+    }
+
+    /// Store lock public keys for consecutive deck cards from `first`
+    pub fn set_lock_public_keys(
+        &mut self,
+        first: usize,
+        keys: &[[u8; COMPRESSED_G1_SIZE]],
+    ) -> Result<(), PokerError> {
+        if first + keys.len() > DECK_SIZE {
+            return Err(PokerError::InvalidCardIndex);
+        }
+        let start = LOCK_KEYS_OFFSET + first * COMPRESSED_G1_SIZE;
+        let area = self
+            .data
+            .get_mut(start..start + keys.len() * COMPRESSED_G1_SIZE)
+            .ok_or(PokerError::InvalidAccountData)?;
+        area.copy_from_slice(keys.as_flattened());
+        Ok(())
+    }
+
+    /// Remember a reveal key, rejecting one already used this hand
+    ///
+    /// Each card is locked with its own key, so the same inverse key showing
+    /// up for a second card is a replay.
+    pub fn record_reveal_key(&mut self, inv_key: &[u8; 32]) -> Result<(), PokerError> {
+        let fingerprint = reveal_key_fingerprint(inv_key);
+        let count = self.data[REVEAL_KEY_COUNT_OFFSET] as usize;
+        let used = (0..count.min(REVEAL_KEY_LOG_CAPACITY))
+            .any(|i| read_u64(self.data, REVEAL_KEYS_OFFSET + i * 8) == fingerprint);
+        if used {
+            return Err(PokerError::RevealKeyReused);
+        }
+        if count >= REVEAL_KEY_LOG_CAPACITY {
+            return Err(PokerError::InvalidState);
+        }
+        let offset = REVEAL_KEYS_OFFSET + count * 8;
+        self.data[offset..offset + 8].copy_from_slice(&fingerprint.to_le_bytes());
+        self.data[REVEAL_KEY_COUNT_OFFSET] = count as u8 + 1;
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a fingerprint of a reveal key
+fn reveal_key_fingerprint(key: &[u8; 32]) -> u64 {
+    key.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[inline]
+fn lock_public_key_at(data: &[u8], index: usize) -> Option<&[u8; COMPRESSED_G1_SIZE]> {
+    if index >= DECK_SIZE {
+        return None;
+    }
+    let offset = LOCK_KEYS_OFFSET + index * COMPRESSED_G1_SIZE;
+    let key: &[u8; COMPRESSED_G1_SIZE] = data.get(offset..offset + COMPRESSED_G1_SIZE)?.try_into().ok()?;
+    key.iter().any(|&byte| byte != 0).then_some(key)
+}
+
+#[inline]
+fn revealed_card_at(data: &[u8], index: usize) -> &[u8; COMPRESSED_G1_SIZE] {
+    let offset = REVEALED_CARDS_OFFSET + index * REVEALED_CARD_SIZE;
+    unsafe { &*(data[offset..].as_ptr() as *const [u8; COMPRESSED_G1_SIZE]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        fn arb_player_state()(
+            keys in any::<(u8, [u8; 32], Pubkey, u8, [u8; 32])>(),
+            chips in any::<(u64, u64)>(),
+            cards in any::<([u8; 2], u8, [[u8; 32]; 2], u8)>(),
+            flags in any::<[u8; 10]>(),
+            hand_cards in any::<[i8; 5]>(),
+            reveal_keys in any::<(u8, [u64; REVEAL_KEY_LOG_CAPACITY])>(),
+            lock_committed in any::<u8>(),
+        ) -> PlayerState {
+            let mut player_state =
+                PlayerState::new(keys.0, keys.1, keys.2, keys.3, chips.0, keys.4);
+            player_state.current_bet = chips.1;
+            player_state.hole_cards = cards.0;
+            player_state.hole_cards_count = cards.1;
+            player_state.revealed_cards = cards.2;
+            player_state.revealed_cards_count = cards.3;
+            player_state.has_committed = flags[0];
+            player_state.is_folded = flags[1];
+            player_state.has_revealed_current = flags[2];
+            player_state.submitted_hand = flags[3];
+            player_state.hand_rank = flags[4];
+            player_state.shuffle_part1_done = flags[5];
+            player_state.lock_part1_done = flags[6];
+            player_state.generate_progress = flags[7];
+            player_state.has_generated = flags[8];
+            player_state.has_submitted = flags[9];
+            player_state.hand_cards = hand_cards;
+            player_state.reveal_key_count = reveal_keys.0;
+            player_state.reveal_keys = reveal_keys.1;
+            player_state.lock_committed = lock_committed;
+            player_state
+        }
+    }
+
+    #[test]
+    fn test_amount_to_call() {
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.current_call_amount = 100;
+        let mut player_state = PlayerState::new(0, [0u8; 32], Pubkey::default(), 0, 1000, [0u8; 32]);
+
+        // Behind the bet
+        player_state.current_bet = 40;
+        assert_eq!(player_state.amount_to_call(&game_state), 60);
+        // Already matched
+        player_state.current_bet = 100;
+        assert_eq!(player_state.amount_to_call(&game_state), 0);
+        // Never negative
+        player_state.current_bet = 150;
+        assert_eq!(player_state.amount_to_call(&game_state), 0);
+
+        // The zero-copy view agrees
+        player_state.current_bet = 40;
+        let mut game_bytes = game_state.to_bytes();
+        let game_view = GameStateMut::from_bytes(&mut game_bytes).unwrap();
+        let mut player_bytes = player_state.to_bytes();
+        let player_view = PlayerStateMut::from_bytes(&mut player_bytes).unwrap();
+        assert_eq!(player_view.amount_to_call(&game_view), 60);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(player_state in arb_player_state()) {
+            prop_assert_eq!(PlayerState::from_bytes(&player_state.to_bytes()), Some(player_state));
+        }
+    }
+
+    #[test]
+    fn test_revealed_card_compressed_round_trip() {
+        use crate::crypto::bn254::{bn254_g1_compress, bn254_mul};
+
+        let mut generator = [0u8; G1_POINT_SIZE];
+        generator[31] = 1;
+        generator[63] = 2;
+        let mut scalar = [0u8; 32];
+        scalar[31] = 7;
+        let point = bn254_mul(&generator, &scalar).unwrap();
+
+        let mut player_state = sample_player_state();
+        player_state.revealed_cards[0] = bn254_g1_compress(&point).unwrap();
+        player_state.revealed_cards_count = 1;
+
+        let restored = PlayerState::from_bytes(&player_state.to_bytes()).unwrap();
+        assert_eq!(restored.revealed_card_point(0), Some(point));
+        // Not revealed yet
+        assert_eq!(restored.revealed_card_point(1), None);
+    }
+
+    #[test]
+    fn test_hole_card_ids_from_revealed_points() {
+        use crate::crypto::bn254::{bn254_g1_compress, bn254_mul};
+        use crate::state::accumulator::{AccumulatorStateMut, ACCUMULATOR_STATE_SIZE};
+
+        let mut generator = [0u8; G1_POINT_SIZE];
+        generator[31] = 1;
+        generator[63] = 2;
+        let card_point = |seed: u8| {
+            let mut scalar = [0u8; 32];
+            scalar[31] = seed;
+            bn254_mul(&generator, &scalar).unwrap()
+        };
+
+        // Deck entries 12 and 40 hold the two points the player revealed
+        let (first, second) = (card_point(3), card_point(9));
+        let mut data = vec![0u8; ACCUMULATOR_STATE_SIZE];
+        {
+            let mut acc = AccumulatorStateMut::from_bytes(&mut data).unwrap();
+            acc.set_deck_mapping(12, first[..32].try_into().unwrap(), first[32..].try_into().unwrap());
+            acc.set_deck_mapping(40, second[..32].try_into().unwrap(), second[32..].try_into().unwrap());
+        }
+        let acc = AccumulatorStateRef::from_bytes(&data).unwrap();
+
+        let mut player_state = sample_player_state();
+        assert_eq!(player_state.hole_card_ids(&acc), [-1, -1]);
+
+        player_state.revealed_cards = [
+            bn254_g1_compress(&second).unwrap(),
+            bn254_g1_compress(&first).unwrap(),
+        ];
+        player_state.revealed_cards_count = 1;
+        assert_eq!(player_state.hole_card_ids(&acc), [40, -1]);
+
+        player_state.revealed_cards_count = 2;
+        assert_eq!(player_state.hole_card_ids(&acc), [40, 12]);
+    }
+
+    fn sample_player_state() -> PlayerState {
+        let mut player_state = PlayerState::new(1, [2u8; 32], [3u8; 32], 4, 1_000, [5u8; 32]);
+        player_state.current_bet = 50;
+        player_state.hole_cards = [7, 19];
+        player_state.revealed_cards[1] = [8u8; 32];
+        player_state.is_folded = 1;
+        player_state
+    }
+
+    #[test]
+    fn test_zero_copy_matches_owned() {
+        let player_state = sample_player_state();
+        let bytes = player_state.to_bytes();
+        let view = PlayerStateRef::from_bytes(&bytes).unwrap();
+
+        assert_eq!(view.player(), &player_state.player);
+        assert_eq!(view.seat_index(), 4);
+        assert_eq!(view.chips(), 1_000);
+        assert_eq!(view.current_bet(), 50);
+        assert!(view.is_folded());
+        assert_eq!(view.hole_cards(), [7, 19]);
+        assert_eq!(view.revealed_card(0), &[0u8; 32]);
+        assert_eq!(view.revealed_card(1), &[8u8; 32]);
+    }
+
+    #[test]
+    fn test_zero_copy_writes_round_trip() {
+        let mut player_state = sample_player_state();
+        let mut bytes = player_state.to_bytes();
+        {
+            let mut view = PlayerStateMut::from_bytes(&mut bytes).unwrap();
+            view.set_chips(900);
+            view.set_current_bet(150);
+            view.set_folded(false);
+            view.set_hole_cards([1, 2]);
+            view.set_revealed_card(0, &[10u8; 32]);
+        }
+
+        player_state.chips = 900;
+        player_state.current_bet = 150;
+        player_state.is_folded = 0;
+        player_state.hole_cards = [1, 2];
+        player_state.revealed_cards[0] = [10u8; 32];
+        assert_eq!(bytes, player_state.to_bytes());
+
+        let restored = PlayerState::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.chips, 900);
+        assert!(!restored.is_folded());
+    }
+
+    #[test]
+    fn test_replayed_reveal_key_rejected() {
+        let mut bytes = sample_player_state().to_bytes();
+        let mut view = PlayerStateMut::from_bytes(&mut bytes).unwrap();
+
+        assert_eq!(view.record_reveal_key(&[1u8; 32]), Ok(()));
+        assert_eq!(view.record_reveal_key(&[2u8; 32]), Ok(()));
+        // Same key submitted for another card
+        assert_eq!(view.record_reveal_key(&[1u8; 32]), Err(PokerError::RevealKeyReused));
+
+        let mut restored = PlayerState::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.reveal_key_count, 2);
+
+        // A new hand starts with a clean log
+        restored.reset_for_next_game();
+        let mut bytes = restored.to_bytes();
+        let mut view = PlayerStateMut::from_bytes(&mut bytes).unwrap();
+        assert_eq!(view.record_reveal_key(&[1u8; 32]), Ok(()));
+    }
+
+    #[test]
+    fn test_zero_copy_rejects_short_data() {
+        assert!(PlayerStateRef::from_bytes(&[0u8; PLAYER_STATE_SIZE - 1]).is_none());
+    }
+
+    #[test]
+    fn test_layout_size() {
+        let player_state = PlayerState::new(0, [0u8; 32], [1u8; 32], 0, 1000, [0u8; 32]);
+        let mut data = player_state.to_bytes().to_vec();
+        assert_eq!(data.len(), PLAYER_STATE_SIZE);
+        assert!(PlayerState::from_bytes(&data[..PLAYER_STATE_SIZE - 1]).is_none());
+        assert!(PlayerStateRef::from_bytes(&data[..PLAYER_STATE_SIZE - 1]).is_none());
+        assert!(PlayerStateMut::from_bytes(&mut data[..PLAYER_STATE_SIZE - 1]).is_none());
+    }
+}
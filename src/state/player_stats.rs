@@ -0,0 +1,124 @@
+//! PlayerStats account - cumulative per-player results at a table
+//!
+//! Seeds: ["stats", game_id, player]
+//!
+//! Only kept on tables initialized with stats enabled. Each player opens
+//! theirs with `InitPlayerStats`; settling a pot then requires every seat's
+//! account and records the hand from the seats' PlayerStates, so results
+//! can't be left out by not passing an account.
+
+use pinocchio::pubkey::{find_program_address, Pubkey};
+
+use crate::constants::PLAYER_STATS_SEED;
+
+/// Size of PlayerStats account in bytes
+/// bump(1) + game_id(32) + player(32) + hands_played(4) + hands_won(4) + net_chips(8) = 81 bytes
+pub const PLAYER_STATS_SIZE: usize = 1 + 32 + 32 + 4 + 4 + 8;
+
+/// Cumulative results for one player at one table
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerStats {
+    /// PDA bump seed
+    pub bump: u8,
+    /// Game ID reference
+    pub game_id: [u8; 32],
+    /// Player's public key
+    pub player: Pubkey,
+    /// Hands the player was dealt into and finished (settled, or left after folding)
+    pub hands_played: u32,
+    /// Hands the player won a share of at ClaimPot
+    pub hands_won: u32,
+    /// Chips won from pots minus chips put into them
+    pub net_chips: i64,
+}
+
+impl PlayerStats {
+    /// Create a new PlayerStats with no hands recorded
+    pub fn new(bump: u8, game_id: [u8; 32], player: Pubkey) -> Self {
+        Self {
+            bump,
+            game_id,
+            player,
+            hands_played: 0,
+            hands_won: 0,
+            net_chips: 0,
+        }
+    }
+
+    /// Derive PDA for PlayerStats
+    pub fn derive_pda(game_id: &[u8; 32], player: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        find_program_address(&[PLAYER_STATS_SEED, game_id, player], program_id)
+    }
+
+    /// Count one finished hand: the chips put in, and what came back
+    pub fn record_hand(&mut self, contribution: u64, payout: u64, won: bool) {
+        self.hands_played = self.hands_played.saturating_add(1);
+        if won {
+            self.hands_won = self.hands_won.saturating_add(1);
+        }
+        self.net_chips = self
+            .net_chips
+            .saturating_sub_unsigned(contribution)
+            .saturating_add_unsigned(payout);
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> [u8; PLAYER_STATS_SIZE] {
+        let mut bytes = [0u8; PLAYER_STATS_SIZE];
+        bytes[0] = self.bump;
+        bytes[1..33].copy_from_slice(&self.game_id);
+        bytes[33..65].copy_from_slice(&self.player);
+        bytes[65..69].copy_from_slice(&self.hands_played.to_le_bytes());
+        bytes[69..73].copy_from_slice(&self.hands_won.to_le_bytes());
+        bytes[73..81].copy_from_slice(&self.net_chips.to_le_bytes());
+        bytes
+    }
+
+    /// Deserialize from bytes
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < PLAYER_STATS_SIZE {
+            return None;
+        }
+        Some(Self {
+            bump: data[0],
+            game_id: data[1..33].try_into().ok()?,
+            player: data[33..65].try_into().ok()?,
+            hands_played: u32::from_le_bytes(data[65..69].try_into().ok()?),
+            hands_won: u32::from_le_bytes(data[69..73].try_into().ok()?),
+            net_chips: i64::from_le_bytes(data[73..81].try_into().ok()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(
+            keys in any::<(u8, [u8; 32], Pubkey)>(),
+            counts in any::<(u32, u32, i64)>(),
+        ) {
+            let mut stats = PlayerStats::new(keys.0, keys.1, keys.2);
+            (stats.hands_played, stats.hands_won, stats.net_chips) = counts;
+            prop_assert_eq!(PlayerStats::from_bytes(&stats.to_bytes()), Some(stats));
+        }
+    }
+
+    #[test]
+    fn test_record_hand() {
+        let mut stats = PlayerStats::new(0, [0u8; 32], [1u8; 32]);
+        stats.record_hand(40, 0, false);
+        assert_eq!((stats.hands_played, stats.hands_won, stats.net_chips), (1, 0, -40));
+
+        // Everyone folded and the pot came back: played, not won
+        stats.record_hand(40, 40, false);
+        assert_eq!((stats.hands_played, stats.hands_won, stats.net_chips), (2, 0, -40));
+
+        stats.record_hand(50, 100, true);
+        assert_eq!((stats.hands_played, stats.hands_won, stats.net_chips), (3, 1, 10));
+    }
+}
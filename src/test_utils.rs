@@ -0,0 +1,100 @@
+//! Builders for serialized account data used in unit tests
+//!
+//! Handlers operate on raw account bytes, so tests need valid buffers for
+//! each state type. These helpers produce them from a few key fields.
+
+use pinocchio::pubkey::Pubkey;
+
+use crate::{constants::MAX_PLAYERS, state::*};
+
+/// Game ID shared by all builders
+pub const TEST_GAME_ID: [u8; 32] = [7u8; 32];
+
+/// Serialized `GameConfig` for a table with `max_players` seats
+pub fn make_game_config(max_players: u8, small_blind: u64) -> [u8; GAME_CONFIG_SIZE] {
+    let mut game_config = GameConfig::new(
+        0,
+        TEST_GAME_ID,
+        [0u8; 32],
+        [0u8; 32],
+        max_players,
+        small_blind,
+        small_blind * 10,
+        0,
+    );
+    game_config.current_players = max_players;
+    game_config.to_bytes()
+}
+
+/// Serialized `GameState` in the given Texas state with turn and pot set
+pub fn make_game_state(phase: TexasHoldEmState, turn: u8, pot: u64) -> [u8; GAME_STATE_SIZE] {
+    let mut game_state = GameState::new(0, TEST_GAME_ID, 0);
+    game_state.game_phase = GamePhase::Drawing as u8;
+    game_state.texas_state = phase as u8;
+    game_state.current_turn = turn;
+    game_state.pot = pot;
+    game_state.to_bytes()
+}
+
+/// Serialized `PlayerList` seating `players` in order
+pub fn make_player_list(players: &[Pubkey]) -> [u8; PLAYER_LIST_SIZE] {
+    assert!(players.len() <= MAX_PLAYERS as usize);
+    let mut player_list = PlayerList::new(0, TEST_GAME_ID);
+    for player in players {
+        player_list.add_player(*player);
+    }
+    player_list.to_bytes()
+}
+
+/// `PlayerList` with seats `0..count` held by players `[seat + 1; 32]`
+pub fn seated_player_list(count: u8) -> PlayerList {
+    let players: Vec<Pubkey> = (0..count).map(|seat| [seat + 1; 32]).collect();
+    PlayerList::from_bytes(&make_player_list(&players)).unwrap()
+}
+
+/// Serialized `PlayerState` for `player` at `seat` holding `chips`
+pub fn make_player_state(player: Pubkey, seat: u8, chips: u64) -> [u8; PLAYER_STATE_SIZE] {
+    PlayerState::new(0, TEST_GAME_ID, player, seat, chips, [0u8; 32]).to_bytes()
+}
+
+/// Serialized, freshly initialized `DeckState` (heap-allocated, it is large)
+pub fn make_deck_state() -> Vec<u8> {
+    let mut data = vec![0u8; DECK_STATE_SIZE];
+    DeckStateMut::from_bytes(&mut data)
+        .unwrap()
+        .initialize(0, &TEST_GAME_ID);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders_round_trip() {
+        let game_config = GameConfig::from_bytes(&make_game_config(3, 10)).unwrap();
+        assert_eq!(game_config.max_players, 3);
+        assert_eq!(game_config.small_blind, 10);
+        assert_eq!(game_config.game_id, TEST_GAME_ID);
+
+        let game_state =
+            GameState::from_bytes(&make_game_state(TexasHoldEmState::Betting, 2, 150)).unwrap();
+        assert_eq!(game_state.texas_state(), TexasHoldEmState::Betting);
+        assert_eq!(game_state.current_turn, 2);
+        assert_eq!(game_state.pot, 150);
+
+        let player_list = PlayerList::from_bytes(&make_player_list(&[[1u8; 32], [2u8; 32]])).unwrap();
+        assert_eq!(player_list.count, 2);
+        assert_eq!(player_list.get_player(1), Some(&[2u8; 32]));
+
+        let player_state = PlayerState::from_bytes(&make_player_state([3u8; 32], 1, 500)).unwrap();
+        assert_eq!(player_state.player, [3u8; 32]);
+        assert_eq!(player_state.seat_index, 1);
+        assert_eq!(player_state.chips, 500);
+
+        let deck_data = make_deck_state();
+        let deck_state = DeckStateRef::from_bytes(&deck_data).unwrap();
+        assert_eq!(deck_state.game_id(), &TEST_GAME_ID);
+        assert!(!deck_state.card_has_owner(0));
+    }
+}
@@ -0,0 +1,9 @@
+//! Little-endian field access for zero-copy account views
+
+/// Read a little-endian u64 at `offset`
+#[inline]
+pub fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
@@ -1,7 +1,9 @@
 //! Utility functions for the poker program
 
+pub mod bytes;
 pub mod pda;
 pub mod validation;
 
+pub use bytes::*;
 pub use pda::*;
 pub use validation::*;
@@ -2,7 +2,15 @@
 
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
-use crate::error::PokerError;
+use crate::{
+    error::PokerError,
+    constants::{DECK_SIZE, MAX_PLAYERS, PLAYER_STATS_SEED},
+    state::{
+        BettingRoundState, GamePhase, GameState, PlayerList, PlayerState, PlayerStateRef,
+        PlayerStats, SeatBetting, ShufflingState, TexasHoldEmState, PLAYER_STATS_SIZE,
+    },
+    utils::verify_pda,
+};
 
 /// Validate that an account is a signer
 pub fn validate_signer(account: &AccountInfo) -> Result<(), ProgramError> {
@@ -60,13 +68,335 @@ pub fn validate_rent_exempt(account: &AccountInfo, min_balance: u64) -> Result<(
     Ok(())
 }
 
-/// Get the next turn index, skipping folded players
-pub fn get_next_turn(current: u8, max_players: u8, is_folded: impl Fn(u8) -> bool) -> u8 {
-    let mut next = (current + 1) % max_players;
-    while is_folded(next) {
-        next = (next + 1) % max_players;
+/// Offset of the `amount` field in an SPL token account
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// Validate that a token account holds at least `required` tokens
+pub fn validate_token_balance(token_account_data: &[u8], required: u64) -> Result<(), ProgramError> {
+    let amount_bytes = token_account_data
+        .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .ok_or(PokerError::InvalidAccountData)?;
+    let amount = u64::from_le_bytes(amount_bytes.try_into().unwrap());
+    if amount < required {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    Ok(())
+}
+
+/// Validate that the signer is the player whose turn it is
+///
+/// Returns the current turn's seat index on success.
+pub fn assert_is_turn(
+    game_state: &GameState,
+    player_list: &PlayerList,
+    signer: &Pubkey,
+) -> Result<u8, ProgramError> {
+    assert_is_turn_at(game_state.current_turn, player_list, signer)
+}
+
+/// Same as `assert_is_turn`, for callers holding only the current turn index
+/// (e.g. a zero-copy `GameStateMut` view)
+pub fn assert_is_turn_at(
+    current_turn: u8,
+    player_list: &PlayerList,
+    signer: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let current_player = player_list
+        .get_player(current_turn)
+        .ok_or(PokerError::NotAPlayer)?;
+    if current_player != signer {
+        return Err(PokerError::NotYourTurn.into());
+    }
+    Ok(current_turn)
+}
+
+/// Validate that the game is shuffling and at the expected shuffle step
+///
+/// The phase is checked first so a stale `shuffling_state` left over from an
+/// earlier phase can never let a generate/shuffle/lock step through.
+pub fn assert_shuffling_step(
+    game_phase: GamePhase,
+    shuffling_state: ShufflingState,
+    expected: ShufflingState,
+) -> Result<(), ProgramError> {
+    if game_phase != GamePhase::Shuffling {
+        return Err(PokerError::InvalidState.into());
+    }
+    if shuffling_state != expected {
+        return Err(PokerError::InvalidShufflingState.into());
+    }
+    Ok(())
+}
+
+/// Reject a hand action on a game that has already finished
+///
+/// A finished game keeps its last texas/betting/drawing states, so a late
+/// action could otherwise pass the per-step checks. Run this first.
+pub fn assert_not_finished(game_phase: GamePhase) -> Result<(), ProgramError> {
+    if game_phase == GamePhase::Finished {
+        return Err(PokerError::InvalidGamePhase.into());
+    }
+    Ok(())
+}
+
+/// Validate that a voluntary bet or fold is allowed in the current state
+///
+/// Blinds are posted through `place_blind`, so betting and folding are
+/// rejected until the blinds round is over.
+pub fn assert_can_act(
+    texas_state: TexasHoldEmState,
+    betting_round_state: BettingRoundState,
+) -> Result<(), ProgramError> {
+    if texas_state != TexasHoldEmState::Betting {
+        return Err(PokerError::InvalidTexasState.into());
+    }
+    if betting_round_state == BettingRoundState::Blinds {
+        return Err(PokerError::InvalidBettingState.into());
+    }
+    Ok(())
+}
+
+/// Reject a bet, check or fold from a player who is already all-in
+///
+/// With no chips behind, an unfolded player has nothing left to act with;
+/// turn handling should skip them, and this stops them acting if it doesn't.
+pub fn assert_not_all_in(chips: u64) -> Result<(), ProgramError> {
+    if chips == 0 {
+        return Err(PokerError::InsufficientChips.into());
+    }
+    Ok(())
+}
+
+/// Validate that a PlayerState is the signer's own, for this game
+///
+/// Handlers that move chips or record a step on the passed PlayerState run
+/// this first, so a player can't act through another seat's account or
+/// through their account at another table.
+pub fn assert_own_player_state(
+    player_state: &PlayerState,
+    player: &Pubkey,
+    game_id: &[u8; 32],
+) -> Result<(), PokerError> {
+    if player_state.player != *player || player_state.game_id != *game_id {
+        return Err(PokerError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Reject a card index from instruction data that falls outside the deck
+///
+/// DeckState's accessors only debug-assert their index, so this must run
+/// before an index taken from the caller is used to read or write a card.
+pub fn assert_card_index(index: u8) -> Result<(), ProgramError> {
+    if index as usize >= DECK_SIZE {
+        return Err(PokerError::InvalidCardIndex.into());
+    }
+    Ok(())
+}
+
+/// Read the betting status of every seat from trailing PlayerState accounts
+///
+/// Returns `None` unless each occupied seat is covered by a program-owned
+/// PlayerState of this game belonging to the seated player, so a caller
+/// cannot close a round by omitting players who can still bet. Empty seats
+/// read as folded.
+pub fn read_table_betting(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    game_id: &[u8; 32],
+    player_list: &PlayerList,
+    max_players: u8,
+) -> Option<[SeatBetting; MAX_PLAYERS as usize]> {
+    let mut seats = [SeatBetting::default(); MAX_PLAYERS as usize];
+    let mut seen = [false; MAX_PLAYERS as usize];
+    for account in accounts {
+        if account.owner() != program_id {
+            return None;
+        }
+        let data = unsafe { account.borrow_data_unchecked() };
+        let state = PlayerStateRef::from_bytes(data)?;
+        let seat = state.seat_index();
+        if state.game_id() != game_id
+            || seat >= max_players
+            || player_list.get_player(seat) != Some(state.player())
+            || seen[seat as usize]
+        {
+            return None;
+        }
+        seen[seat as usize] = true;
+        seats[seat as usize] = state.seat_betting();
+    }
+    for seat in 0..max_players {
+        if !seen[seat as usize] {
+            if player_list.is_seat_occupied(seat) {
+                return None;
+            }
+            seats[seat as usize].is_folded = true;
+        }
+    }
+    Some(seats)
+}
+
+/// Read the PlayerStats of one player of this game
+///
+/// The account must sit at the player's stats PDA, so it can't be swapped
+/// for another account holding the same data.
+pub fn load_player_stats_for(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    game_id: &[u8; 32],
+    player: &Pubkey,
+) -> Result<PlayerStats, ProgramError> {
+    if account.owner() != program_id || account.data_len() != PLAYER_STATS_SIZE {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    let stats = unsafe { PlayerStats::from_bytes(account.borrow_data_unchecked()) }
+        .ok_or(PokerError::InvalidAccountData)?;
+    if &stats.game_id != game_id || &stats.player != player {
+        return Err(PokerError::InvalidAccountData.into());
+    }
+    if !verify_pda(account.key(), &[PLAYER_STATS_SEED, game_id, player], stats.bump, program_id) {
+        return Err(PokerError::InvalidPDA.into());
+    }
+    Ok(stats)
+}
+
+/// Seats that hold a player who has not folded, in seat order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveSeats {
+    seats: [u8; MAX_PLAYERS as usize],
+    len: usize,
+}
+
+impl ActiveSeats {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.seats[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// First active seat at or after `seat`, wrapping around the table
+    pub fn first_from(&self, seat: u8) -> Option<u8> {
+        let seats = self.as_slice();
+        seats.iter().copied().find(|&active| active >= seat).or(seats.first().copied())
+    }
+
+    /// First active seat after `current`, wrapping around the table
+    pub fn next_after(&self, current: u8) -> Option<u8> {
+        let seats = self.as_slice();
+        seats.iter().copied().find(|&seat| seat > current).or(seats.first().copied())
+    }
+
+    /// Last active seat before `current`, wrapping around the table
+    pub fn previous_before(&self, current: u8) -> Option<u8> {
+        let seats = self.as_slice();
+        seats.iter().rev().copied().find(|&seat| seat < current).or(seats.last().copied())
+    }
+}
+
+/// Collect the seats that are occupied and not folded
+///
+/// Seats vacated by `leave` are zeroed in the player list, so they are
+/// skipped along with folded players.
+pub fn active_seats(
+    player_list: &PlayerList,
+    max_players: u8,
+    is_folded: impl Fn(u8) -> bool,
+) -> ActiveSeats {
+    let mut active = ActiveSeats { seats: [0; MAX_PLAYERS as usize], len: 0 };
+    for seat in 0..max_players.min(MAX_PLAYERS) {
+        if player_list.is_seat_occupied(seat) && !is_folded(seat) {
+            active.seats[active.len] = seat;
+            active.len += 1;
+        }
+    }
+    active
+}
+
+/// Get the next turn index, skipping folded players and empty seats
+///
+/// Folds are read from the player list's `folded_bitmap`, so every caller
+/// skips them whether or not it was passed the table's PlayerStates. Stays
+/// on `current` when nobody else is left to act.
+pub fn get_next_turn(current: u8, player_list: &PlayerList, max_players: u8) -> u8 {
+    active_seats(player_list, max_players, |seat| player_list.has_folded(seat))
+        .next_after(current)
+        .unwrap_or(current)
+}
+
+/// Get the next seat to bet after `current`
+///
+/// Like `get_next_turn`, but all-in seats are skipped too: they stay in the
+/// hand but have nothing left to bet. Stays on `current` when nobody else can.
+pub fn next_to_bet(current: u8, player_list: &PlayerList, max_players: u8) -> u8 {
+    active_seats(player_list, max_players, |seat| !player_list.can_bet(seat))
+        .next_after(current)
+        .unwrap_or(current)
+}
+
+/// First seat that can still bet at or after `seat` (taken modulo the table size)
+pub fn first_to_bet_from(player_list: &PlayerList, max_players: u8, seat: u8) -> u8 {
+    let seat = seat % max_players;
+    active_seats(player_list, max_players, |seat| !player_list.can_bet(seat))
+        .first_from(seat)
+        .unwrap_or(seat)
+}
+
+/// First seat still in the hand at or after `seat` (taken modulo the table size)
+pub fn first_in_hand_from(player_list: &PlayerList, max_players: u8, seat: u8) -> u8 {
+    let seat = seat % max_players;
+    active_seats(player_list, max_players, |seat| player_list.has_folded(seat))
+        .first_from(seat)
+        .unwrap_or(seat)
+}
+
+/// First occupied seat at or after `seat` (taken modulo the table size)
+///
+/// Phases that start "N seats left of the dealer" use this so the turn never
+/// lands on a seat vacated by `leave`.
+pub fn first_occupied_from(player_list: &PlayerList, max_players: u8, seat: u8) -> u8 {
+    let seat = seat % max_players;
+    active_seats(player_list, max_players, |_| false)
+        .first_from(seat)
+        .unwrap_or(seat)
+}
+
+/// Players still in the hand: occupied seats that have not folded
+///
+/// A folded player who leaves mid-hand is taken off `num_folded` by `leave`,
+/// so the two counts never cover the same seat.
+pub fn players_in_hand(player_list: &PlayerList, num_folded: u8) -> u8 {
+    player_list.occupied_count().saturating_sub(num_folded)
+}
+
+/// Count the current seat's generate/shuffle/lock step and pass the turn on
+///
+/// Every occupied seat takes each step once, in seat order. Returns true
+/// once all of them have, with the turn moved to the first occupied seat
+/// from `next_first_seat` for the following step; otherwise the turn moves
+/// to the next occupied seat.
+pub fn finish_seat_step(
+    game_state: &mut GameState,
+    player_list: &PlayerList,
+    max_players: u8,
+    next_first_seat: u8,
+) -> bool {
+    game_state.active_player_count += 1;
+    if game_state.active_player_count >= player_list.occupied_count() {
+        game_state.active_player_count = 0;
+        game_state.current_turn = first_occupied_from(player_list, max_players, next_first_seat);
+        true
+    } else {
+        game_state.current_turn =
+            get_next_turn(game_state.current_turn, player_list, max_players);
+        false
     }
-    next
 }
 
 /// Get the previous index in circular fashion
@@ -91,3 +421,146 @@ pub fn get_first_to_call(
     }
     index
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bet_rejected_after_game_finished() {
+        use crate::state::GameStateMut;
+
+        // The hand ended mid-betting; its states are left behind
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.texas_state = TexasHoldEmState::Betting as u8;
+        game_state.betting_round_state = BettingRoundState::PostFlop as u8;
+        game_state.game_phase = GamePhase::Finished as u8;
+        let mut data = game_state.to_bytes();
+        let view = GameStateMut::from_bytes(&mut data).unwrap();
+
+        // A late bet would otherwise pass the betting check
+        assert!(assert_can_act(view.texas_state(), view.betting_round_state()).is_ok());
+        assert_eq!(
+            assert_not_finished(view.game_phase()),
+            Err(PokerError::InvalidGamePhase.into())
+        );
+        assert!(assert_not_finished(GamePhase::Drawing).is_ok());
+    }
+
+    #[test]
+    fn test_fold_rejected_during_blinds() {
+        assert_eq!(
+            assert_can_act(TexasHoldEmState::Betting, BettingRoundState::Blinds),
+            Err(PokerError::InvalidBettingState.into())
+        );
+        assert_eq!(
+            assert_can_act(TexasHoldEmState::Drawing, BettingRoundState::PreFlop),
+            Err(PokerError::InvalidTexasState.into())
+        );
+        for round in [
+            BettingRoundState::PreFlop,
+            BettingRoundState::PostFlop,
+            BettingRoundState::PostTurn,
+            BettingRoundState::Showdown,
+        ] {
+            assert!(assert_can_act(TexasHoldEmState::Betting, round).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_all_in_player_cannot_act() {
+        assert_eq!(assert_not_all_in(0), Err(PokerError::InsufficientChips.into()));
+        assert!(assert_not_all_in(1).is_ok());
+    }
+
+    #[test]
+    fn test_validate_token_balance_underfunded() {
+        let mut vault = [0u8; 165];
+        vault[64..72].copy_from_slice(&500u64.to_le_bytes());
+
+        assert!(validate_token_balance(&vault, 500).is_ok());
+        assert_eq!(
+            validate_token_balance(&vault, 501),
+            Err(PokerError::InvalidAccountData.into())
+        );
+        assert_eq!(
+            validate_token_balance(&vault[..70], 0),
+            Err(PokerError::InvalidAccountData.into())
+        );
+    }
+
+    #[test]
+    fn test_assert_is_turn() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        player_list.add_player([1u8; 32]);
+        player_list.add_player([2u8; 32]);
+
+        let mut game_state = GameState::new(0, [0u8; 32], 0);
+        game_state.current_turn = 1;
+
+        assert_eq!(assert_is_turn(&game_state, &player_list, &[2u8; 32]), Ok(1));
+        assert_eq!(
+            assert_is_turn(&game_state, &player_list, &[1u8; 32]),
+            Err(PokerError::NotYourTurn.into())
+        );
+
+        game_state.current_turn = 2;
+        assert_eq!(
+            assert_is_turn(&game_state, &player_list, &[2u8; 32]),
+            Err(PokerError::NotAPlayer.into())
+        );
+    }
+
+    #[test]
+    fn test_active_seats_skip_folds_and_empty_seats() {
+        // Six seats: seat 4 left the table, seats 1 and 5 folded
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..6u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        player_list.remove_player(4);
+        let folded = [false, true, false, false, false, true];
+
+        let active = active_seats(&player_list, 6, |seat| folded[seat as usize]);
+        assert_eq!(active.as_slice(), &[0, 2, 3]);
+        assert_eq!(active.len(), 3);
+
+        // Turn order skips the same seats and wraps around
+        player_list.mark_folded(1);
+        player_list.mark_folded(5);
+        assert_eq!(get_next_turn(0, &player_list, 6), 2);
+        assert_eq!(get_next_turn(3, &player_list, 6), 0);
+        assert_eq!(active.next_after(5), Some(0));
+        assert_eq!(first_in_hand_from(&player_list, 6, 4), 0);
+        assert_eq!(first_in_hand_from(&player_list, 6, 1), 2);
+
+        // Phases starting left of the dealer skip the empty seat
+        assert_eq!(first_occupied_from(&player_list, 6, 4), 5);
+        assert_eq!(first_occupied_from(&player_list, 6, 3 + 3), 0);
+        assert_eq!(players_in_hand(&player_list, 2), 3);
+
+        // Everyone folded: nobody to move to
+        let none = active_seats(&player_list, 6, |_| true);
+        assert!(none.is_empty());
+        for seat in [0, 2, 3] {
+            player_list.mark_folded(seat);
+        }
+        assert_eq!(get_next_turn(2, &player_list, 6), 2);
+    }
+
+    #[test]
+    fn test_betting_turns_skip_all_in_seats() {
+        let mut player_list = PlayerList::new(0, [0u8; 32]);
+        for seat in 0..4u8 {
+            player_list.add_player([seat + 1; 32]);
+        }
+        player_list.mark_all_in(1);
+        player_list.mark_folded(2);
+
+        // All-in seats still open and submit, but never bet again
+        assert_eq!(get_next_turn(0, &player_list, 4), 1);
+        assert_eq!(next_to_bet(0, &player_list, 4), 3);
+        assert_eq!(first_to_bet_from(&player_list, 4, 1), 3);
+        assert_eq!(player_list.count_can_bet(), 2);
+    }
+}
@@ -20,6 +20,7 @@ import {
   PlayerData,
   Instruction,
   derivePlayerState,
+  derivePlayerStats,
   DECK_SIZE,
   fetchGameState,
   fetchPlayerState,
@@ -31,6 +32,7 @@ import {
   encryptWorkDeck,
   shuffleWorkDeck,
   lockWorkDeck,
+  lockPublicKeys,
   generateWorkDeck,
   getCurveOrderBytes,
   keyToBytes,
@@ -62,7 +64,7 @@ function buildInstruction(discriminator: Instruction, data?: Buffer): Buffer {
  * Data: 32-byte seed (reduced from 1664 bytes via seed-based derivation)
  *
  * The on-chain program will:
- * 1. Verify keccak256(seed) == player's stored commitment
+ * 1. Verify keccak256(seed || player) == player's stored commitment
  * 2. Derive v[i] = keccak256(seed || i) for all 52 cards
  * 3. Add derived values to the accumulator
  */
@@ -99,14 +101,59 @@ export async function generateShuffleVector(
   });
 }
 
+/**
+ * Generate shuffle vector in chunks
+ * Data: 32-byte seed + start (1) + end (1)
+ *
+ * Derives and accumulates only v[start..end). Chunks must be sent in order;
+ * the player's turn ends once the chunk ending at 52 is accumulated.
+ */
+export async function generateShuffleVectorChunk(
+  player: PlayerData,
+  gameAccounts: GameAccounts,
+  start: number,
+  end: number
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  if (!player.shuffleSeed) {
+    throw new Error('Player shuffleSeed not set - ensure createPlayer uses seed-based approach');
+  }
+
+  const data = Buffer.alloc(34);
+  data.set(player.shuffleSeed, 0);
+  data.writeUInt8(start, 32);
+  data.writeUInt8(end, 33);
+
+  const ix = new TransactionInstruction({
+    keys: [
+      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+      { pubkey: player.playerState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: buildInstruction(Instruction.GenerateChunk, data),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+}
+
 /**
  * Map deck instruction - submit initial deck mapping
- * Data: 52 EC points (52 x 64 bytes = 3328 bytes)
+ * Data: one EC point per card (52 x 64 bytes = 3328 bytes, 36 for a short deck)
  */
 export async function mapDeck(
   player: PlayerData,
   gameAccounts: GameAccounts,
-  accumulator: string[]
+  accumulator: string[],
+  deckSize: number = DECK_SIZE
 ): Promise<any[]> {
   const connection = getConnection();
   const programId = getProgramId();
@@ -114,9 +161,9 @@ export async function mapDeck(
   // Generate work deck from accumulator
   const workDeck = generateWorkDeck(accumulator);
 
-  // Build data: 52 x 64 bytes (EC points)
-  const data = Buffer.alloc(DECK_SIZE * 64);
-  for (let i = 0; i < DECK_SIZE; i++) {
+  // Build data: deckSize x 64 bytes (EC points)
+  const data = Buffer.alloc(deckSize * 64);
+  for (let i = 0; i < deckSize; i++) {
     const point = workDeck[i];
     const pointBytes = pointToBytes(point);
     Buffer.from(pointBytes).copy(data, i * 64);
@@ -127,6 +174,7 @@ export async function mapDeck(
   const ix = new TransactionInstruction({
     keys: [
       { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
@@ -169,6 +217,7 @@ export async function mapDeckPart1(
   const ix = new TransactionInstruction({
     keys: [
       { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
@@ -185,18 +234,19 @@ export async function mapDeckPart1(
 }
 
 /**
- * Map deck part 2 instruction - stores cards 26-51 in accumulator
+ * Map deck part 2 instruction - stores cards 26 onward in accumulator
  */
 export async function mapDeckPart2(
   player: PlayerData,
   gameAccounts: GameAccounts,
-  workDeck: any[]
+  workDeck: any[],
+  deckSize: number = DECK_SIZE
 ): Promise<void> {
   const connection = getConnection();
   const programId = getProgramId();
 
-  // Build data: 26 compressed EC points (26 x 33 bytes = 858 bytes)
-  const remainingCards = DECK_SIZE - CARDS_PER_PART;
+  // Build data: the rest of the deck compressed (26 x 33 bytes = 858 bytes, 10 for a short deck)
+  const remainingCards = deckSize - CARDS_PER_PART;
   const data = Buffer.alloc(remainingCards * COMPRESSED_POINT_SIZE);
   for (let i = 0; i < remainingCards; i++) {
     const cardIndex = CARDS_PER_PART + i;
@@ -208,6 +258,7 @@ export async function mapDeckPart2(
   const ix = new TransactionInstruction({
     keys: [
       { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
@@ -230,10 +281,11 @@ export async function mapDeckPart2(
 export async function mapDeckWithParts(
   player: PlayerData,
   gameAccounts: GameAccounts,
-  workDeck: any[]
+  workDeck: any[],
+  deckSize: number = DECK_SIZE
 ): Promise<void> {
   await mapDeckPart1(player, gameAccounts, workDeck);
-  await mapDeckPart2(player, gameAccounts, workDeck);
+  await mapDeckPart2(player, gameAccounts, workDeck, deckSize);
 }
 
 /**
@@ -346,6 +398,9 @@ export async function lockCards(
   gameAccounts: GameAccounts,
   currentDeck: any[]
 ): Promise<any[]> {
+  // Commit the lock public keys before any card is locked
+  await commitLockKeys(player, gameAccounts);
+
   // Lock the deck with player's lock vector
   const locked = lockWorkDeck(currentDeck, player.lockVector);
 
@@ -358,6 +413,44 @@ export async function lockCards(
   return locked;
 }
 
+/**
+ * Commit lock public keys (lock * G per card) in two runs of 26
+ * Data: first_index(1) + compressed key(32) per card
+ */
+export async function commitLockKeys(
+  player: PlayerData,
+  gameAccounts: GameAccounts
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+  const keys = lockPublicKeys(player.lockVector);
+
+  for (let first = 0; first < DECK_SIZE; first += CARDS_PER_PART) {
+    const count = Math.min(CARDS_PER_PART, DECK_SIZE - first);
+    const data = Buffer.alloc(1 + count * COMPRESSED_POINT_SIZE);
+    data.writeUInt8(first, 0);
+    for (let i = 0; i < count; i++) {
+      Buffer.from(compressPoint(keys[first + i])).copy(data, 1 + i * COMPRESSED_POINT_SIZE);
+    }
+
+    const ix = new TransactionInstruction({
+      keys: [
+        { pubkey: player.keypair.publicKey, isSigner: true, isWritable: false },
+        { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+        { pubkey: gameAccounts.gameState, isSigner: false, isWritable: false },
+        { pubkey: player.playerState, isSigner: false, isWritable: true },
+      ],
+      programId,
+      data: buildInstruction(Instruction.CommitLockKeys, data),
+    });
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+      commitment: 'confirmed',
+    });
+  }
+}
+
 /**
  * Lock cards Part1 - submit compressed points for cards 0-25
  */
@@ -455,7 +548,7 @@ export async function placeBlind(
       { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
     ],
     programId,
     data: buildInstruction(Instruction.PlaceBlind, data),
@@ -467,6 +560,34 @@ export async function placeBlind(
   });
 }
 
+/**
+ * Post a UTG straddle (2x big blind) after blinds, before cards are drawn
+ */
+export async function straddle(
+  player: PlayerData,
+  gameAccounts: GameAccounts
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  const ix = new TransactionInstruction({
+    keys: [
+      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+      { pubkey: player.playerState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+    ],
+    programId,
+    data: buildInstruction(Instruction.Straddle),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+}
+
 /**
  * Place blinds for small and big blind
  */
@@ -522,7 +643,7 @@ export async function draw(
 
 /**
  * Reveal card instruction (other players decrypt a drawn card)
- * Data: inv_key(32) + index(1) = 33 bytes
+ * Data: reveal_round(2) + inv_key(32) + index(1) = 35 bytes
  * The inverse key is computed off-chain to avoid expensive on-chain computation
  */
 export async function reveal(
@@ -538,9 +659,13 @@ export async function reveal(
   const invKey = modInverse(lockKey);
   const invKeyBytes = keyToBytes(invKey);
 
-  const data = Buffer.alloc(33);
-  Buffer.from(invKeyBytes).copy(data, 0);
-  data.writeUInt8(cardIndex, 32);
+  // The reveal must name the card currently up for reveal
+  const { revealRound } = await fetchGameState(gameAccounts.gameState);
+
+  const data = Buffer.alloc(35);
+  data.writeUInt16LE(revealRound, 0);
+  Buffer.from(invKeyBytes).copy(data, 2);
+  data.writeUInt8(cardIndex, 34);
 
   const ix = new TransactionInstruction({
     keys: [
@@ -549,6 +674,7 @@ export async function reveal(
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.deckState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+      { pubkey: player.playerState, isSigner: false, isWritable: true },
     ],
     programId,
     data: buildInstruction(Instruction.RevealCard, data),
@@ -560,6 +686,53 @@ export async function reveal(
   });
 }
 
+/**
+ * Reveal a card for every non-owner at once, decrypted with one combined key
+ *
+ * `revealers` must be every occupied seat except the card's owner, in seat order,
+ * each paired with the lock key they applied to the card.
+ */
+export async function revealCombined(
+  revealers: { player: PlayerData; lockKey: string }[],
+  gameAccounts: GameAccounts,
+  cardIndex: number
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  const invKeys = revealers.map(({ lockKey }) => BigInt(modInverse(lockKey)));
+
+  // index(1) + inv_key(32) per revealer; the program combines the keys
+  const data = Buffer.alloc(1 + 32 * invKeys.length);
+  data.writeUInt8(cardIndex, 0);
+  invKeys.forEach((key, i) => {
+    Buffer.from(keyToBytes(key.toString(16))).copy(data, 1 + 32 * i);
+  });
+
+  const ix = new TransactionInstruction({
+    keys: [
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.deckState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+      ...revealers.flatMap(({ player }) => [
+        { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+        { pubkey: player.playerState, isSigner: false, isWritable: true },
+      ]),
+    ],
+    programId,
+    data: buildInstruction(Instruction.RevealCombined, data),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(
+    connection,
+    tx,
+    revealers.map(({ player }) => player.keypair),
+    { commitment: 'confirmed' }
+  );
+}
+
 /**
  * Draw and reveal cards for all players
  */
@@ -619,7 +792,7 @@ export async function bet(
       { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
     ],
     programId,
     data: buildInstruction(Instruction.Bet, data),
@@ -652,7 +825,7 @@ export async function call(
       { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
     ],
     programId,
     data: buildInstruction(Instruction.Bet, amountData),
@@ -674,14 +847,16 @@ export async function fold(
   const connection = getConnection();
   const programId = getProgramId();
 
+  const keys = [
+    { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: player.playerState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+  ];
+
   const ix = new TransactionInstruction({
-    keys: [
-      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
-      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
-      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
-      { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
-    ],
+    keys,
     programId,
     data: buildInstruction(Instruction.Fold),
   });
@@ -712,7 +887,7 @@ export async function check(
       { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
     ],
     programId,
     data: buildInstruction(Instruction.Bet, amountData),
@@ -760,7 +935,8 @@ export async function everyoneCalls(
  */
 export async function dealCommunityCards(
   dealer: PlayerData,
-  gameAccounts: GameAccounts
+  gameAccounts: GameAccounts,
+  instruction: Instruction = Instruction.DealCommunityCard
 ): Promise<number[]> {
   const connection = getConnection();
   const programId = getProgramId();
@@ -779,7 +955,7 @@ export async function dealCommunityCards(
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
     ],
     programId,
-    data: buildInstruction(Instruction.DealCommunityCard),
+    data: buildInstruction(instruction),
   });
 
   const tx = new Transaction().add(ix);
@@ -802,6 +978,17 @@ export async function dealCommunityCards(
   return cardIndices;
 }
 
+/**
+ * Deal flop instruction - deals all three flop cards at once
+ * The flop is then revealed and opened as a batch of 3 (inv_key, index) pairs
+ */
+export async function dealFlop(
+  dealer: PlayerData,
+  gameAccounts: GameAccounts
+): Promise<number[]> {
+  return dealCommunityCards(dealer, gameAccounts, Instruction.DealFlop);
+}
+
 /**
  * Open community card instruction
  * Data: inv_key(32) + index(1) = 33 bytes
@@ -831,7 +1018,8 @@ export async function openCommunityCard(
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.deckState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.communityCards, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: false },
     ],
     programId,
     data: buildInstruction(Instruction.OpenCommunityCard, data),
@@ -936,6 +1124,45 @@ export async function openCard(
   });
 }
 
+/**
+ * Open both of a player's hole cards in a single transaction
+ */
+export async function openBothCards(
+  player: PlayerData,
+  gameAccounts: GameAccounts,
+  cardIndices: [number, number]
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  // Two inv_key(32) + index(1) pairs
+  const data = Buffer.alloc(66);
+  cardIndices.forEach((cardIndex, i) => {
+    const invKey = modInverse(player.lockVector[cardIndex]);
+    Buffer.from(keyToBytes(invKey)).copy(data, i * 33);
+    data.writeUInt8(cardIndex, i * 33 + 32);
+  });
+
+  const ix = new TransactionInstruction({
+    keys: [
+      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+      { pubkey: player.playerState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.deckState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.communityCards, isSigner: false, isWritable: false },
+      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: buildInstruction(Instruction.OpenCard, data),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+}
+
 /**
  * Open all player hole cards in showdown
  */
@@ -980,7 +1207,13 @@ export async function openCards(
 export async function submitBestHand(
   player: PlayerData,
   gameAccounts: GameAccounts,
-  bestHandPoints: any[]
+  bestHandPoints: any[],
+  autoPay?: {
+    potAccount: PublicKey;
+    allPlayerStates: PublicKey[];
+    winnerTokenAccounts: PublicKey[];
+    playerStats?: PublicKey[];
+  }
 ): Promise<void> {
   const connection = getConnection();
   const programId = getProgramId();
@@ -992,22 +1225,123 @@ export async function submitBestHand(
     Buffer.from(pointBytes).copy(data, i * 64);
   }
 
+  const keys = [
+    { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: player.playerState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.communityCards, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
+  ];
+
+  // Last submission on an auto-pay table: the ClaimPot accounts follow
+  if (autoPay) {
+    keys.push({ pubkey: autoPay.potAccount, isSigner: false, isWritable: true });
+    keys.push({ pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false });
+    for (const playerState of autoPay.allPlayerStates) {
+      keys.push({ pubkey: playerState, isSigner: false, isWritable: false });
+    }
+    for (const winnerToken of autoPay.winnerTokenAccounts) {
+      keys.push({ pubkey: winnerToken, isSigner: false, isWritable: true });
+    }
+    for (const stats of autoPay.playerStats ?? []) {
+      keys.push({ pubkey: stats, isSigner: false, isWritable: true });
+    }
+  }
+
+  const ix = new TransactionInstruction({
+    keys,
+    programId,
+    data: buildInstruction(Instruction.SubmitBestHand, data),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+}
+
+/**
+ * Muck instruction - give up at showdown instead of opening or submitting a hand
+ */
+export async function muck(
+  player: PlayerData,
+  gameAccounts: GameAccounts,
+  autoPay?: {
+    potAccount: PublicKey;
+    allPlayerStates: PublicKey[];
+    winnerTokenAccounts: PublicKey[];
+    playerStats?: PublicKey[];
+  }
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  const keys = [
+    { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: player.playerState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+  ];
+
+  // Last muck on an auto-pay table: the ClaimPot accounts follow
+  if (autoPay) {
+    keys.push({ pubkey: autoPay.potAccount, isSigner: false, isWritable: true });
+    keys.push({ pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false });
+    for (const playerState of autoPay.allPlayerStates) {
+      keys.push({ pubkey: playerState, isSigner: false, isWritable: false });
+    }
+    for (const winnerToken of autoPay.winnerTokenAccounts) {
+      keys.push({ pubkey: winnerToken, isSigner: false, isWritable: true });
+    }
+    for (const stats of autoPay.playerStats ?? []) {
+      keys.push({ pubkey: stats, isSigner: false, isWritable: true });
+    }
+  }
+
+  const ix = new TransactionInstruction({
+    keys,
+    programId,
+    data: buildInstruction(Instruction.Muck),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+}
+
+/**
+ * SetDeck instruction (`test-deck` builds only) - force the deck order
+ *
+ * Replaces every shuffle once the deck is mapped; `cardIds` lists the card
+ * IDs in dealing order. Locking must then start from the on-chain DeckState.
+ */
+export async function setDeck(
+  authority: Keypair,
+  gameAccounts: GameAccounts,
+  cardIds: number[]
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
   const ix = new TransactionInstruction({
     keys: [
-      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: authority.publicKey, isSigner: true, isWritable: false },
       { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
-      { pubkey: player.playerState, isSigner: false, isWritable: true },
+      { pubkey: gameAccounts.deckState, isSigner: false, isWritable: true },
       { pubkey: gameAccounts.accumulator, isSigner: false, isWritable: false },
-      { pubkey: gameAccounts.communityCards, isSigner: false, isWritable: false },
       { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
     ],
     programId,
-    data: buildInstruction(Instruction.SubmitBestHand, data),
+    data: buildInstruction(Instruction.SetDeck, Buffer.from(cardIds)),
   });
 
   const tx = new Transaction().add(ix);
-  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+  await sendAndConfirmTransaction(connection, tx, [authority], {
     commitment: 'confirmed',
   });
 }
@@ -1020,7 +1354,8 @@ export async function claimPot(
   gameAccounts: GameAccounts,
   allPlayerStates: PublicKey[],
   potAccount: PublicKey,
-  winnerTokenAccounts: PublicKey[]
+  winnerTokenAccounts: PublicKey[],
+  playerStats: PublicKey[] = []
 ): Promise<PublicKey[]> {
   const connection = getConnection();
   const programId = getProgramId();
@@ -1045,6 +1380,11 @@ export async function claimPot(
     keys.push({ pubkey: winnerToken, isSigner: false, isWritable: true });
   }
 
+  // Stats table: every seated player's PlayerStats, in seat order
+  for (const stats of playerStats) {
+    keys.push({ pubkey: stats, isSigner: false, isWritable: true });
+  }
+
   const ix = new TransactionInstruction({
     keys,
     programId,
@@ -1060,6 +1400,36 @@ export async function claimPot(
   return [];
 }
 
+/**
+ * Init player stats instruction - open a player's PlayerStats on a stats table
+ */
+export async function initPlayerStats(
+  player: PlayerData,
+  gameAccounts: GameAccounts
+): Promise<PublicKey> {
+  const connection = getConnection();
+  const programId = getProgramId();
+  const [playerStats] = derivePlayerStats(gameAccounts.gameId, player.keypair.publicKey, programId);
+
+  const ix = new TransactionInstruction({
+    keys: [
+      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+      { pubkey: playerStats, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: buildInstruction(Instruction.InitPlayerStats),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [player.keypair], {
+    commitment: 'confirmed',
+  });
+
+  return playerStats;
+}
+
 /**
  * Start next game instruction
  */
@@ -1113,17 +1483,19 @@ export async function slash(
   const connection = getConnection();
   const programId = getProgramId();
 
+  const keys = [
+    { pubkey: caller.keypair.publicKey, isSigner: true, isWritable: true },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: offenderState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+    { pubkey: chipVault, isSigner: false, isWritable: true },
+    { pubkey: slashRecipient, isSigner: false, isWritable: true },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
   const ix = new TransactionInstruction({
-    keys: [
-      { pubkey: caller.keypair.publicKey, isSigner: true, isWritable: true },
-      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: false },
-      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
-      { pubkey: offenderState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: false },
-      { pubkey: chipVault, isSigner: false, isWritable: true },
-      { pubkey: slashRecipient, isSigner: false, isWritable: true },
-      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
-    ],
+    keys,
     programId,
     data: buildInstruction(Instruction.Leave), // Note: Slash might be a different instruction number
   });
@@ -1139,19 +1511,29 @@ export async function slash(
  */
 export async function leaveGame(
   player: PlayerData,
-  gameAccounts: GameAccounts
+  gameAccounts: GameAccounts,
+  playerStats?: PublicKey
 ): Promise<void> {
   const connection = getConnection();
   const programId = getProgramId();
 
+  const keys = [
+    { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: player.playerState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.vault, isSigner: false, isWritable: true },
+    { pubkey: player.tokenAccount, isSigner: false, isWritable: true },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+  // Stats table, leaving before the hand is settled: the player's PlayerStats
+  if (playerStats) {
+    keys.push({ pubkey: playerStats, isSigner: false, isWritable: true });
+  }
+
   const ix = new TransactionInstruction({
-    keys: [
-      { pubkey: player.keypair.publicKey, isSigner: true, isWritable: true },
-      { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
-      { pubkey: player.playerState, isSigner: false, isWritable: true },
-      { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
-    ],
+    keys,
     programId,
     data: buildInstruction(Instruction.Leave),
   });
@@ -1161,3 +1543,42 @@ export async function leaveGame(
     commitment: 'confirmed',
   });
 }
+
+/**
+ * Cancel game instruction - the authority abandons a table still waiting
+ * for players, refunding every seated player's deposit
+ */
+export async function cancelGame(
+  authority: Keypair,
+  gameAccounts: GameAccounts,
+  seatedPlayers: PlayerData[]
+): Promise<void> {
+  const connection = getConnection();
+  const programId = getProgramId();
+
+  const keys = [
+    { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+    { pubkey: gameAccounts.gameConfig, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.gameState, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.playerList, isSigner: false, isWritable: true },
+    { pubkey: gameAccounts.vault, isSigner: false, isWritable: true },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
+  // (player state, token account) per seated player, in seat order
+  for (const player of seatedPlayers) {
+    keys.push({ pubkey: player.playerState, isSigner: false, isWritable: true });
+    keys.push({ pubkey: player.tokenAccount, isSigner: false, isWritable: true });
+  }
+
+  const ix = new TransactionInstruction({
+    keys,
+    programId,
+    data: buildInstruction(Instruction.CancelGame),
+  });
+
+  const tx = new Transaction().add(ix);
+  await sendAndConfirmTransaction(connection, tx, [authority], {
+    commitment: 'confirmed',
+  });
+}
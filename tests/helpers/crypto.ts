@@ -141,22 +141,23 @@ export function generateShuffleSeed(): Uint8Array {
 
 /**
  * Calculate the commitment for a shuffle seed
- * commitment = keccak256(seed)
+ * commitment = keccak256(seed || player)
  *
  * This is what gets stored on-chain during JoinGame.
- * The hiding property ensures other players can't see the seed until Generate.
+ * The hiding property ensures other players can't see the seed until Generate,
+ * and binding the player's pubkey stops anyone else reusing the commitment.
  */
-export function calculateSeedCommitment(seed: Uint8Array): Uint8Array {
+export function calculateSeedCommitment(seed: Uint8Array, player: Uint8Array): Uint8Array {
   // Use proper Keccak256 (NOT SHA3-256 - they are different!)
-  const hash = keccak256Hash(seed);
+  const hash = keccak256Hash(Buffer.concat([Buffer.from(seed), Buffer.from(player)]));
   return new Uint8Array(Buffer.from(hash, 'hex'));
 }
 
 /**
  * Derive a shuffle value from a seed and index
- * v[i] = keccak256(seed || i)
+ * v[i] = keccak256(seed || i) mod CURVE_ORDER
  *
- * This must match the on-chain derivation in generate.rs
+ * This must match derive_shuffle_value in crypto/commitments.rs
  * Used by the client for shuffle/lock/reveal operations
  */
 export function deriveShuffleValue(seed: Uint8Array, index: number): Uint8Array {
@@ -167,7 +168,8 @@ export function deriveShuffleValue(seed: Uint8Array, index: number): Uint8Array
 
   // Use proper Keccak256 (NOT SHA3-256 - they are different!)
   const hash = keccak256Hash(preimage);
-  return new Uint8Array(Buffer.from(hash, 'hex'));
+  const reduced = BigInt('0x' + hash) % CURVE_ORDER;
+  return keyToBytes(reduced.toString(16));
 }
 
 /**
@@ -327,6 +329,16 @@ export function lockWorkDeck(workDeck: Bn254Point[], lock: string[]): Bn254Point
   return points;
 }
 
+/**
+ * Lock public keys (lock * G) for every card, checked against reveal keys on-chain
+ */
+export function lockPublicKeys(lock: string[]): Bn254Point[] {
+  return lock.map((key) => {
+    const lockBig = BigInt(key.toString().replace('0x', '0x') || '0');
+    return Bn254Point.fromGenerator().mul(lockBig % CURVE_ORDER);
+  });
+}
+
 /**
  * Unlock a single card by multiplying by the modular inverse of each lock key
  */
@@ -41,15 +41,18 @@ export const ACCUMULATOR_SEED = Buffer.from('accumulator');
 export const COMMUNITY_CARDS_SEED = Buffer.from('community');
 export const VAULT_SEED = Buffer.from('vault');
 export const PLAYER_LIST_SEED = Buffer.from('player_list');
+export const PLAYER_STATS_SEED = Buffer.from('stats');
 
 // Account sizes (matching Rust state structs)
-export const GAME_CONFIG_SIZE = 134; // bump(1) + game_id(32) + authority(32) + token_mint(32) + max_players(1) + current_players(1) + small_blind(8) + min_buy_in(8) + dealer_index(1) + is_accepting_players(1) + created_at(8) + timeout_seconds(4) + slash_percentage(1) + game_number(4) = 134
-export const GAME_STATE_SIZE = 125;
-export const PLAYER_STATE_SIZE = 256;
+export const GAME_CONFIG_SIZE = 144; // bump(1) + game_id(32) + authority(32) + token_mint(32) + max_players(1) + current_players(1) + small_blind(8) + min_buy_in(8) + dealer_index(1) + is_accepting_players(1) + created_at(8) + timeout_seconds(4) + slash_percentage(1) + game_number(4) + deck_size(1) + rotate_dealer(1) + dead_button(1) + allow_straddle(1) + chip_decimals(1) + cap_to_effective_stack(1) + min_buy_in_big_blinds(1) + game_mode(1) + auto_pay(1) + stats_enabled(1) = 144
+export const GAME_STATE_SIZE = 199;
+export const PLAYER_STATE_SIZE = 327; // ... + reveal_keys(16*8) + lock_committed(1) = 327
+export const PLAYER_STATE_ACCOUNT_SIZE = 327 + 52 * 32; // state + lock public key per card = 1991
 export const DECK_STATE_SIZE = 5025; // bump(1) + game_id(32) + work_deck(52*64) + card_owners(52*32) = 5025
 export const ACCUMULATOR_STATE_SIZE = 5025;
-export const COMMUNITY_CARDS_SIZE = 360;
-export const PLAYER_LIST_SIZE = 227;
+export const COMMUNITY_CARDS_SIZE = 365;
+export const PLAYER_LIST_SIZE = 229;
+export const PLAYER_STATS_SIZE = 81; // bump(1) + game_id(32) + player(32) + hands_played(4) + hands_won(4) + net_chips(8) = 81
 
 // Game constants
 export const MAX_PLAYERS = 6;
@@ -58,6 +61,8 @@ export const DECK_SIZE = 52;
 export const HOLE_CARDS_PER_PLAYER = 2;
 export const MAX_COMMUNITY_CARDS = 5;
 export const TOKEN_DECIMALS = 9;
+export const DEFAULT_TIMEOUT_SECONDS = 120;
+export const DEFAULT_SLASH_PERCENTAGE = 10;
 
 // Instruction discriminators (matching processor.rs)
 export enum Instruction {
@@ -87,6 +92,17 @@ export enum Instruction {
   LockPart2 = 23,
   MapDeckPart1 = 25,
   MapDeckPart2 = 26,
+  GenerateChunk = 27,
+  Straddle = 28,
+  Benchmark = 29, // only with the `benchmark` feature
+  RevealCombined = 30,
+  DealFlop = 31,
+  Muck = 32,
+  SetDeck = 33, // only with the `test-deck` feature
+  InitializeAndJoin = 34,
+  CancelGame = 35,
+  CommitLockKeys = 36,
+  InitPlayerStats = 37,
 }
 
 /**
@@ -122,7 +138,7 @@ export interface PlayerData {
   shuffleSeed: Uint8Array;
   /** Derived shuffle values (for shuffle/lock/reveal operations) */
   shuffleVector: string[];
-  /** commitment = keccak256(shuffleSeed) - stored on-chain */
+  /** commitment = keccak256(shuffleSeed || player) - stored on-chain */
   commitment: Uint8Array;
   lockVector: string[];
 }
@@ -161,6 +177,20 @@ export function derivePlayerState(
   );
 }
 
+/**
+ * Derive player stats PDA
+ */
+export function derivePlayerStats(
+  gameId: Uint8Array,
+  player: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PLAYER_STATS_SEED, gameId, player.toBuffer()],
+    programId
+  );
+}
+
 /**
  * Derive deck state PDA
  */
@@ -379,9 +409,23 @@ export function buildInitializeGameData(
   gameId: Uint8Array,
   maxPlayers: number,
   smallBlind: bigint,
-  minBuyIn: bigint
+  minBuyIn: bigint,
+  deckSize: number = DECK_SIZE,
+  timeoutSeconds: number = DEFAULT_TIMEOUT_SECONDS,
+  slashPercentage: number = DEFAULT_SLASH_PERCENTAGE,
+  rotateDealer: boolean = true,
+  allowStraddle: boolean = false,
+  chipDecimals: number = 0,
+  capToEffectiveStack: boolean = false,
+  minBuyInBigBlinds: number = 0,
+  gameMode: GameMode = GameMode.CashGame,
+  preSeat?: { commitment: Uint8Array; depositAmount: bigint },
+  autoPay: boolean = false,
+  statsEnabled: boolean = false
 ): Buffer {
-  const data = Buffer.alloc(50); // 1 (discriminator) + 32 (gameId) + 1 (maxPlayers) + 8 (smallBlind) + 8 (minBuyIn)
+  // Optional authority pre-seat: flag(1) + commitment(32) + depositAmount(8),
+  // then the optional auto-pay and stats flags(1 each), which need the pre-seat section (zeroed if unused)
+  const data = Buffer.alloc(statsEnabled ? 105 : autoPay ? 104 : preSeat ? 103 : 62); // 1 (discriminator) + 32 (gameId) + 1 (maxPlayers) + 8 (smallBlind) + 8 (minBuyIn) + 1 (deckSize) + 4 (timeoutSeconds) + 1 (slashPercentage) + 1 (rotateDealer) + 1 (allowStraddle) + 1 (chipDecimals) + 1 (capToEffectiveStack) + 1 (minBuyInBigBlinds) + 1 (gameMode)
   let offset = 0;
 
   data.writeUInt8(Instruction.InitializeGame, offset);
@@ -397,7 +441,85 @@ export function buildInitializeGameData(
   offset += 8;
 
   data.writeBigUInt64LE(minBuyIn, offset);
+  offset += 8;
+
+  data.writeUInt8(deckSize, offset);
+  offset += 1;
+
+  data.writeUInt32LE(timeoutSeconds, offset);
+  offset += 4;
+
+  data.writeUInt8(slashPercentage, offset);
+  offset += 1;
+
+  data.writeUInt8(rotateDealer ? 1 : 0, offset);
+  offset += 1;
+
+  data.writeUInt8(allowStraddle ? 1 : 0, offset);
+  offset += 1;
+
+  data.writeUInt8(chipDecimals, offset);
+  offset += 1;
+
+  data.writeUInt8(capToEffectiveStack ? 1 : 0, offset);
+  offset += 1;
+
+  data.writeUInt8(minBuyInBigBlinds, offset);
+  offset += 1;
+
+  data.writeUInt8(gameMode, offset);
+  offset += 1;
 
+  if (preSeat) {
+    data.writeUInt8(1, offset);
+    offset += 1;
+
+    data.set(preSeat.commitment, offset);
+    offset += 32;
+
+    data.writeBigUInt64LE(preSeat.depositAmount, offset);
+  }
+
+  if (autoPay) {
+    data.writeUInt8(1, 103);
+  }
+
+  if (statsEnabled) {
+    data.writeUInt8(1, 104);
+  }
+
+  return data;
+}
+
+/**
+ * Build InitializeAndJoin instruction data: InitializeGame with the
+ * authority's seat (commitment and deposit) required
+ */
+export function buildInitializeAndJoinData(
+  gameId: Uint8Array,
+  maxPlayers: number,
+  smallBlind: bigint,
+  minBuyIn: bigint,
+  commitment: Uint8Array,
+  depositAmount: bigint
+): Buffer {
+  const data = buildInitializeGameData(
+    gameId,
+    maxPlayers,
+    smallBlind,
+    minBuyIn,
+    DECK_SIZE,
+    DEFAULT_TIMEOUT_SECONDS,
+    DEFAULT_SLASH_PERCENTAGE,
+    true,
+    false,
+    0,
+    false,
+    0,
+    GameMode.CashGame,
+    { commitment, depositAmount }
+  );
+  data.writeUInt8(Instruction.InitializeAndJoin, 0);
   return data;
 }
 
@@ -483,7 +605,7 @@ export async function initializeGame(
  *
  * Uses seed-based derivation:
  * - shuffleSeed: random 32-byte seed
- * - commitment: keccak256(shuffleSeed) - stored on-chain during JoinGame
+ * - commitment: keccak256(shuffleSeed || player) - stored on-chain during JoinGame
  * - shuffleVector: derived as v[i] = keccak256(seed || i) for shuffle/lock/reveal
  */
 export async function createPlayer(
@@ -505,9 +627,9 @@ export async function createPlayer(
   await mintTokens(payer, tokenMint, payer, tokenAccount, buyIn);
 
   // Generate shuffle seed and commitment using seed-based derivation
-  // commitment = keccak256(seed) - preserves hiding property
+  // commitment = keccak256(seed || player) - hides the seed, bound to this player
   const shuffleSeed = generateShuffleSeed();
-  const commitment = calculateSeedCommitment(shuffleSeed);
+  const commitment = calculateSeedCommitment(shuffleSeed, player.publicKey.toBytes());
 
   // Derive shuffle values for client-side operations (shuffle/lock/reveal)
   // v[i] = keccak256(seed || i) - same derivation as on-chain
@@ -740,8 +862,8 @@ export async function fetchPlayerState(playerState: PublicKey): Promise<{
   const holeCardsCount = data[offset];
   offset += 1;
 
-  // Skip revealed_cards (128 bytes) and revealed_cards_count (1)
-  offset += 129;
+  // Skip revealed_cards (2 x 32-byte compressed points) and revealed_cards_count (1)
+  offset += 65;
 
   const isFolded = data[offset] !== 0;
 
@@ -780,6 +902,7 @@ export async function fetchGameState(gameState: PublicKey): Promise<{
   currentCallAmount: bigint;
   cardToReveal: number;
   cardsLeftInDeck: number;
+  revealRound: number;
 }> {
   const connection = getConnection();
   const account = await connection.getAccountInfo(gameState);
@@ -844,6 +967,9 @@ export async function fetchGameState(gameState: PublicKey): Promise<{
 
   const cardsLeftInDeck = data[offset];
 
+  // reveal_round is the last field of the account
+  const revealRound = data.readUInt16LE(GAME_STATE_SIZE - 2);
+
   return {
     bump,
     gameId,
@@ -861,6 +987,7 @@ export async function fetchGameState(gameState: PublicKey): Promise<{
     currentCallAmount,
     cardToReveal,
     cardsLeftInDeck,
+    revealRound,
   };
 }
 
@@ -914,3 +1041,17 @@ export enum CommunityCardsState {
   TurnAwaiting = 2,
   RiverAwaiting = 3,
 }
+
+export enum CardStage {
+  Preflop = 0,
+  Flop1 = 1,
+  Flop2 = 2,
+  Flop3 = 3,
+  Turn = 4,
+  River = 5,
+}
+
+export enum GameMode {
+  CashGame = 0,
+  SitAndGo = 1,
+}
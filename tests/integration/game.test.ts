@@ -66,6 +66,7 @@ import {
   unlockCard,
   getCardName,
   calculateKeccak256Hash,
+  CURVE_ORDER,
 } from '../helpers/crypto';
 import {
   selectBestHand,
@@ -199,10 +200,10 @@ describe('Texas Hold\'em - Full Game Flow', () => {
 
         // Add to local accumulator
         for (let j = 0; j < DECK_SIZE; j++) {
-          // Add values (in a real implementation, would do proper modular addition)
+          // Modular addition in the BN254 scalar field, as on-chain
           const current = BigInt(accumulator[j]);
           const addition = BigInt(player.shuffleVector[j]);
-          accumulator[j] = '0x' + ((current + addition) % (2n ** 256n)).toString(16);
+          accumulator[j] = '0x' + ((current + addition) % CURVE_ORDER).toString(16);
         }
       }
 
@@ -641,7 +641,7 @@ export async function foldAction(
 			{ pubkey: accounts.gameConfig, isSigner: false, isWritable: false },
 			{ pubkey: accounts.gameState, isSigner: false, isWritable: true },
 			{ pubkey: playerState, isSigner: false, isWritable: true },
-			{ pubkey: accounts.playerList, isSigner: false, isWritable: false }
+			{ pubkey: accounts.playerList, isSigner: false, isWritable: true }
 		],
 		programId: PROGRAM_ID,
 		data: foldData
@@ -1154,7 +1154,7 @@ export async function placeBlindAction(
 			{ pubkey: accounts.gameConfig, isSigner: false, isWritable: false },
 			{ pubkey: accounts.gameState, isSigner: false, isWritable: true },
 			{ pubkey: playerState, isSigner: false, isWritable: true },
-			{ pubkey: accounts.playerList, isSigner: false, isWritable: false }
+			{ pubkey: accounts.playerList, isSigner: false, isWritable: true }
 		],
 		programId: PROGRAM_ID,
 		data: buildInstructionData(Instruction.PlaceBlind, data)
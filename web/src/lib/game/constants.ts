@@ -26,13 +26,13 @@ export const VAULT_SEED = new TextEncoder().encode('vault');
 export const PLAYER_LIST_SEED = new TextEncoder().encode('player_list');
 
 // Account sizes (matching Rust state structs)
-export const GAME_CONFIG_SIZE = 134;
-export const GAME_STATE_SIZE = 125;
-export const PLAYER_STATE_SIZE = 256;
+export const GAME_CONFIG_SIZE = 144;
+export const GAME_STATE_SIZE = 199;
+export const PLAYER_STATE_SIZE = 327;
 export const DECK_STATE_SIZE = 5025;
 export const ACCUMULATOR_STATE_SIZE = 5025;
-export const COMMUNITY_CARDS_SIZE = 360;
-export const PLAYER_LIST_SIZE = 227;
+export const COMMUNITY_CARDS_SIZE = 365;
+export const PLAYER_LIST_SIZE = 229;
 
 // Game constants
 export const MAX_PLAYERS = 6;
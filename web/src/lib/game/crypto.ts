@@ -136,7 +136,7 @@ export function deriveAllShuffleValues(seed: Uint8Array): string[] {
  */
 export function generateWorkDeck(accumulator: string[]): Bn254Point[] {
 	return accumulator.map((m) => {
-		const scalar = BigInt(m.toString().replace('0x', '0x') || '0');
+		const scalar = BigInt(m.toString() || '0');
 		// scalar mod n to ensure it's in valid range
 		const scalarMod = scalar % CURVE_ORDER;
 		return Bn254Point.fromGenerator().mul(scalarMod);
@@ -160,7 +160,7 @@ export function shuffleArray<T>(array: T[]): T[] {
  * This is the encryption step where each player multiplies by their secret key
  */
 export function encryptWorkDeck(workDeck: Bn254Point[], s: string): Bn254Point[] {
-	const scalar = BigInt(s.toString().replace('0x', '0x') || '0');
+	const scalar = BigInt(s.toString() || '0');
 	const scalarMod = scalar % CURVE_ORDER;
 
 	return workDeck.map((point) => {
@@ -176,7 +176,7 @@ export function lockWorkDeck(workDeck: Bn254Point[], lock: string[]): Bn254Point
 	const points: Bn254Point[] = [];
 
 	for (let i = 0; i < 52; i++) {
-		const lockBig = BigInt(lock[i].toString().replace('0x', '0x') || '0');
+		const lockBig = BigInt(lock[i].toString() || '0');
 		const lockMod = lockBig % CURVE_ORDER;
 		const lockedPoint = workDeck[i].mul(lockMod);
 
@@ -214,7 +214,7 @@ function modPow(base: bigint, exp: bigint, m: bigint): bigint {
  * what lockWorkDeck does when applying lock keys.
  */
 export function modInverse(a: string): string {
-	const aBig = BigInt(a.toString().replace('0x', '0x') || '0');
+	const aBig = BigInt(a.toString() || '0');
 	// First reduce mod n to match what lockWorkDeck does
 	const aReduced = aBig % CURVE_ORDER;
 	// inv = a^(n-2) mod n
@@ -233,7 +233,7 @@ export function unlockCard(lockedCard: PointTuple, keys: string[]): Bn254Point {
 	let card = Bn254Point.fromCoords(xBig, yBig);
 
 	for (const key of keys) {
-		const keyBig = BigInt(key.toString().replace('0x', '0x') || '0');
+		const keyBig = BigInt(key.toString() || '0');
 		const keyInv = modPow(keyBig, CURVE_ORDER - 2n, CURVE_ORDER);
 		card = card.mul(keyInv);
 	}